@@ -354,6 +354,21 @@ impl MongoDB {
         self.database.collection("conhecimentos_transporte")
     }
 
+    /// Collection para Manifestos de Documentos Fiscais
+    pub fn manifestos(&self) -> Collection<crate::models::ManifestoDocumentos> {
+        self.database.collection("manifestos")
+    }
+
+    /// Collection para Notas Fiscais de Serviço (NFS-e)
+    pub fn notas_servico(&self) -> Collection<crate::models::NotaFiscalServico> {
+        self.database.collection("notas_servico")
+    }
+
+    /// Collection para Eventos Fiscais (cancelamento, CC-e, manifestação...)
+    pub fn eventos_fiscais(&self) -> Collection<crate::models::EventoFiscal> {
+        self.database.collection("eventos_fiscais")
+    }
+
     /// Collection para Validações Fiscais
     pub fn validacoes_fiscais(&self) -> Collection<crate::validators::fiscal::ValidationResult> {
         self.database.collection("validacoes_fiscais")
@@ -371,6 +386,18 @@ impl MongoDB {
         Ok(result.inserted_id.as_object_id().unwrap())
     }
 
+    /// Insere um Manifesto de Documentos Fiscais
+    pub async fn insert_manifesto(&self, mdfe: &crate::models::ManifestoDocumentos) -> Result<ObjectId> {
+        let result = self.manifestos().insert_one(mdfe, None).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    /// Insere uma Nota Fiscal de Serviço
+    pub async fn insert_nota_servico(&self, nfse: &crate::models::NotaFiscalServico) -> Result<ObjectId> {
+        let result = self.notas_servico().insert_one(nfse, None).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
     /// Busca Nota Fiscal por chave de acesso
     pub async fn find_nota_fiscal_by_chave(&self, chave: &str) -> Result<Option<crate::models::NotaFiscal>> {
         let filter = doc! { "chave_acesso": chave };
@@ -383,6 +410,76 @@ impl MongoDB {
         Ok(self.conhecimentos_transporte().find_one(filter, None).await?)
     }
 
+    /// Busca os Conhecimentos de Transporte que transportam o documento com a chave informada
+    pub async fn find_conhecimentos_by_documento_referenciado(&self, chave: &str) -> Result<Vec<crate::models::ConhecimentoTransporte>> {
+        use futures::stream::TryStreamExt;
+        let filter = doc! { "documentos_referenciados.chave_acesso": chave };
+        let cursor = self.conhecimentos_transporte().find(filter, None).await?;
+        Ok(cursor.try_collect().await?)
+    }
+
+    /// Busca MDF-e por chave de acesso
+    pub async fn find_manifesto_by_chave(&self, chave: &str) -> Result<Option<crate::models::ManifestoDocumentos>> {
+        let filter = doc! { "chave_acesso": chave };
+        Ok(self.manifestos().find_one(filter, None).await?)
+    }
+
+    /// Busca os MDF-e que relacionam o CT-e ou NF-e com a chave informada
+    pub async fn find_manifestos_by_documento(&self, chave: &str) -> Result<Vec<crate::models::ManifestoDocumentos>> {
+        use futures::stream::TryStreamExt;
+        let filter = doc! {
+            "$or": [
+                { "municipios_descarregamento.chaves_cte": chave },
+                { "municipios_descarregamento.chaves_nfe": chave },
+            ]
+        };
+        let cursor = self.manifestos().find(filter, None).await?;
+        Ok(cursor.try_collect().await?)
+    }
+
+    /// Busca NFS-e pela chave (Padrão Nacional) ou identificador composto (ABRASF)
+    pub async fn find_nota_servico_by_chave(&self, chave: &str) -> Result<Option<crate::models::NotaFiscalServico>> {
+        let filter = doc! { "chave_acesso": chave };
+        Ok(self.notas_servico().find_one(filter, None).await?)
+    }
+
+    /// Insere um Evento Fiscal
+    pub async fn insert_evento(&self, evento: &crate::models::EventoFiscal) -> Result<ObjectId> {
+        let result = self.eventos_fiscais().insert_one(evento, None).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    /// Busca um evento pelo documento, código (`tpEvento`) e sequência
+    pub async fn find_evento(&self, chave: &str, codigo: &str, sequencia: u32) -> Result<Option<crate::models::EventoFiscal>> {
+        let filter = doc! { "chave_acesso": chave, "codigo": codigo, "sequencia": sequencia as i64 };
+        Ok(self.eventos_fiscais().find_one(filter, None).await?)
+    }
+
+    /// Lista os eventos de um documento em ordem cronológica
+    pub async fn find_eventos_by_chave(&self, chave: &str) -> Result<Vec<crate::models::EventoFiscal>> {
+        use futures::stream::TryStreamExt;
+        use mongodb::options::FindOptions;
+        let options = FindOptions::builder().sort(doc! { "data_evento": 1 }).build();
+        let cursor = self.eventos_fiscais().find(doc! { "chave_acesso": chave }, options).await?;
+        Ok(cursor.try_collect().await?)
+    }
+
+    /// Aplica o evento à NF-e ou ao CT-e referenciado; retorna `false` se o documento não existe
+    pub async fn aplicar_evento(&self, evento: &crate::models::EventoFiscal) -> Result<bool> {
+        let filter = doc! { "chave_acesso": &evento.chave_acesso };
+        if let Some(mut nf) = self.find_nota_fiscal_by_chave(&evento.chave_acesso).await? {
+            nf.aplicar_evento(evento);
+            self.notas_fiscais().replace_one(filter, &nf, None).await?;
+            return Ok(true);
+        }
+        if let Some(mut cte) = self.find_conhecimento_by_chave(&evento.chave_acesso).await? {
+            cte.aplicar_evento(evento);
+            self.conhecimentos_transporte().replace_one(filter, &cte, None).await?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     /// Insere resultado de validação fiscal
     pub async fn insert_validation(&self, validation: &crate::validators::fiscal::ValidationResult) -> Result<ObjectId> {
         let result = self.validacoes_fiscais().insert_one(validation, None).await?;
@@ -395,23 +492,24 @@ impl MongoDB {
         Ok(self.validacoes_fiscais().find_one(filter, None).await?)
     }
 
-    /// Lista documentos com paginação
+    /// Lista documentos com paginação; cancelados ficam de fora, como em `count_documents`
     pub async fn list_documents(&self, doc_type: Option<&str>, limit: i64, offset: i64) -> Result<Vec<crate::database::repository::DocumentSummary>> {
         let mut results = Vec::new();
 
-        // Buscar NFes se não for especificado tipo ou se for "NFe"
-        if doc_type.is_none() || doc_type == Some("NFe") {
+        // Buscar NFes/NFCes se não for especificado tipo ou se for "NFe"/"NFCe"
+        if let Some(mut filter) = Self::filtro_modelo_nota(doc_type) {
+            filter.insert("status", doc! { "$ne": "Cancelled" });
             use mongodb::options::FindOptions;
             let options = FindOptions::builder()
                 .skip(offset as u64)
                 .limit(limit)
                 .build();
             
-            let mut cursor = self.notas_fiscais().find(doc! {}, options).await?;
+            let mut cursor = self.notas_fiscais().find(filter, options).await?;
             while cursor.advance().await? {
                 let nfe = cursor.deserialize_current()?;
                 results.push(crate::database::repository::DocumentSummary {
-                    document_type: "NFe".to_string(),
+                    document_type: nfe.document_type().codigo().to_string(),
                     chave_acesso: nfe.chave_acesso,
                     numero: nfe.numero,
                     serie: nfe.serie,
@@ -423,19 +521,23 @@ impl MongoDB {
             }
         }
 
-        // Buscar CTes se não for especificado tipo ou se for "CTe"
-        if doc_type.is_none() || doc_type == Some("CTe") {
+        // Buscar CTes (inclusive CT-e OS e Simplificado) conforme o tipo pedido
+        if let (Some(mut filter), Some(restante)) = (
+            Self::filtro_tipo_conhecimento(doc_type),
+            Self::limite_restante(doc_type, limit, results.len()),
+        ) {
+            filter.insert("status", doc! { "$ne": "Cancelled" });
             use mongodb::options::FindOptions;
             let options = FindOptions::builder()
                 .skip(if doc_type.is_some() { offset } else { 0 } as u64)
-                .limit(if doc_type.is_some() { limit } else { limit - results.len() as i64 })
+                .limit(restante)
                 .build();
             
-            let mut cursor = self.conhecimentos_transporte().find(doc! {}, options).await?;
+            let mut cursor = self.conhecimentos_transporte().find(filter, options).await?;
             while cursor.advance().await? {
                 let cte = cursor.deserialize_current()?;
                 results.push(crate::database::repository::DocumentSummary {
-                    document_type: "CTe".to_string(),
+                    document_type: cte.document_type().codigo().to_string(),
                     chave_acesso: cte.chave_acesso,
                     numero: cte.numero,
                     serie: cte.serie,
@@ -447,23 +549,142 @@ impl MongoDB {
             }
         }
 
+        // Buscar MDF-e se não for especificado tipo ou se for "MDFe"
+        let restante = Self::limite_restante(doc_type, limit, results.len())
+            .filter(|_| doc_type.is_none() || doc_type == Some("MDFe"));
+        if let Some(restante) = restante {
+            use mongodb::options::FindOptions;
+            let options = FindOptions::builder()
+                .skip(if doc_type.is_some() { offset } else { 0 } as u64)
+                .limit(restante)
+                .build();
+
+            let mut cursor = self.manifestos().find(doc! {}, options).await?;
+            while cursor.advance().await? {
+                let mdfe = cursor.deserialize_current()?;
+                results.push(crate::database::repository::DocumentSummary {
+                    document_type: "MDFe".to_string(),
+                    chave_acesso: mdfe.chave_acesso,
+                    numero: mdfe.numero,
+                    serie: mdfe.serie,
+                    data_emissao: mdfe.data_emissao.to_rfc3339(),
+                    emitente: mdfe.emitente.razao_social,
+                    destinatario: String::new(),
+                    valor_total: mdfe.totais.valor_carga,
+                });
+            }
+        }
+
+        // Buscar NFS-e se não for especificado tipo ou se for "NFSe"
+        let restante = Self::limite_restante(doc_type, limit, results.len())
+            .filter(|_| doc_type.is_none() || doc_type == Some("NFSe"));
+        if let Some(restante) = restante {
+            use mongodb::options::FindOptions;
+            let options = FindOptions::builder()
+                .skip(if doc_type.is_some() { offset } else { 0 } as u64)
+                .limit(restante)
+                .build();
+
+            let mut cursor = self.notas_servico().find(doc! { "status": { "$ne": "Cancelled" } }, options).await?;
+            while cursor.advance().await? {
+                let nfse = cursor.deserialize_current()?;
+                results.push(crate::database::repository::DocumentSummary {
+                    document_type: "NFSe".to_string(),
+                    chave_acesso: nfse.chave_acesso,
+                    numero: nfse.numero,
+                    serie: nfse.serie_rps.unwrap_or_default(),
+                    data_emissao: nfse.data_emissao.to_rfc3339(),
+                    emitente: nfse.prestador.razao_social,
+                    destinatario: nfse.tomador.map(|t| t.razao_social).unwrap_or_default(),
+                    valor_total: nfse.valores.valor_servicos,
+                });
+            }
+        }
+
         Ok(results)
     }
 
-    /// Conta total de documentos
+    /// Conta total de documentos ativos (NF-e, CT-e e NFS-e cancelados ficam de fora)
     pub async fn count_documents(&self, doc_type: Option<&str>) -> Result<i64> {
         let mut total = 0i64;
 
-        if doc_type.is_none() || doc_type == Some("NFe") {
-            total += self.notas_fiscais().count_documents(doc! {}, None).await? as i64;
+        if let Some(mut filter) = Self::filtro_modelo_nota(doc_type) {
+            filter.insert("status", doc! { "$ne": "Cancelled" });
+            total += self.notas_fiscais().count_documents(filter, None).await? as i64;
+        }
+        if let Some(mut filter) = Self::filtro_tipo_conhecimento(doc_type) {
+            filter.insert("status", doc! { "$ne": "Cancelled" });
+            total += self.conhecimentos_transporte().count_documents(filter, None).await? as i64;
+        }
+        if doc_type.is_none() || doc_type == Some("MDFe") {
+            total += self.manifestos().count_documents(doc! {}, None).await? as i64;
         }
-        if doc_type.is_none() || doc_type == Some("CTe") {
-            total += self.conhecimentos_transporte().count_documents(doc! {}, None).await? as i64;
+        if doc_type.is_none() || doc_type == Some("NFSe") {
+            let filter = doc! { "status": { "$ne": "Cancelled" } };
+            total += self.notas_servico().count_documents(filter, None).await? as i64;
         }
 
         Ok(total)
     }
+
+    /// Conta NF-e e CT-e cancelados por evento e NFS-e canceladas
+    pub async fn count_cancelados(&self) -> Result<i64> {
+        let filter = doc! { "status": "Cancelled" };
+        let notas = self.notas_fiscais().count_documents(filter.clone(), None).await?;
+        let ctes = self.conhecimentos_transporte().count_documents(filter.clone(), None).await?;
+        let nfses = self.notas_servico().count_documents(filter, None).await?;
+        Ok((notas + ctes + nfses) as i64)
+    }
+
+    /// Documentos que ainda cabem na página; `None` quando já está cheia, pois o driver trata
+    /// `limit(0)` como sem limite
+    fn limite_restante(doc_type: Option<&str>, limit: i64, listados: usize) -> Option<i64> {
+        let restante = if doc_type.is_some() {
+            limit
+        } else {
+            limit - listados as i64
+        };
+        (restante > 0).then_some(restante)
+    }
+
+    /// Filtro da coleção de notas por modelo; documentos antigos sem `modelo` contam como NF-e
+    fn filtro_modelo_nota(doc_type: Option<&str>) -> Option<Document> {
+        match doc_type {
+            None => Some(doc! {}),
+            Some("NFe") => Some(doc! { "modelo": { "$ne": "NFCe" } }),
+            Some("NFCe") => Some(doc! { "modelo": "NFCe" }),
+            Some(_) => None,
+        }
+    }
+
+    /// Filtro da coleção de CT-e por modelo (57/67) e `tipo_cte`; documentos antigos contam como CT-e
+    fn filtro_tipo_conhecimento(doc_type: Option<&str>) -> Option<Document> {
+        match doc_type {
+            None => Some(doc! {}),
+            Some("CTe") => Some(doc! {
+                "modelo": { "$ne": "CTeOS" },
+                "tipo_cte": { "$nin": ["Simplificado", "SubstituicaoSimplificado"] },
+            }),
+            Some("CTeOS") => Some(doc! { "modelo": "CTeOS" }),
+            Some("CTeSimp") => Some(doc! {
+                "tipo_cte": { "$in": ["Simplificado", "SubstituicaoSimplificado"] },
+            }),
+            Some(_) => None,
+        }
+    }
 }
 
 /// Wrapper Arc para compartilhamento entre threads
 pub type MongoDBConnection = Arc<MongoDB>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limite_restante() {
+        assert_eq!(MongoDB::limite_restante(None, 50, 20), Some(30));
+        assert_eq!(MongoDB::limite_restante(None, 50, 50), None);
+        assert_eq!(MongoDB::limite_restante(Some("MDFe"), 50, 0), Some(50));
+    }
+}
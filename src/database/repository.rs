@@ -1,6 +1,10 @@
 use crate::database::DatabasePool;
 use crate::error::Result;
-use crate::models::{ConhecimentoTransporte, DocumentType, NotaFiscal};
+use crate::models::{
+    ConhecimentoTransporte, DadosModal, DocumentType, EventoFiscal, ManifestoDocumentos,
+    ModeloConhecimento, ModeloNotaFiscal, NotaFiscal, NotaFiscalServico, ProcessingStatus,
+    TipoCTe, TipoEvento,
+};
 use crate::validators::ValidationResult;
 use chrono::Utc;
 use sqlx::Row;
@@ -16,6 +20,8 @@ impl Repository {
 
     /// Insere uma Nota Fiscal no banco de dados
     pub async fn insert_nota_fiscal(&self, nf: &NotaFiscal) -> Result<()> {
+        let transportadora = nf.transporte.transportadora.as_ref();
+        let suplementares = nf.informacoes_suplementares.as_ref();
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
                 sqlx::query(
@@ -28,12 +34,21 @@ impl Repository {
                         dest_cnpj_cpf, dest_razao_social,
                         dest_logradouro, dest_numero, dest_bairro, dest_municipio, dest_uf, dest_cep,
                         valor_produtos, valor_total, valor_icms, valor_ipi, valor_pis, valor_cofins,
+                        base_calculo_icms, valor_icms_desonerado, valor_fcp,
+                        base_calculo_icms_st, valor_icms_st, valor_frete, valor_seguro,
+                        valor_desconto, valor_ii, outras_despesas, valor_total_tributos,
+                        modalidade_frete, transp_cnpj_cpf, transp_razao_social, veiculo_placa,
+                        peso_liquido, peso_bruto, protocolo_cstat, data_autorizacao,
+                        modelo, qr_code, url_chave,
                         informacoes_adicionais
                     ) VALUES (
                         ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9,
                         ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
                         ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26,
-                        ?27, ?28, ?29, ?30, ?31, ?32, ?33
+                        ?27, ?28, ?29, ?30, ?31, ?32,
+                        ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43,
+                        ?44, ?45, ?46, ?47, ?48, ?49, ?50, ?51,
+                        ?52, ?53, ?54, ?55
                     )
                     "#,
                 )
@@ -69,6 +84,28 @@ impl Repository {
                 .bind(nf.totais.valor_ipi)
                 .bind(nf.totais.valor_pis)
                 .bind(nf.totais.valor_cofins)
+                .bind(nf.totais.base_calculo_icms)
+                .bind(nf.totais.valor_icms_desonerado)
+                .bind(nf.totais.valor_fcp)
+                .bind(nf.totais.base_calculo_icms_st)
+                .bind(nf.totais.valor_icms_st)
+                .bind(nf.totais.valor_frete)
+                .bind(nf.totais.valor_seguro)
+                .bind(nf.totais.valor_desconto)
+                .bind(nf.totais.valor_ii)
+                .bind(nf.totais.outras_despesas)
+                .bind(nf.totais.valor_total_tributos)
+                .bind(format!("{:?}", nf.transporte.modalidade_frete))
+                .bind(transportadora.map(|t| &t.cnpj_cpf))
+                .bind(transportadora.map(|t| &t.razao_social))
+                .bind(nf.transporte.veiculo.as_ref().map(|v| &v.placa))
+                .bind(nf.transporte.peso_liquido_total())
+                .bind(nf.transporte.peso_bruto_total())
+                .bind(nf.protocolo.as_ref().map(|p| &p.codigo_status))
+                .bind(nf.protocolo.as_ref().and_then(|p| p.data_recebimento))
+                .bind(nf.modelo.codigo())
+                .bind(suplementares.map(|i| &i.qr_code))
+                .bind(suplementares.map(|i| &i.url_chave))
                 .bind(&nf.informacoes_adicionais)
                 .execute(pool)
                 .await?;
@@ -84,12 +121,21 @@ impl Repository {
                         dest_cnpj_cpf, dest_razao_social,
                         dest_logradouro, dest_numero, dest_bairro, dest_municipio, dest_uf, dest_cep,
                         valor_produtos, valor_total, valor_icms, valor_ipi, valor_pis, valor_cofins,
+                        base_calculo_icms, valor_icms_desonerado, valor_fcp,
+                        base_calculo_icms_st, valor_icms_st, valor_frete, valor_seguro,
+                        valor_desconto, valor_ii, outras_despesas, valor_total_tributos,
+                        modalidade_frete, transp_cnpj_cpf, transp_razao_social, veiculo_placa,
+                        peso_liquido, peso_bruto, protocolo_cstat, data_autorizacao,
+                        modelo, qr_code, url_chave,
                         informacoes_adicionais
                     ) VALUES (
                         $1, $2, $3, $4, $5, $6, $7, $8, $9,
                         $10, $11, $12, $13, $14, $15, $16, $17, $18,
                         $19, $20, $21, $22, $23, $24, $25, $26,
-                        $27, $28, $29, $30, $31, $32, $33
+                        $27, $28, $29, $30, $31, $32,
+                        $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43,
+                        $44, $45, $46, $47, $48, $49, $50, $51,
+                        $52, $53, $54, $55
                     )
                     "#,
                 )
@@ -125,11 +171,238 @@ impl Repository {
                 .bind(nf.totais.valor_ipi)
                 .bind(nf.totais.valor_pis)
                 .bind(nf.totais.valor_cofins)
+                .bind(nf.totais.base_calculo_icms)
+                .bind(nf.totais.valor_icms_desonerado)
+                .bind(nf.totais.valor_fcp)
+                .bind(nf.totais.base_calculo_icms_st)
+                .bind(nf.totais.valor_icms_st)
+                .bind(nf.totais.valor_frete)
+                .bind(nf.totais.valor_seguro)
+                .bind(nf.totais.valor_desconto)
+                .bind(nf.totais.valor_ii)
+                .bind(nf.totais.outras_despesas)
+                .bind(nf.totais.valor_total_tributos)
+                .bind(format!("{:?}", nf.transporte.modalidade_frete))
+                .bind(transportadora.map(|t| &t.cnpj_cpf))
+                .bind(transportadora.map(|t| &t.razao_social))
+                .bind(nf.transporte.veiculo.as_ref().map(|v| &v.placa))
+                .bind(nf.transporte.peso_liquido_total())
+                .bind(nf.transporte.peso_bruto_total())
+                .bind(nf.protocolo.as_ref().map(|p| &p.codigo_status))
+                .bind(nf.protocolo.as_ref().and_then(|p| p.data_recebimento))
+                .bind(nf.modelo.codigo())
+                .bind(suplementares.map(|i| &i.qr_code))
+                .bind(suplementares.map(|i| &i.url_chave))
                 .bind(&nf.informacoes_adicionais)
                 .execute(pool)
                 .await?;
             }
         }
+        self.insert_itens_nota_fiscal(nf).await?;
+        self.insert_pagamentos_nota(nf).await?;
+        self.insert_duplicatas(nf).await?;
+        self.reaplicar_eventos("notas_fiscais", &nf.chave_acesso).await?;
+        Ok(())
+    }
+
+    /// Insere as formas de pagamento (pag/detPag) de uma Nota Fiscal
+    async fn insert_pagamentos_nota(&self, nf: &NotaFiscal) -> Result<()> {
+        for forma in &nf.pagamento.formas {
+            let cartao = forma.cartao.as_ref();
+            match &self.pool {
+                DatabasePool::Sqlite(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO pagamentos_nota (
+                            nota_fiscal_id, meio_pagamento, descricao, valor, a_prazo,
+                            cartao_cnpj, cartao_bandeira, cartao_autorizacao
+                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                        "#,
+                    )
+                    .bind(nf.id.to_string())
+                    .bind(format!("{:?}", forma.meio))
+                    .bind(&forma.descricao)
+                    .bind(forma.valor)
+                    .bind(forma.a_prazo)
+                    .bind(cartao.and_then(|c| c.cnpj_credenciadora.as_ref()))
+                    .bind(cartao.and_then(|c| c.bandeira.as_ref()))
+                    .bind(cartao.and_then(|c| c.autorizacao.as_ref()))
+                    .execute(pool)
+                    .await?;
+                }
+                DatabasePool::Postgres(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO pagamentos_nota (
+                            nota_fiscal_id, meio_pagamento, descricao, valor, a_prazo,
+                            cartao_cnpj, cartao_bandeira, cartao_autorizacao
+                        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                        "#,
+                    )
+                    .bind(nf.id)
+                    .bind(format!("{:?}", forma.meio))
+                    .bind(&forma.descricao)
+                    .bind(forma.valor)
+                    .bind(forma.a_prazo)
+                    .bind(cartao.and_then(|c| c.cnpj_credenciadora.as_ref()))
+                    .bind(cartao.and_then(|c| c.bandeira.as_ref()))
+                    .bind(cartao.and_then(|c| c.autorizacao.as_ref()))
+                    .execute(pool)
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Insere as duplicatas (cobr/dup) de uma Nota Fiscal
+    async fn insert_duplicatas(&self, nf: &NotaFiscal) -> Result<()> {
+        let numero_fatura = nf.cobranca.fatura.as_ref().and_then(|f| f.numero.as_ref());
+        for duplicata in &nf.cobranca.duplicatas {
+            match &self.pool {
+                DatabasePool::Sqlite(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO duplicatas (nota_fiscal_id, numero_fatura, numero, vencimento, valor)
+                        VALUES (?1, ?2, ?3, ?4, ?5)
+                        "#,
+                    )
+                    .bind(nf.id.to_string())
+                    .bind(numero_fatura)
+                    .bind(&duplicata.numero)
+                    .bind(duplicata.vencimento)
+                    .bind(duplicata.valor)
+                    .execute(pool)
+                    .await?;
+                }
+                DatabasePool::Postgres(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO duplicatas (nota_fiscal_id, numero_fatura, numero, vencimento, valor)
+                        VALUES ($1, $2, $3, $4, $5)
+                        "#,
+                    )
+                    .bind(nf.id)
+                    .bind(numero_fatura)
+                    .bind(&duplicata.numero)
+                    .bind(duplicata.vencimento)
+                    .bind(duplicata.valor)
+                    .execute(pool)
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Insere os itens (det) de uma Nota Fiscal
+    async fn insert_itens_nota_fiscal(&self, nf: &NotaFiscal) -> Result<()> {
+        for item in &nf.itens {
+            let impostos = &item.impostos;
+            let icms = impostos.icms.as_ref();
+            let ipi = impostos.ipi.as_ref();
+            let pis = impostos.pis.as_ref();
+            let cofins = impostos.cofins.as_ref();
+            let impostos_json = serde_json::to_string(impostos)?;
+            match &self.pool {
+                DatabasePool::Sqlite(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO itens_nota_fiscal (
+                            nota_fiscal_id, numero_item, codigo_produto, descricao, ncm, cfop,
+                            unidade_comercial, quantidade_comercial, valor_unitario, valor_total,
+                            ean, informacoes_adicionais,
+                            icms_cst, icms_base_calculo, icms_aliquota, icms_valor,
+                            icms_st_base_calculo, icms_st_valor, icms_fcp_valor,
+                            ipi_cst, ipi_aliquota, ipi_valor, pis_cst, pis_valor,
+                            cofins_cst, cofins_valor, impostos_json
+                        ) VALUES (
+                            ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12,
+                            ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24,
+                            ?25, ?26, ?27
+                        )
+                        "#,
+                    )
+                    .bind(nf.id.to_string())
+                    .bind(item.numero_item)
+                    .bind(&item.codigo_produto)
+                    .bind(&item.descricao)
+                    .bind(&item.ncm)
+                    .bind(&item.cfop)
+                    .bind(&item.unidade_comercial)
+                    .bind(item.quantidade_comercial)
+                    .bind(item.valor_unitario)
+                    .bind(item.valor_total)
+                    .bind(&item.ean)
+                    .bind(&item.informacoes_adicionais)
+                    .bind(icms.map(|i| i.situacao_tributaria().to_string()))
+                    .bind(icms.map(|i| i.base_calculo))
+                    .bind(icms.map(|i| i.aliquota))
+                    .bind(icms.map(|i| i.valor))
+                    .bind(icms.map(|i| i.base_calculo_st))
+                    .bind(icms.map(|i| i.valor_st))
+                    .bind(icms.map(|i| i.valor_fcp))
+                    .bind(ipi.map(|i| i.cst.clone()))
+                    .bind(ipi.map(|i| i.aliquota))
+                    .bind(ipi.map(|i| i.valor))
+                    .bind(pis.map(|p| p.cst.clone()))
+                    .bind(pis.map(|p| p.valor))
+                    .bind(cofins.map(|c| c.cst.clone()))
+                    .bind(cofins.map(|c| c.valor))
+                    .bind(&impostos_json)
+                    .execute(pool)
+                    .await?;
+                }
+                DatabasePool::Postgres(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO itens_nota_fiscal (
+                            nota_fiscal_id, numero_item, codigo_produto, descricao, ncm, cfop,
+                            unidade_comercial, quantidade_comercial, valor_unitario, valor_total,
+                            ean, informacoes_adicionais,
+                            icms_cst, icms_base_calculo, icms_aliquota, icms_valor,
+                            icms_st_base_calculo, icms_st_valor, icms_fcp_valor,
+                            ipi_cst, ipi_aliquota, ipi_valor, pis_cst, pis_valor,
+                            cofins_cst, cofins_valor, impostos_json
+                        ) VALUES (
+                            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12,
+                            $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24,
+                            $25, $26, $27
+                        )
+                        "#,
+                    )
+                    .bind(nf.id)
+                    .bind(item.numero_item)
+                    .bind(&item.codigo_produto)
+                    .bind(&item.descricao)
+                    .bind(&item.ncm)
+                    .bind(&item.cfop)
+                    .bind(&item.unidade_comercial)
+                    .bind(item.quantidade_comercial)
+                    .bind(item.valor_unitario)
+                    .bind(item.valor_total)
+                    .bind(&item.ean)
+                    .bind(&item.informacoes_adicionais)
+                    .bind(icms.map(|i| i.situacao_tributaria().to_string()))
+                    .bind(icms.map(|i| i.base_calculo))
+                    .bind(icms.map(|i| i.aliquota))
+                    .bind(icms.map(|i| i.valor))
+                    .bind(icms.map(|i| i.base_calculo_st))
+                    .bind(icms.map(|i| i.valor_st))
+                    .bind(icms.map(|i| i.valor_fcp))
+                    .bind(ipi.map(|i| i.cst.clone()))
+                    .bind(ipi.map(|i| i.aliquota))
+                    .bind(ipi.map(|i| i.valor))
+                    .bind(pis.map(|p| p.cst.clone()))
+                    .bind(pis.map(|p| p.valor))
+                    .bind(cofins.map(|c| c.cst.clone()))
+                    .bind(cofins.map(|c| c.valor))
+                    .bind(&impostos_json)
+                    .execute(pool)
+                    .await?;
+                }
+            }
+        }
         Ok(())
     }
 
@@ -157,6 +430,25 @@ impl Repository {
 
     /// Insere um Conhecimento de Transporte no banco de dados
     pub async fn insert_cte(&self, cte: &ConhecimentoTransporte) -> Result<()> {
+        let expedidor = cte.expedidor.as_ref();
+        let recebedor = cte.recebedor.as_ref();
+        let tomador = cte.tomador.as_ref();
+        let rntrc = match &cte.dados_modal {
+            Some(DadosModal::Rodoviario(rodo)) => Some(rodo.rntrc.as_str()),
+            _ => None,
+        };
+        let icms = cte.impostos.icms.as_ref();
+        let impostos_json = serde_json::to_string(&cte.impostos)?;
+        let dados_modal_json = cte
+            .dados_modal
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let entregas_json = if cte.entregas.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&cte.entregas)?)
+        };
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
                 sqlx::query(
@@ -167,12 +459,24 @@ impl Repository {
                         emit_cnpj_cpf, emit_razao_social, emit_uf,
                         rem_cnpj_cpf, rem_razao_social, rem_municipio, rem_uf,
                         dest_cnpj_cpf, dest_razao_social, dest_municipio, dest_uf,
-                        valor_total, valor_receber, valor_carga, produto_predominante, peso_bruto,
-                        informacoes_adicionais
+                        exped_cnpj_cpf, exped_razao_social, exped_municipio, exped_uf,
+                        receb_cnpj_cpf, receb_razao_social, receb_municipio, receb_uf,
+                        toma_tipo, toma_cnpj_cpf, toma_razao_social, toma_municipio, toma_uf,
+                        valor_total, valor_receber, icms_cst, icms_base_calculo, icms_aliquota, icms_valor,
+                        valor_total_tributos, impostos_json,
+                        valor_carga, produto_predominante, peso_bruto,
+                        peso_cubado, volume_m3, quantidade_volumes,
+                        rntrc, dados_modal_json, protocolo_cstat, data_autorizacao,
+                        informacoes_adicionais,
+                        modelo, tipo_cte, descricao_servico, entregas_json
                     ) VALUES (
                         ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
                         ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21,
-                        ?22, ?23, ?24, ?25, ?26, ?27
+                        ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29,
+                        ?30, ?31, ?32, ?33, ?34,
+                        ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42,
+                        ?43, ?44, ?45, ?46, ?47, ?48, ?49, ?50, ?51, ?52, ?53,
+                        ?54, ?55, ?56, ?57
                     )
                     "#,
                 )
@@ -197,12 +501,42 @@ impl Repository {
                 .bind(&cte.destinatario.razao_social)
                 .bind(&cte.destinatario.endereco.municipio)
                 .bind(&cte.destinatario.endereco.uf)
+                .bind(expedidor.map(|p| &p.cnpj_cpf))
+                .bind(expedidor.map(|p| &p.razao_social))
+                .bind(expedidor.map(|p| &p.endereco.municipio))
+                .bind(expedidor.map(|p| &p.endereco.uf))
+                .bind(recebedor.map(|p| &p.cnpj_cpf))
+                .bind(recebedor.map(|p| &p.razao_social))
+                .bind(recebedor.map(|p| &p.endereco.municipio))
+                .bind(recebedor.map(|p| &p.endereco.uf))
+                .bind(tomador.map(|t| format!("{:?}", t.tipo)))
+                .bind(tomador.map(|t| &t.participante.cnpj_cpf))
+                .bind(tomador.map(|t| &t.participante.razao_social))
+                .bind(tomador.map(|t| &t.participante.endereco.municipio))
+                .bind(tomador.map(|t| &t.participante.endereco.uf))
                 .bind(cte.valores_prestacao.valor_total)
                 .bind(cte.valores_prestacao.valor_receber)
+                .bind(icms.map(|i| &i.cst))
+                .bind(icms.map(|i| i.base_calculo))
+                .bind(icms.map(|i| i.aliquota))
+                .bind(icms.map(|i| i.valor))
+                .bind(cte.impostos.valor_total_tributos)
+                .bind(&impostos_json)
                 .bind(cte.informacoes_carga.valor_carga)
                 .bind(&cte.informacoes_carga.produto_predominante)
                 .bind(cte.informacoes_carga.peso_bruto)
+                .bind(cte.informacoes_carga.peso_cubado)
+                .bind(cte.informacoes_carga.volume_m3)
+                .bind(cte.informacoes_carga.quantidade_volumes)
+                .bind(rntrc)
+                .bind(&dados_modal_json)
+                .bind(cte.protocolo.as_ref().map(|p| &p.codigo_status))
+                .bind(cte.protocolo.as_ref().and_then(|p| p.data_recebimento))
                 .bind(&cte.informacoes_adicionais)
+                .bind(cte.modelo.codigo())
+                .bind(cte.tipo_cte.codigo())
+                .bind(cte.servico.as_ref().map(|s| &s.descricao))
+                .bind(&entregas_json)
                 .execute(pool)
                 .await?;
             }
@@ -215,12 +549,24 @@ impl Repository {
                         emit_cnpj_cpf, emit_razao_social, emit_uf,
                         rem_cnpj_cpf, rem_razao_social, rem_municipio, rem_uf,
                         dest_cnpj_cpf, dest_razao_social, dest_municipio, dest_uf,
-                        valor_total, valor_receber, valor_carga, produto_predominante, peso_bruto,
-                        informacoes_adicionais
+                        exped_cnpj_cpf, exped_razao_social, exped_municipio, exped_uf,
+                        receb_cnpj_cpf, receb_razao_social, receb_municipio, receb_uf,
+                        toma_tipo, toma_cnpj_cpf, toma_razao_social, toma_municipio, toma_uf,
+                        valor_total, valor_receber, icms_cst, icms_base_calculo, icms_aliquota, icms_valor,
+                        valor_total_tributos, impostos_json,
+                        valor_carga, produto_predominante, peso_bruto,
+                        peso_cubado, volume_m3, quantidade_volumes,
+                        rntrc, dados_modal_json, protocolo_cstat, data_autorizacao,
+                        informacoes_adicionais,
+                        modelo, tipo_cte, descricao_servico, entregas_json
                     ) VALUES (
                         $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
                         $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21,
-                        $22, $23, $24, $25, $26, $27
+                        $22, $23, $24, $25, $26, $27, $28, $29,
+                        $30, $31, $32, $33, $34,
+                        $35, $36, $37, $38, $39, $40, $41, $42,
+                        $43, $44, $45, $46, $47, $48, $49, $50, $51, $52, $53,
+                        $54, $55, $56, $57
                     )
                     "#,
                 )
@@ -245,19 +591,417 @@ impl Repository {
                 .bind(&cte.destinatario.razao_social)
                 .bind(&cte.destinatario.endereco.municipio)
                 .bind(&cte.destinatario.endereco.uf)
+                .bind(expedidor.map(|p| &p.cnpj_cpf))
+                .bind(expedidor.map(|p| &p.razao_social))
+                .bind(expedidor.map(|p| &p.endereco.municipio))
+                .bind(expedidor.map(|p| &p.endereco.uf))
+                .bind(recebedor.map(|p| &p.cnpj_cpf))
+                .bind(recebedor.map(|p| &p.razao_social))
+                .bind(recebedor.map(|p| &p.endereco.municipio))
+                .bind(recebedor.map(|p| &p.endereco.uf))
+                .bind(tomador.map(|t| format!("{:?}", t.tipo)))
+                .bind(tomador.map(|t| &t.participante.cnpj_cpf))
+                .bind(tomador.map(|t| &t.participante.razao_social))
+                .bind(tomador.map(|t| &t.participante.endereco.municipio))
+                .bind(tomador.map(|t| &t.participante.endereco.uf))
                 .bind(cte.valores_prestacao.valor_total)
                 .bind(cte.valores_prestacao.valor_receber)
+                .bind(icms.map(|i| &i.cst))
+                .bind(icms.map(|i| i.base_calculo))
+                .bind(icms.map(|i| i.aliquota))
+                .bind(icms.map(|i| i.valor))
+                .bind(cte.impostos.valor_total_tributos)
+                .bind(&impostos_json)
                 .bind(cte.informacoes_carga.valor_carga)
                 .bind(&cte.informacoes_carga.produto_predominante)
                 .bind(cte.informacoes_carga.peso_bruto)
+                .bind(cte.informacoes_carga.peso_cubado)
+                .bind(cte.informacoes_carga.volume_m3)
+                .bind(cte.informacoes_carga.quantidade_volumes)
+                .bind(rntrc)
+                .bind(&dados_modal_json)
+                .bind(cte.protocolo.as_ref().map(|p| &p.codigo_status))
+                .bind(cte.protocolo.as_ref().and_then(|p| p.data_recebimento))
                 .bind(&cte.informacoes_adicionais)
+                .bind(cte.modelo.codigo())
+                .bind(cte.tipo_cte.codigo())
+                .bind(cte.servico.as_ref().map(|s| &s.descricao))
+                .bind(&entregas_json)
+                .execute(pool)
+                .await?;
+            }
+        }
+        self.insert_componentes_prestacao(cte).await?;
+        self.insert_documentos_referenciados(cte).await?;
+        self.reaplicar_eventos("conhecimentos_transporte", &cte.chave_acesso).await?;
+        Ok(())
+    }
+
+    /// Insere os componentes do valor da prestação (vPrest/Comp) de um CT-e
+    async fn insert_componentes_prestacao(&self, cte: &ConhecimentoTransporte) -> Result<()> {
+        for componente in &cte.valores_prestacao.componentes {
+            match &self.pool {
+                DatabasePool::Sqlite(pool) => {
+                    sqlx::query(
+                        "INSERT INTO componentes_prestacao (cte_id, nome, valor) VALUES (?1, ?2, ?3)",
+                    )
+                    .bind(cte.id.to_string())
+                    .bind(&componente.nome)
+                    .bind(componente.valor)
+                    .execute(pool)
+                    .await?;
+                }
+                DatabasePool::Postgres(pool) => {
+                    sqlx::query(
+                        "INSERT INTO componentes_prestacao (cte_id, nome, valor) VALUES ($1, $2, $3)",
+                    )
+                    .bind(cte.id)
+                    .bind(&componente.nome)
+                    .bind(componente.valor)
+                    .execute(pool)
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Insere os documentos transportados (infDoc) de um CT-e
+    async fn insert_documentos_referenciados(&self, cte: &ConhecimentoTransporte) -> Result<()> {
+        for doc in &cte.documentos_referenciados {
+            match &self.pool {
+                DatabasePool::Sqlite(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO documentos_referenciados (cte_id, tipo, chave_acesso, numero, serie)
+                        VALUES (?1, ?2, ?3, ?4, ?5)
+                        "#,
+                    )
+                    .bind(cte.id.to_string())
+                    .bind(format!("{:?}", doc.tipo))
+                    .bind(&doc.chave_acesso)
+                    .bind(&doc.numero)
+                    .bind(&doc.serie)
+                    .execute(pool)
+                    .await?;
+                }
+                DatabasePool::Postgres(pool) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO documentos_referenciados (cte_id, tipo, chave_acesso, numero, serie)
+                        VALUES ($1, $2, $3, $4, $5)
+                        "#,
+                    )
+                    .bind(cte.id)
+                    .bind(format!("{:?}", doc.tipo))
+                    .bind(&doc.chave_acesso)
+                    .bind(&doc.numero)
+                    .bind(&doc.serie)
+                    .execute(pool)
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Insere um MDF-e e os documentos vinculados a cada município de descarregamento
+    pub async fn insert_mdfe(&self, mdfe: &ManifestoDocumentos) -> Result<()> {
+        let percurso = mdfe.percurso_ufs.join(",");
+        let veiculos: Vec<_> = mdfe
+            .veiculo_tracao
+            .iter()
+            .chain(mdfe.veiculos_reboque.iter())
+            .collect();
+        let veiculos_json = serde_json::to_string(&veiculos)?;
+        let condutores_json = serde_json::to_string(&mdfe.condutores)?;
+        let placa_tracao = mdfe.veiculo_tracao.as_ref().map(|v| &v.placa);
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO manifestos (
+                        id, chave_acesso, numero, serie, data_emissao, data_inicio_viagem, modal,
+                        protocolo_autorizacao, protocolo_cstat, status, created_at,
+                        emit_cnpj_cpf, emit_razao_social, emit_uf,
+                        uf_inicio, uf_fim, percurso,
+                        rntrc, placa_tracao, veiculos_json, condutores_json,
+                        valor_carga, codigo_unidade, quantidade_carga,
+                        informacoes_adicionais
+                    ) VALUES (
+                        ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11,
+                        ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21,
+                        ?22, ?23, ?24, ?25
+                    )
+                    "#,
+                )
+                .bind(mdfe.id.to_string())
+                .bind(&mdfe.chave_acesso)
+                .bind(&mdfe.numero)
+                .bind(&mdfe.serie)
+                .bind(mdfe.data_emissao)
+                .bind(mdfe.data_inicio_viagem)
+                .bind(format!("{:?}", mdfe.modal))
+                .bind(&mdfe.protocolo_autorizacao)
+                .bind(mdfe.protocolo.as_ref().map(|p| &p.codigo_status))
+                .bind(format!("{:?}", mdfe.status))
+                .bind(mdfe.created_at)
+                .bind(&mdfe.emitente.cnpj_cpf)
+                .bind(&mdfe.emitente.razao_social)
+                .bind(&mdfe.emitente.endereco.uf)
+                .bind(&mdfe.uf_inicio)
+                .bind(&mdfe.uf_fim)
+                .bind(&percurso)
+                .bind(&mdfe.rntrc)
+                .bind(placa_tracao)
+                .bind(&veiculos_json)
+                .bind(&condutores_json)
+                .bind(mdfe.totais.valor_carga)
+                .bind(&mdfe.totais.codigo_unidade)
+                .bind(mdfe.totais.quantidade_carga)
+                .bind(&mdfe.informacoes_adicionais)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO manifestos (
+                        id, chave_acesso, numero, serie, data_emissao, data_inicio_viagem, modal,
+                        protocolo_autorizacao, protocolo_cstat, status, created_at,
+                        emit_cnpj_cpf, emit_razao_social, emit_uf,
+                        uf_inicio, uf_fim, percurso,
+                        rntrc, placa_tracao, veiculos_json, condutores_json,
+                        valor_carga, codigo_unidade, quantidade_carga,
+                        informacoes_adicionais
+                    ) VALUES (
+                        $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+                        $12, $13, $14, $15, $16, $17, $18, $19, $20, $21,
+                        $22, $23, $24, $25
+                    )
+                    "#,
+                )
+                .bind(mdfe.id)
+                .bind(&mdfe.chave_acesso)
+                .bind(&mdfe.numero)
+                .bind(&mdfe.serie)
+                .bind(mdfe.data_emissao)
+                .bind(mdfe.data_inicio_viagem)
+                .bind(format!("{:?}", mdfe.modal))
+                .bind(&mdfe.protocolo_autorizacao)
+                .bind(mdfe.protocolo.as_ref().map(|p| &p.codigo_status))
+                .bind(format!("{:?}", mdfe.status))
+                .bind(mdfe.created_at)
+                .bind(&mdfe.emitente.cnpj_cpf)
+                .bind(&mdfe.emitente.razao_social)
+                .bind(&mdfe.emitente.endereco.uf)
+                .bind(&mdfe.uf_inicio)
+                .bind(&mdfe.uf_fim)
+                .bind(&percurso)
+                .bind(&mdfe.rntrc)
+                .bind(placa_tracao)
+                .bind(&veiculos_json)
+                .bind(&condutores_json)
+                .bind(mdfe.totais.valor_carga)
+                .bind(&mdfe.totais.codigo_unidade)
+                .bind(mdfe.totais.quantidade_carga)
+                .bind(&mdfe.informacoes_adicionais)
+                .execute(pool)
+                .await?;
+            }
+        }
+        self.insert_manifesto_documentos(mdfe).await?;
+        Ok(())
+    }
+
+    /// Insere as chaves de CT-e/NF-e de cada município de descarregamento (infDoc)
+    async fn insert_manifesto_documentos(&self, mdfe: &ManifestoDocumentos) -> Result<()> {
+        for municipio in &mdfe.municipios_descarregamento {
+            let documentos = municipio
+                .chaves_cte
+                .iter()
+                .map(|chave| ("CTe", chave))
+                .chain(municipio.chaves_nfe.iter().map(|chave| ("NFe", chave)));
+            for (tipo, chave) in documentos {
+                match &self.pool {
+                    DatabasePool::Sqlite(pool) => {
+                        sqlx::query(
+                            r#"
+                            INSERT INTO manifesto_documentos (manifesto_id, municipio_codigo, municipio_nome, tipo, chave_acesso)
+                            VALUES (?1, ?2, ?3, ?4, ?5)
+                            "#,
+                        )
+                        .bind(mdfe.id.to_string())
+                        .bind(&municipio.codigo)
+                        .bind(&municipio.nome)
+                        .bind(tipo)
+                        .bind(chave)
+                        .execute(pool)
+                        .await?;
+                    }
+                    DatabasePool::Postgres(pool) => {
+                        sqlx::query(
+                            r#"
+                            INSERT INTO manifesto_documentos (manifesto_id, municipio_codigo, municipio_nome, tipo, chave_acesso)
+                            VALUES ($1, $2, $3, $4, $5)
+                            "#,
+                        )
+                        .bind(mdfe.id)
+                        .bind(&municipio.codigo)
+                        .bind(&municipio.nome)
+                        .bind(tipo)
+                        .bind(chave)
+                        .execute(pool)
+                        .await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Insere uma NFS-e (Padrão Nacional ou ABRASF)
+    pub async fn insert_nfse(&self, nfse: &NotaFiscalServico) -> Result<()> {
+        let tomador = nfse.tomador.as_ref();
+        let servico = &nfse.servico;
+        let valores = &nfse.valores;
+        let retencoes = &nfse.retencoes;
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO notas_servico (
+                        id, layout, chave_acesso, numero, codigo_verificacao, data_emissao, competencia,
+                        codigo_municipio_emissor, numero_rps, serie_rps, status, created_at,
+                        prest_cnpj_cpf, prest_razao_social, prest_inscricao_municipal, prest_uf,
+                        optante_simples_nacional, toma_cnpj_cpf, toma_razao_social,
+                        codigo_tributacao_nacional, item_lista_servico, codigo_tributacao_municipal,
+                        codigo_cnae, discriminacao, codigo_municipio_prestacao, codigo_municipio_incidencia,
+                        valor_servicos, valor_deducoes, desconto_incondicionado, desconto_condicionado,
+                        base_calculo, aliquota_iss, valor_iss, iss_retido, valor_liquido,
+                        ret_pis, ret_cofins, ret_inss, ret_irrf, ret_csll, ret_outras
+                    ) VALUES (
+                        ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12,
+                        ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24,
+                        ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35, ?36,
+                        ?37, ?38, ?39, ?40, ?41
+                    )
+                    "#,
+                )
+                .bind(nfse.id.to_string())
+                .bind(format!("{:?}", nfse.layout))
+                .bind(&nfse.chave_acesso)
+                .bind(&nfse.numero)
+                .bind(&nfse.codigo_verificacao)
+                .bind(nfse.data_emissao)
+                .bind(nfse.competencia)
+                .bind(&nfse.codigo_municipio_emissor)
+                .bind(&nfse.numero_rps)
+                .bind(&nfse.serie_rps)
+                .bind(format!("{:?}", nfse.status))
+                .bind(nfse.created_at)
+                .bind(&nfse.prestador.cnpj_cpf)
+                .bind(&nfse.prestador.razao_social)
+                .bind(&nfse.prestador.inscricao_municipal)
+                .bind(&nfse.prestador.endereco.uf)
+                .bind(nfse.optante_simples_nacional)
+                .bind(tomador.map(|t| &t.cnpj_cpf))
+                .bind(tomador.map(|t| &t.razao_social))
+                .bind(&servico.codigo_tributacao_nacional)
+                .bind(&servico.item_lista_servico)
+                .bind(&servico.codigo_tributacao_municipal)
+                .bind(&servico.codigo_cnae)
+                .bind(&servico.discriminacao)
+                .bind(&servico.codigo_municipio_prestacao)
+                .bind(&servico.codigo_municipio_incidencia)
+                .bind(valores.valor_servicos)
+                .bind(valores.valor_deducoes)
+                .bind(valores.desconto_incondicionado)
+                .bind(valores.desconto_condicionado)
+                .bind(valores.base_calculo)
+                .bind(valores.aliquota_iss)
+                .bind(valores.valor_iss)
+                .bind(valores.iss_retido)
+                .bind(valores.valor_liquido)
+                .bind(retencoes.pis)
+                .bind(retencoes.cofins)
+                .bind(retencoes.inss)
+                .bind(retencoes.irrf)
+                .bind(retencoes.csll)
+                .bind(retencoes.outras)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO notas_servico (
+                        id, layout, chave_acesso, numero, codigo_verificacao, data_emissao, competencia,
+                        codigo_municipio_emissor, numero_rps, serie_rps, status, created_at,
+                        prest_cnpj_cpf, prest_razao_social, prest_inscricao_municipal, prest_uf,
+                        optante_simples_nacional, toma_cnpj_cpf, toma_razao_social,
+                        codigo_tributacao_nacional, item_lista_servico, codigo_tributacao_municipal,
+                        codigo_cnae, discriminacao, codigo_municipio_prestacao, codigo_municipio_incidencia,
+                        valor_servicos, valor_deducoes, desconto_incondicionado, desconto_condicionado,
+                        base_calculo, aliquota_iss, valor_iss, iss_retido, valor_liquido,
+                        ret_pis, ret_cofins, ret_inss, ret_irrf, ret_csll, ret_outras
+                    ) VALUES (
+                        $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12,
+                        $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24,
+                        $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36,
+                        $37, $38, $39, $40, $41
+                    )
+                    "#,
+                )
+                .bind(nfse.id)
+                .bind(format!("{:?}", nfse.layout))
+                .bind(&nfse.chave_acesso)
+                .bind(&nfse.numero)
+                .bind(&nfse.codigo_verificacao)
+                .bind(nfse.data_emissao)
+                .bind(nfse.competencia)
+                .bind(&nfse.codigo_municipio_emissor)
+                .bind(&nfse.numero_rps)
+                .bind(&nfse.serie_rps)
+                .bind(format!("{:?}", nfse.status))
+                .bind(nfse.created_at)
+                .bind(&nfse.prestador.cnpj_cpf)
+                .bind(&nfse.prestador.razao_social)
+                .bind(&nfse.prestador.inscricao_municipal)
+                .bind(&nfse.prestador.endereco.uf)
+                .bind(nfse.optante_simples_nacional)
+                .bind(tomador.map(|t| &t.cnpj_cpf))
+                .bind(tomador.map(|t| &t.razao_social))
+                .bind(&servico.codigo_tributacao_nacional)
+                .bind(&servico.item_lista_servico)
+                .bind(&servico.codigo_tributacao_municipal)
+                .bind(&servico.codigo_cnae)
+                .bind(&servico.discriminacao)
+                .bind(&servico.codigo_municipio_prestacao)
+                .bind(&servico.codigo_municipio_incidencia)
+                .bind(valores.valor_servicos)
+                .bind(valores.valor_deducoes)
+                .bind(valores.desconto_incondicionado)
+                .bind(valores.desconto_condicionado)
+                .bind(valores.base_calculo)
+                .bind(valores.aliquota_iss)
+                .bind(valores.valor_iss)
+                .bind(valores.iss_retido)
+                .bind(valores.valor_liquido)
+                .bind(retencoes.pis)
+                .bind(retencoes.cofins)
+                .bind(retencoes.inss)
+                .bind(retencoes.irrf)
+                .bind(retencoes.csll)
+                .bind(retencoes.outras)
                 .execute(pool)
                 .await?;
             }
         }
         Ok(())
     }
-    /// Lista documentos (NF-e e/ou CT-e) com paginação e filtro opcional por tipo.
+
+    /// Lista documentos (NF-e e/ou CT-e) ativos com paginação e filtro opcional por tipo.
     pub async fn list_documents(
         &self,
         doc_type: Option<&str>,
@@ -267,12 +1011,15 @@ impl Repository {
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
                 if let Some(t) = doc_type {
-                    if t == "NFe" {
+                    if t == "NFe" || t == "NFCe" {
+                        let modelo = if t == "NFCe" { "65" } else { "55" };
                         let rows = sqlx::query(
-                            r#"SELECT 'NFe' as document_type, chave_acesso, numero, serie, data_emissao,
+                            r#"SELECT modelo, chave_acesso, numero, serie, data_emissao,
                                    emit_razao_social, dest_razao_social, valor_total
-                                FROM notas_fiscais ORDER BY data_emissao DESC LIMIT ?1 OFFSET ?2"#,
+                                FROM notas_fiscais WHERE modelo = ?1 AND status <> 'Cancelled'
+                                ORDER BY data_emissao DESC LIMIT ?2 OFFSET ?3"#,
                         )
+                        .bind(modelo)
                         .bind(limit)
                         .bind(offset)
                         .fetch_all(pool)
@@ -281,16 +1028,19 @@ impl Repository {
                             .into_iter()
                             .map(|r| DocumentSummary::from_nf_row(&r))
                             .collect());
-                    } else if t == "CTe" {
-                        let rows = sqlx::query(
-                            r#"SELECT 'CTe' as document_type, chave_acesso, numero, serie, data_emissao,
+                    } else if let Some(filtro) = Self::filtro_tipo_cte(t) {
+                        let sql = format!(
+                            r#"SELECT modelo, tipo_cte, chave_acesso, numero, serie, data_emissao,
                                    emit_razao_social, dest_razao_social, valor_total
-                                FROM conhecimentos_transporte ORDER BY data_emissao DESC LIMIT ?1 OFFSET ?2"#,
-                        )
-                        .bind(limit)
-                        .bind(offset)
-                        .fetch_all(pool)
-                        .await?;
+                                FROM conhecimentos_transporte WHERE {} AND status <> 'Cancelled'
+                                ORDER BY data_emissao DESC LIMIT ?1 OFFSET ?2"#,
+                            filtro
+                        );
+                        let rows = sqlx::query(&sql)
+                            .bind(limit)
+                            .bind(offset)
+                            .fetch_all(pool)
+                            .await?;
                         return Ok(rows
                             .into_iter()
                             .map(|r| DocumentSummary::from_cte_row(&r))
@@ -299,13 +1049,13 @@ impl Repository {
                 }
                 let rows = sqlx::query(
                     r#"SELECT * FROM (
-                            SELECT 'NFe' as document_type, chave_acesso, numero, serie, data_emissao,
+                            SELECT 'NFe' as document_type, modelo, '0' as tipo_cte, chave_acesso, numero, serie, data_emissao,
                                    emit_razao_social, dest_razao_social, valor_total
-                              FROM notas_fiscais
+                              FROM notas_fiscais WHERE status <> 'Cancelled'
                             UNION ALL
-                            SELECT 'CTe' as document_type, chave_acesso, numero, serie, data_emissao,
+                            SELECT 'CTe' as document_type, modelo, tipo_cte, chave_acesso, numero, serie, data_emissao,
                                    emit_razao_social, dest_razao_social, valor_total
-                              FROM conhecimentos_transporte
+                              FROM conhecimentos_transporte WHERE status <> 'Cancelled'
                         ) ORDER BY data_emissao DESC LIMIT ?1 OFFSET ?2"#,
                 )
                 .bind(limit)
@@ -326,12 +1076,15 @@ impl Repository {
             }
             DatabasePool::Postgres(pool) => {
                 if let Some(t) = doc_type {
-                    if t == "NFe" {
+                    if t == "NFe" || t == "NFCe" {
+                        let modelo = if t == "NFCe" { "65" } else { "55" };
                         let rows = sqlx::query(
-                            r#"SELECT 'NFe' as document_type, chave_acesso, numero, serie, data_emissao::text as data_emissao,
+                            r#"SELECT modelo, chave_acesso, numero, serie, data_emissao::text as data_emissao,
                                    emit_razao_social, dest_razao_social, valor_total
-                                FROM notas_fiscais ORDER BY data_emissao DESC LIMIT $1 OFFSET $2"#,
+                                FROM notas_fiscais WHERE modelo = $1 AND status <> 'Cancelled'
+                                ORDER BY data_emissao DESC LIMIT $2 OFFSET $3"#,
                         )
+                        .bind(modelo)
                         .bind(limit)
                         .bind(offset)
                         .fetch_all(pool)
@@ -340,16 +1093,19 @@ impl Repository {
                             .into_iter()
                             .map(|r| DocumentSummary::from_nf_row_pg(&r))
                             .collect());
-                    } else if t == "CTe" {
-                        let rows = sqlx::query(
-                            r#"SELECT 'CTe' as document_type, chave_acesso, numero, serie, data_emissao::text as data_emissao,
+                    } else if let Some(filtro) = Self::filtro_tipo_cte(t) {
+                        let sql = format!(
+                            r#"SELECT modelo, tipo_cte, chave_acesso, numero, serie, data_emissao::text as data_emissao,
                                    emit_razao_social, dest_razao_social, valor_total
-                                FROM conhecimentos_transporte ORDER BY data_emissao DESC LIMIT $1 OFFSET $2"#,
-                        )
-                        .bind(limit)
-                        .bind(offset)
-                        .fetch_all(pool)
-                        .await?;
+                                FROM conhecimentos_transporte WHERE {} AND status <> 'Cancelled'
+                                ORDER BY data_emissao DESC LIMIT $1 OFFSET $2"#,
+                            filtro
+                        );
+                        let rows = sqlx::query(&sql)
+                            .bind(limit)
+                            .bind(offset)
+                            .fetch_all(pool)
+                            .await?;
                         return Ok(rows
                             .into_iter()
                             .map(|r| DocumentSummary::from_cte_row_pg(&r))
@@ -358,11 +1114,11 @@ impl Repository {
                 }
                 let rows = sqlx::query(
                     r#"SELECT * FROM (
-                            SELECT 'NFe' as document_type, chave_acesso, numero, serie, data_emissao::text as data_emissao,
-                                   emit_razao_social, dest_razao_social, valor_total FROM notas_fiscais
+                            SELECT 'NFe' as document_type, modelo, '0' as tipo_cte, chave_acesso, numero, serie, data_emissao::text as data_emissao,
+                                   emit_razao_social, dest_razao_social, valor_total FROM notas_fiscais WHERE status <> 'Cancelled'
                             UNION ALL
-                            SELECT 'CTe' as document_type, chave_acesso, numero, serie, data_emissao::text as data_emissao,
-                                   emit_razao_social, dest_razao_social, valor_total FROM conhecimentos_transporte
+                            SELECT 'CTe' as document_type, modelo, tipo_cte, chave_acesso, numero, serie, data_emissao::text as data_emissao,
+                                   emit_razao_social, dest_razao_social, valor_total FROM conhecimentos_transporte WHERE status <> 'Cancelled'
                         ) t ORDER BY data_emissao DESC LIMIT $1 OFFSET $2"#,
                 )
                 .bind(limit)
@@ -408,17 +1164,367 @@ impl Repository {
         }
     }
 
-    /// Retorna contagem de notas fiscais
+    /// Lista as chaves dos CT-e que transportam o documento com a chave informada
+    pub async fn find_ctes_by_documento_referenciado(&self, chave: &str) -> Result<Vec<String>> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let rows = sqlx::query(
+                    r#"SELECT c.chave_acesso FROM conhecimentos_transporte c
+                        JOIN documentos_referenciados d ON d.cte_id = c.id
+                        WHERE d.chave_acesso = ?"#,
+                )
+                .bind(chave)
+                .fetch_all(pool)
+                .await?;
+                Ok(rows.into_iter().map(|r| r.get("chave_acesso")).collect())
+            }
+            DatabasePool::Postgres(pool) => {
+                let rows = sqlx::query(
+                    r#"SELECT c.chave_acesso FROM conhecimentos_transporte c
+                        JOIN documentos_referenciados d ON d.cte_id = c.id
+                        WHERE d.chave_acesso = $1"#,
+                )
+                .bind(chave)
+                .fetch_all(pool)
+                .await?;
+                Ok(rows.into_iter().map(|r| r.get("chave_acesso")).collect())
+            }
+        }
+    }
+
+    /// Insere um evento fiscal e, se ele mudar o status (cancelamento), atualiza o documento
+    pub async fn insert_evento(&self, evento: &EventoFiscal) -> Result<()> {
+        let correcao = evento.correcao.clone().or_else(|| {
+            (!evento.correcoes.is_empty()).then(|| {
+                evento
+                    .correcoes
+                    .iter()
+                    .map(|c| format!("{}/{}: {}", c.grupo, c.campo, c.valor))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            })
+        });
+        let protocolo = evento.protocolo.as_ref();
+        let status = evento.status_resultante().map(|s| format!("{:?}", s));
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO eventos_fiscais (
+                        id, chave_acesso, codigo, sequencia, data_evento, descricao,
+                        autor_cnpj_cpf, justificativa, correcao,
+                        protocolo, protocolo_cstat, data_registro, created_at
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                    "#,
+                )
+                .bind(evento.id.to_string())
+                .bind(&evento.chave_acesso)
+                .bind(&evento.codigo)
+                .bind(evento.sequencia as i64)
+                .bind(evento.data_evento)
+                .bind(&evento.descricao)
+                .bind(&evento.autor_cnpj_cpf)
+                .bind(&evento.justificativa)
+                .bind(&correcao)
+                .bind(protocolo.and_then(|p| p.numero.as_ref()))
+                .bind(protocolo.map(|p| &p.codigo_status))
+                .bind(protocolo.and_then(|p| p.data_recebimento))
+                .bind(evento.created_at)
+                .execute(pool)
+                .await?;
+
+                if let Some(status) = &status {
+                    for tabela in ["notas_fiscais", "conhecimentos_transporte"] {
+                        sqlx::query(&format!(
+                            "UPDATE {} SET status = ?1 WHERE chave_acesso = ?2",
+                            tabela
+                        ))
+                        .bind(status)
+                        .bind(&evento.chave_acesso)
+                        .execute(pool)
+                        .await?;
+                    }
+                }
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO eventos_fiscais (
+                        id, chave_acesso, codigo, sequencia, data_evento, descricao,
+                        autor_cnpj_cpf, justificativa, correcao,
+                        protocolo, protocolo_cstat, data_registro, created_at
+                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                    "#,
+                )
+                .bind(evento.id)
+                .bind(&evento.chave_acesso)
+                .bind(&evento.codigo)
+                .bind(evento.sequencia as i32)
+                .bind(evento.data_evento)
+                .bind(&evento.descricao)
+                .bind(&evento.autor_cnpj_cpf)
+                .bind(&evento.justificativa)
+                .bind(&correcao)
+                .bind(protocolo.and_then(|p| p.numero.as_ref()))
+                .bind(protocolo.map(|p| &p.codigo_status))
+                .bind(protocolo.and_then(|p| p.data_recebimento))
+                .bind(evento.created_at)
+                .execute(pool)
+                .await?;
+
+                if let Some(status) = &status {
+                    for tabela in ["notas_fiscais", "conhecimentos_transporte"] {
+                        sqlx::query(&format!(
+                            "UPDATE {} SET status = $1 WHERE chave_acesso = $2",
+                            tabela
+                        ))
+                        .bind(status)
+                        .bind(&evento.chave_acesso)
+                        .execute(pool)
+                        .await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Aplica ao documento recém-inserido os eventos que chegaram antes dele
+    async fn reaplicar_eventos(&self, tabela: &str, chave: &str) -> Result<()> {
+        let cancelado = self
+            .find_eventos_by_chave(chave)
+            .await?
+            .iter()
+            .any(|codigo| TipoEvento::from_codigo(codigo).cancela_documento());
+        if !cancelado {
+            return Ok(());
+        }
+        let status = format!("{:?}", ProcessingStatus::Cancelled);
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                sqlx::query(&format!(
+                    "UPDATE {} SET status = ?1 WHERE chave_acesso = ?2",
+                    tabela
+                ))
+                .bind(&status)
+                .bind(chave)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::Postgres(pool) => {
+                sqlx::query(&format!(
+                    "UPDATE {} SET status = $1 WHERE chave_acesso = $2",
+                    tabela
+                ))
+                .bind(&status)
+                .bind(chave)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lista os códigos (`tpEvento`) dos eventos de um documento em ordem cronológica
+    pub async fn find_eventos_by_chave(&self, chave: &str) -> Result<Vec<String>> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let rows = sqlx::query(
+                    "SELECT codigo FROM eventos_fiscais WHERE chave_acesso = ? ORDER BY data_evento, sequencia",
+                )
+                .bind(chave)
+                .fetch_all(pool)
+                .await?;
+                Ok(rows.into_iter().map(|r| r.get("codigo")).collect())
+            }
+            DatabasePool::Postgres(pool) => {
+                let rows = sqlx::query(
+                    "SELECT codigo FROM eventos_fiscais WHERE chave_acesso = $1 ORDER BY data_evento, sequencia",
+                )
+                .bind(chave)
+                .fetch_all(pool)
+                .await?;
+                Ok(rows.into_iter().map(|r| r.get("codigo")).collect())
+            }
+        }
+    }
+
+    /// Busca MDF-e por chave de acesso
+    pub async fn find_mdfe_by_chave(&self, chave: &str) -> Result<Option<String>> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query("SELECT id FROM manifestos WHERE chave_acesso = ?")
+                    .bind(chave)
+                    .fetch_optional(pool)
+                    .await?;
+
+                Ok(row.map(|r| r.get("id")))
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query("SELECT id FROM manifestos WHERE chave_acesso = $1")
+                    .bind(chave)
+                    .fetch_optional(pool)
+                    .await?;
+
+                Ok(row.map(|r| r.get("id")))
+            }
+        }
+    }
+
+    /// Busca NFS-e pela chave (Padrão Nacional) ou identificador composto (ABRASF)
+    pub async fn find_nfse_by_chave(&self, chave: &str) -> Result<Option<String>> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let row = sqlx::query("SELECT id FROM notas_servico WHERE chave_acesso = ?")
+                    .bind(chave)
+                    .fetch_optional(pool)
+                    .await?;
+
+                Ok(row.map(|r| r.get("id")))
+            }
+            DatabasePool::Postgres(pool) => {
+                let row = sqlx::query("SELECT id FROM notas_servico WHERE chave_acesso = $1")
+                    .bind(chave)
+                    .fetch_optional(pool)
+                    .await?;
+
+                Ok(row.map(|r| r.get("id")))
+            }
+        }
+    }
+
+    /// Lista as chaves dos MDF-e que relacionam o CT-e ou NF-e com a chave informada
+    pub async fn find_mdfes_by_documento(&self, chave: &str) -> Result<Vec<String>> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let rows = sqlx::query(
+                    r#"SELECT DISTINCT m.chave_acesso FROM manifestos m
+                        JOIN manifesto_documentos d ON d.manifesto_id = m.id
+                        WHERE d.chave_acesso = ?"#,
+                )
+                .bind(chave)
+                .fetch_all(pool)
+                .await?;
+                Ok(rows.into_iter().map(|r| r.get("chave_acesso")).collect())
+            }
+            DatabasePool::Postgres(pool) => {
+                let rows = sqlx::query(
+                    r#"SELECT DISTINCT m.chave_acesso FROM manifestos m
+                        JOIN manifesto_documentos d ON d.manifesto_id = m.id
+                        WHERE d.chave_acesso = $1"#,
+                )
+                .bind(chave)
+                .fetch_all(pool)
+                .await?;
+                Ok(rows.into_iter().map(|r| r.get("chave_acesso")).collect())
+            }
+        }
+    }
+
+    /// Retorna contagem de notas fiscais (NF-e, modelo 55)
     pub async fn count_notas_fiscais(&self) -> Result<i64> {
+        self.count_notas_por_modelo(ModeloNotaFiscal::NFe).await
+    }
+
+    /// Retorna contagem de notas fiscais de consumidor (NFC-e, modelo 65)
+    pub async fn count_nfces(&self) -> Result<i64> {
+        self.count_notas_por_modelo(ModeloNotaFiscal::NFCe).await
+    }
+
+    async fn count_notas_por_modelo(&self, modelo: ModeloNotaFiscal) -> Result<i64> {
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
-                let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM notas_fiscais")
+                let count = sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM notas_fiscais WHERE modelo = ?1 AND status <> 'Cancelled'",
+                )
+                .bind(modelo.codigo())
+                .fetch_one(pool)
+                .await?;
+                Ok(count)
+            }
+            DatabasePool::Postgres(pool) => {
+                let count = sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM notas_fiscais WHERE modelo = $1 AND status <> 'Cancelled'",
+                )
+                .bind(modelo.codigo())
+                .fetch_one(pool)
+                .await?;
+                Ok(count)
+            }
+        }
+    }
+
+    /// Retorna contagem de CT-es (modelo 57, exceto o CT-e Simplificado)
+    pub async fn count_ctes(&self) -> Result<i64> {
+        self.count_ctes_por_tipo(DocumentType::ConhecimentoTransporte).await
+    }
+
+    /// Retorna contagem de CT-es OS (modelo 67)
+    pub async fn count_ctes_os(&self) -> Result<i64> {
+        self.count_ctes_por_tipo(DocumentType::ConhecimentoTransporteOS).await
+    }
+
+    /// Retorna contagem de CT-es Simplificados (`tpCTe` 5 e 6)
+    pub async fn count_ctes_simplificados(&self) -> Result<i64> {
+        self.count_ctes_por_tipo(DocumentType::ConhecimentoTransporteSimplificado).await
+    }
+
+    async fn count_ctes_por_tipo(&self, tipo: DocumentType) -> Result<i64> {
+        let filtro = Self::filtro_tipo_cte(tipo.codigo()).unwrap_or("1 = 1");
+        let sql = format!(
+            "SELECT COUNT(*) FROM conhecimentos_transporte WHERE {} AND status <> 'Cancelled'",
+            filtro
+        );
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let count = sqlx::query_scalar::<_, i64>(&sql).fetch_one(pool).await?;
+                Ok(count)
+            }
+            DatabasePool::Postgres(pool) => {
+                let count = sqlx::query_scalar::<_, i64>(&sql).fetch_one(pool).await?;
+                Ok(count)
+            }
+        }
+    }
+
+    /// Condição SQL sobre `modelo`/`tipo_cte` que separa CT-e, CT-e OS e CT-e Simplificado
+    fn filtro_tipo_cte(tipo: &str) -> Option<&'static str> {
+        match tipo {
+            "CTe" => Some("modelo = '57' AND tipo_cte NOT IN ('5', '6')"),
+            "CTeOS" => Some("modelo = '67'"),
+            "CTeSimp" => Some("modelo = '57' AND tipo_cte IN ('5', '6')"),
+            _ => None,
+        }
+    }
+
+    /// Retorna contagem de NF-e e CT-e cancelados por evento e de NFS-e canceladas
+    pub async fn count_cancelados(&self) -> Result<i64> {
+        let sql = r#"SELECT (SELECT COUNT(*) FROM notas_fiscais WHERE status = 'Cancelled')
+                          + (SELECT COUNT(*) FROM conhecimentos_transporte WHERE status = 'Cancelled')
+                          + (SELECT COUNT(*) FROM notas_servico WHERE status = 'Cancelled')"#;
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let count = sqlx::query_scalar::<_, i64>(sql).fetch_one(pool).await?;
+                Ok(count)
+            }
+            DatabasePool::Postgres(pool) => {
+                let count = sqlx::query_scalar::<_, i64>(sql).fetch_one(pool).await?;
+                Ok(count)
+            }
+        }
+    }
+
+    /// Retorna contagem de MDF-es
+    pub async fn count_mdfes(&self) -> Result<i64> {
+        match &self.pool {
+            DatabasePool::Sqlite(pool) => {
+                let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM manifestos")
                     .fetch_one(pool)
                     .await?;
                 Ok(count)
             }
             DatabasePool::Postgres(pool) => {
-                let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM notas_fiscais")
+                let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM manifestos")
                     .fetch_one(pool)
                     .await?;
                 Ok(count)
@@ -426,21 +1532,16 @@ impl Repository {
         }
     }
 
-    /// Retorna contagem de CT-es
-    pub async fn count_ctes(&self) -> Result<i64> {
+    /// Retorna contagem de NFS-es ativas
+    pub async fn count_nfses(&self) -> Result<i64> {
+        let sql = "SELECT COUNT(*) FROM notas_servico WHERE status <> 'Cancelled'";
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
-                let count =
-                    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM conhecimentos_transporte")
-                        .fetch_one(pool)
-                        .await?;
+                let count = sqlx::query_scalar::<_, i64>(sql).fetch_one(pool).await?;
                 Ok(count)
             }
             DatabasePool::Postgres(pool) => {
-                let count =
-                    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM conhecimentos_transporte")
-                        .fetch_one(pool)
-                        .await?;
+                let count = sqlx::query_scalar::<_, i64>(sql).fetch_one(pool).await?;
                 Ok(count)
             }
         }
@@ -564,7 +1665,7 @@ impl Repository {
         match &self.pool {
             DatabasePool::Sqlite(pool) => {
                 if let Some(row) = sqlx::query(
-                    r#"SELECT chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
+                    r#"SELECT modelo, chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
                         FROM notas_fiscais WHERE chave_acesso = ?"#
                 )
                 .bind(chave)
@@ -573,7 +1674,7 @@ impl Repository {
                     return Ok(Some(DocumentSummary::from_nf_row(&row)));
                 }
                 if let Some(row) = sqlx::query(
-                    r#"SELECT chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
+                    r#"SELECT modelo, tipo_cte, chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
                         FROM conhecimentos_transporte WHERE chave_acesso = ?"#
                 )
                 .bind(chave)
@@ -585,7 +1686,7 @@ impl Repository {
             }
             DatabasePool::Postgres(pool) => {
                 if let Some(row) = sqlx::query(
-                    r#"SELECT chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
+                    r#"SELECT modelo, chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
                         FROM notas_fiscais WHERE chave_acesso = $1"#
                 )
                 .bind(chave)
@@ -594,7 +1695,7 @@ impl Repository {
                     return Ok(Some(DocumentSummary::from_nf_row_pg(&row)));
                 }
                 if let Some(row) = sqlx::query(
-                    r#"SELECT chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
+                    r#"SELECT modelo, tipo_cte, chave_acesso, numero, serie, data_emissao, emit_razao_social, dest_razao_social, valor_total
                         FROM conhecimentos_transporte WHERE chave_acesso = $1"#
                 )
                 .bind(chave)
@@ -609,14 +1710,26 @@ impl Repository {
 
     /// Retorna estatísticas agregadas
     pub async fn stats(&self) -> Result<Stats> {
-        let (nf, cte, today) = tokio::try_join!(
+        let (nf, nfce, cte, cte_os, cte_simp, mdfe, nfse, cancelados, today) = tokio::try_join!(
             self.count_notas_fiscais(),
+            self.count_nfces(),
             self.count_ctes(),
+            self.count_ctes_os(),
+            self.count_ctes_simplificados(),
+            self.count_mdfes(),
+            self.count_nfses(),
+            self.count_cancelados(),
             self.count_processed_today()
         )?;
         Ok(Stats {
             notas_fiscais: nf,
+            nfces: nfce,
             ctes: cte,
+            ctes_os: cte_os,
+            ctes_simplificados: cte_simp,
+            mdfes: mdfe,
+            nfses: nfse,
+            cancelados,
             processed_today: today,
         })
     }
@@ -625,7 +1738,14 @@ impl Repository {
 /// Estrutura agregada de estatísticas
 pub struct Stats {
     pub notas_fiscais: i64,
+    pub nfces: i64,
     pub ctes: i64,
+    pub ctes_os: i64,
+    pub ctes_simplificados: i64,
+    pub mdfes: i64,
+    pub nfses: i64,
+    /// NF-e, CT-e e NFS-e cancelados, fora das contagens acima
+    pub cancelados: i64,
     pub processed_today: i64,
 }
 
@@ -643,10 +1763,33 @@ pub struct DocumentSummary {
 }
 
 impl DocumentSummary {
+    /// Tipo do documento a partir da coluna `modelo` de `notas_fiscais`
+    fn tipo_nota(modelo: &str) -> String {
+        let modelo = ModeloNotaFiscal::from_codigo(modelo).unwrap_or_default();
+        match modelo {
+            ModeloNotaFiscal::NFe => DocumentType::NotaFiscal.codigo().to_string(),
+            ModeloNotaFiscal::NFCe => DocumentType::NotaFiscalConsumidor.codigo().to_string(),
+        }
+    }
+
+    /// Tipo do documento a partir das colunas `modelo` e `tipo_cte` de `conhecimentos_transporte`
+    fn tipo_conhecimento(modelo: &str, tipo_cte: &str) -> String {
+        let modelo = ModeloConhecimento::from_codigo(modelo).unwrap_or_default();
+        let tipo_cte = TipoCTe::from_codigo(tipo_cte).unwrap_or_default();
+        let tipo = match modelo {
+            ModeloConhecimento::CTeOS => DocumentType::ConhecimentoTransporteOS,
+            ModeloConhecimento::CTe if tipo_cte.simplificado() => {
+                DocumentType::ConhecimentoTransporteSimplificado
+            }
+            ModeloConhecimento::CTe => DocumentType::ConhecimentoTransporte,
+        };
+        tipo.codigo().to_string()
+    }
+
     fn from_nf_row(row: &sqlx::sqlite::SqliteRow) -> Self {
         // For SQLite rows
         DocumentSummary {
-            document_type: "NFe".to_string(),
+            document_type: DocumentSummary::tipo_nota(&row.get::<String, _>("modelo")),
             chave_acesso: row.get("chave_acesso"),
             numero: row.get("numero"),
             serie: row.get("serie"),
@@ -658,7 +1801,7 @@ impl DocumentSummary {
     }
     fn from_cte_row(row: &sqlx::sqlite::SqliteRow) -> Self {
         DocumentSummary {
-            document_type: "CTe".to_string(),
+            document_type: Self::tipo_conhecimento(row.get("modelo"), row.get("tipo_cte")),
             chave_acesso: row.get("chave_acesso"),
             numero: row.get("numero"),
             serie: row.get("serie"),
@@ -673,7 +1816,7 @@ impl DocumentSummary {
         // For PostgreSQL rows
         use sqlx::Row;
         DocumentSummary {
-            document_type: "NFe".to_string(),
+            document_type: DocumentSummary::tipo_nota(&row.get::<String, _>("modelo")),
             chave_acesso: row.get("chave_acesso"),
             numero: row.get("numero"),
             serie: row.get("serie"),
@@ -687,7 +1830,7 @@ impl DocumentSummary {
     fn from_cte_row_pg(row: &sqlx::postgres::PgRow) -> Self {
         use sqlx::Row;
         DocumentSummary {
-            document_type: "CTe".to_string(),
+            document_type: Self::tipo_conhecimento(row.get("modelo"), row.get("tipo_cte")),
             chave_acesso: row.get("chave_acesso"),
             numero: row.get("numero"),
             serie: row.get("serie"),
@@ -699,3 +1842,310 @@ impl DocumentSummary {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use chrono::NaiveDate;
+    use crate::parsers::{
+        cte::CTeParser, evento::EventoParser, mdfe::MDFeParser, nfe::NFeParser,
+        nfse::NFSeParser, FiscalDocumentParser,
+    };
+
+    #[tokio::test]
+    async fn test_insert_nota_fiscal_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let nf = NFeParser::new()
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap();
+        repo.insert_nota_fiscal(&nf).await.unwrap();
+
+        assert!(repo.find_nota_fiscal_by_chave(&nf.chave_acesso).await.unwrap().is_some());
+        let DatabasePool::Sqlite(pool) = db.pool() else {
+            unreachable!()
+        };
+        let itens = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM itens_nota_fiscal")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(itens, nf.itens.len() as i64);
+        let vpis = sqlx::query_scalar::<_, f64>("SELECT valor_pis FROM notas_fiscais")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(vpis, 16.5);
+        let vencimentos = sqlx::query_scalar::<_, NaiveDate>(
+            "SELECT vencimento FROM duplicatas ORDER BY vencimento",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        assert_eq!(vencimentos, vec![
+            NaiveDate::from_ymd_opt(2021, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 3, 1).unwrap(),
+        ]);
+        let pagamentos = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM pagamentos_nota")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(pagamentos, 1);
+    }
+
+    #[tokio::test]
+    async fn test_schema_legado_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let DatabasePool::Sqlite(pool) = db.pool() else {
+            unreachable!()
+        };
+        for ddl in include_str!("../../test_data/schema_legado_sqlite.sql").split(';') {
+            if ddl.lines().any(|l| !l.trim().is_empty() && !l.starts_with("--")) {
+                sqlx::query(ddl).execute(pool).await.unwrap();
+            }
+        }
+
+        // Duas vezes: as colunas novas são criadas uma única vez
+        db.initialize_schema().await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+        let nf = NFeParser::new()
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap();
+        repo.insert_nota_fiscal(&nf).await.unwrap();
+        let cte = CTeParser::new()
+            .parse_string(include_str!("../../test_data/sample_cte.xml"))
+            .unwrap();
+        repo.insert_cte(&cte).await.unwrap();
+
+        let modelo = sqlx::query_scalar::<_, String>("SELECT modelo FROM notas_fiscais")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(modelo, "55");
+    }
+
+    #[tokio::test]
+    async fn test_list_documents_nfce_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let parser = NFeParser::new();
+        let nfe = parser
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap();
+        let nfce = parser
+            .parse_string(
+                r#"<NFe><infNFe Id="NFe35210112345678901234650010000000011000000010">
+                    <ide><mod>65</mod><nNF>1</nNF><serie>1</serie><dhEmi>2021-01-20T10:00:00-03:00</dhEmi></ide>
+                </infNFe></NFe>"#,
+            )
+            .unwrap();
+        repo.insert_nota_fiscal(&nfe).await.unwrap();
+        repo.insert_nota_fiscal(&nfce).await.unwrap();
+
+        let nfces = repo.list_documents(Some("NFCe"), 10, 0).await.unwrap();
+        assert_eq!(nfces.len(), 1);
+        assert_eq!(nfces[0].document_type, "NFCe");
+        assert_eq!(nfces[0].chave_acesso, nfce.chave_acesso);
+        let todos = repo.list_documents(None, 10, 0).await.unwrap();
+        assert_eq!(todos.iter().filter(|d| d.document_type == "NFe").count(), 1);
+
+        let stats = repo.stats().await.unwrap();
+        assert_eq!(stats.notas_fiscais, 1);
+        assert_eq!(stats.nfces, 1);
+    }
+
+    #[tokio::test]
+    async fn test_insert_cte_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let cte = CTeParser::new()
+            .parse_string(include_str!("../../test_data/sample_cte.xml"))
+            .unwrap();
+        repo.insert_cte(&cte).await.unwrap();
+
+        assert!(repo.find_cte_by_chave(&cte.chave_acesso).await.unwrap().is_some());
+        let nfe_chave = cte.documentos_referenciados[0].chave_acesso.as_deref().unwrap();
+        let ctes = repo.find_ctes_by_documento_referenciado(nfe_chave).await.unwrap();
+        assert_eq!(ctes, vec![cte.chave_acesso.clone()]);
+
+        if let DatabasePool::Sqlite(pool) = db.pool() {
+            let total: f64 = sqlx::query_scalar("SELECT SUM(valor) FROM componentes_prestacao")
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            assert_eq!(total, 500.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_documents_cte_variantes_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let parser = CTeParser::new();
+        for xml in [
+            include_str!("../../test_data/sample_cte.xml"),
+            include_str!("../../test_data/sample_cte_os.xml"),
+            include_str!("../../test_data/sample_cte_simp.xml"),
+        ] {
+            repo.insert_cte(&parser.parse_string(xml).unwrap()).await.unwrap();
+        }
+
+        let os = repo.list_documents(Some("CTeOS"), 10, 0).await.unwrap();
+        assert_eq!(os.len(), 1);
+        assert_eq!(os[0].document_type, "CTeOS");
+        let simp = repo.list_documents(Some("CTeSimp"), 10, 0).await.unwrap();
+        assert_eq!(simp.len(), 1);
+        let summary = repo.find_document_summary(&simp[0].chave_acesso).await.unwrap().unwrap();
+        assert_eq!(summary.document_type, "CTeSimp");
+
+        let stats = repo.stats().await.unwrap();
+        assert_eq!((stats.ctes, stats.ctes_os, stats.ctes_simplificados), (1, 1, 1));
+
+        // NF-e das entregas do Simplificado ficam vinculadas como documentos transportados
+        let ctes = repo
+            .find_ctes_by_documento_referenciado("35210112345678000190550010000000021123456781")
+            .await
+            .unwrap();
+        assert_eq!(ctes, vec![simp[0].chave_acesso.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_insert_evento_cancelamento_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let nf = NFeParser::new()
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap();
+        repo.insert_nota_fiscal(&nf).await.unwrap();
+        assert_eq!(repo.stats().await.unwrap().notas_fiscais, 1);
+
+        let evento = EventoParser::new()
+            .parse_string(include_str!("../../test_data/sample_evento_cancelamento.xml"))
+            .unwrap();
+        assert_eq!(evento.chave_acesso, nf.chave_acesso);
+        repo.insert_evento(&evento).await.unwrap();
+        // O mesmo evento (chave, tpEvento, nSeqEvento) não é registrado duas vezes
+        assert!(repo.insert_evento(&evento).await.is_err());
+
+        assert_eq!(repo.find_eventos_by_chave(&nf.chave_acesso).await.unwrap(), vec!["110111"]);
+        let stats = repo.stats().await.unwrap();
+        assert_eq!(stats.notas_fiscais, 0);
+        assert_eq!(stats.cancelados, 1);
+        assert!(repo.list_documents(None, 10, 0).await.unwrap().is_empty());
+        assert!(repo.list_documents(Some("NFe"), 10, 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_evento_antes_do_documento_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let evento = EventoParser::new()
+            .parse_string(include_str!("../../test_data/sample_evento_cancelamento.xml"))
+            .unwrap();
+        repo.insert_evento(&evento).await.unwrap();
+
+        let nf = NFeParser::new()
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap();
+        repo.insert_nota_fiscal(&nf).await.unwrap();
+
+        let stats = repo.stats().await.unwrap();
+        assert_eq!(stats.notas_fiscais, 0);
+        assert_eq!(stats.cancelados, 1);
+    }
+
+    #[tokio::test]
+    async fn test_insert_mdfe_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let mdfe = MDFeParser::new()
+            .parse_string(include_str!("../../test_data/sample_mdfe.xml"))
+            .unwrap();
+        repo.insert_mdfe(&mdfe).await.unwrap();
+
+        assert!(repo.find_mdfe_by_chave(&mdfe.chave_acesso).await.unwrap().is_some());
+        let cte_chave = mdfe.chaves_cte().next().unwrap();
+        let mdfes = repo.find_mdfes_by_documento(cte_chave).await.unwrap();
+        assert_eq!(mdfes, vec![mdfe.chave_acesso.clone()]);
+        assert_eq!(repo.stats().await.unwrap().mdfes, 1);
+
+        if let DatabasePool::Sqlite(pool) = db.pool() {
+            let (placa, percurso): (String, String) =
+                sqlx::query_as("SELECT placa_tracao, percurso FROM manifestos")
+                    .fetch_one(pool)
+                    .await
+                    .unwrap();
+            assert_eq!(placa, "ABC1D23");
+            assert_eq!(percurso, "PR,SC");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_nfse_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new_sqlite(dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        db.initialize_schema().await.unwrap();
+        let repo = Repository::new(db.pool().clone());
+
+        let nfse = NFSeParser::new()
+            .parse_string(include_str!("../../test_data/sample_nfse.xml"))
+            .unwrap();
+        repo.insert_nfse(&nfse).await.unwrap();
+
+        assert!(repo.find_nfse_by_chave(&nfse.chave_acesso).await.unwrap().is_some());
+        let stats = repo.stats().await.unwrap();
+        assert_eq!((stats.nfses, stats.cancelados), (1, 0));
+
+        if let DatabasePool::Sqlite(pool) = db.pool() {
+            let (im, iss_retido, valor_iss): (String, bool, f64) = sqlx::query_as(
+                "SELECT prest_inscricao_municipal, iss_retido, valor_iss FROM notas_servico",
+            )
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            assert_eq!(im, "12345");
+            assert!(iss_retido);
+            assert_eq!(valor_iss, 50.0);
+        }
+    }
+}
@@ -12,6 +12,7 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
             chave_acesso TEXT UNIQUE NOT NULL,
             numero TEXT NOT NULL,
             serie TEXT NOT NULL,
+            modelo TEXT NOT NULL DEFAULT '55',
             data_emissao DATETIME NOT NULL,
             tipo_nota TEXT NOT NULL,
             protocolo_autorizacao TEXT,
@@ -46,6 +47,33 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
             valor_ipi REAL,
             valor_pis REAL,
             valor_cofins REAL,
+            base_calculo_icms REAL,
+            valor_icms_desonerado REAL,
+            valor_fcp REAL,
+            base_calculo_icms_st REAL,
+            valor_icms_st REAL,
+            valor_frete REAL,
+            valor_seguro REAL,
+            valor_desconto REAL,
+            valor_ii REAL,
+            outras_despesas REAL,
+            valor_total_tributos REAL,
+            
+            -- Transporte (grupo transp)
+            modalidade_frete TEXT,
+            transp_cnpj_cpf TEXT,
+            transp_razao_social TEXT,
+            veiculo_placa TEXT,
+            peso_liquido REAL,
+            peso_bruto REAL,
+            
+            -- Protocolo de autorização
+            protocolo_cstat TEXT,
+            data_autorizacao DATETIME,
+            
+            -- NFC-e (infNFeSupl)
+            qr_code TEXT,
+            url_chave TEXT,
             
             informacoes_adicionais TEXT
         )
@@ -72,6 +100,23 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
             ean TEXT,
             informacoes_adicionais TEXT,
             
+            -- Tributos do item
+            icms_cst TEXT,
+            icms_base_calculo REAL,
+            icms_aliquota REAL,
+            icms_valor REAL,
+            icms_st_base_calculo REAL,
+            icms_st_valor REAL,
+            icms_fcp_valor REAL,
+            ipi_cst TEXT,
+            ipi_aliquota REAL,
+            ipi_valor REAL,
+            pis_cst TEXT,
+            pis_valor REAL,
+            cofins_cst TEXT,
+            cofins_valor REAL,
+            impostos_json TEXT,
+            
             FOREIGN KEY (nota_fiscal_id) REFERENCES notas_fiscais(id)
         )
         "#,
@@ -80,6 +125,47 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
     .await?;
 
     // Tabela de Conhecimentos de Transporte
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS pagamentos_nota (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nota_fiscal_id TEXT NOT NULL,
+            meio_pagamento TEXT NOT NULL,
+            descricao TEXT,
+            valor REAL NOT NULL,
+            a_prazo BOOLEAN NOT NULL,
+            cartao_cnpj TEXT,
+            cartao_bandeira TEXT,
+            cartao_autorizacao TEXT,
+            
+            FOREIGN KEY (nota_fiscal_id) REFERENCES notas_fiscais(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS duplicatas (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nota_fiscal_id TEXT NOT NULL,
+            numero_fatura TEXT,
+            numero TEXT NOT NULL,
+            vencimento DATE,
+            valor REAL NOT NULL,
+            
+            FOREIGN KEY (nota_fiscal_id) REFERENCES notas_fiscais(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_dup_vencimento ON duplicatas(vencimento)")
+        .execute(pool)
+        .await?;
+
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS conhecimentos_transporte (
@@ -87,6 +173,8 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
             chave_acesso TEXT UNIQUE NOT NULL,
             numero TEXT NOT NULL,
             serie TEXT NOT NULL,
+            modelo TEXT NOT NULL DEFAULT '57',
+            tipo_cte TEXT NOT NULL DEFAULT '0',
             data_emissao DATETIME NOT NULL,
             tipo_servico TEXT NOT NULL,
             modal TEXT NOT NULL,
@@ -111,12 +199,50 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
             dest_municipio TEXT,
             dest_uf TEXT,
             
+            -- Expedidor e Recebedor
+            exped_cnpj_cpf TEXT,
+            exped_razao_social TEXT,
+            exped_municipio TEXT,
+            exped_uf TEXT,
+            receb_cnpj_cpf TEXT,
+            receb_razao_social TEXT,
+            receb_municipio TEXT,
+            receb_uf TEXT,
+            
+            -- Tomador do serviço
+            toma_tipo TEXT,
+            toma_cnpj_cpf TEXT,
+            toma_razao_social TEXT,
+            toma_municipio TEXT,
+            toma_uf TEXT,
+            
             -- Valores
             valor_total REAL NOT NULL,
             valor_receber REAL NOT NULL,
+            icms_cst TEXT,
+            icms_base_calculo REAL,
+            icms_aliquota REAL,
+            icms_valor REAL,
+            valor_total_tributos REAL,
+            impostos_json TEXT,
             valor_carga REAL NOT NULL,
             produto_predominante TEXT NOT NULL,
             peso_bruto REAL NOT NULL,
+            peso_cubado REAL,
+            volume_m3 REAL,
+            quantidade_volumes REAL,
+            
+            -- Modal (RNTRC e grupo infModal em JSON)
+            rntrc TEXT,
+            dados_modal_json TEXT,
+            
+            -- Protocolo de autorização
+            protocolo_cstat TEXT,
+            data_autorizacao DATETIME,
+            
+            -- CT-e OS (infServico) e CT-e Simplificado (det em JSON)
+            descricao_servico TEXT,
+            entregas_json TEXT,
             
             informacoes_adicionais TEXT
         )
@@ -143,6 +269,185 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
     .execute(pool)
     .await?;
 
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_docref_chave ON documentos_referenciados(chave_acesso)",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS componentes_prestacao (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            cte_id TEXT NOT NULL,
+            nome TEXT NOT NULL,
+            valor REAL NOT NULL,
+            
+            FOREIGN KEY (cte_id) REFERENCES conhecimentos_transporte(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Tabela de Manifestos (MDF-e)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS manifestos (
+            id TEXT PRIMARY KEY,
+            chave_acesso TEXT UNIQUE NOT NULL,
+            numero TEXT NOT NULL,
+            serie TEXT NOT NULL,
+            data_emissao DATETIME NOT NULL,
+            data_inicio_viagem DATETIME,
+            modal TEXT NOT NULL,
+            protocolo_autorizacao TEXT,
+            protocolo_cstat TEXT,
+            status TEXT NOT NULL,
+            created_at DATETIME NOT NULL,
+            
+            -- Emitente
+            emit_cnpj_cpf TEXT NOT NULL,
+            emit_razao_social TEXT NOT NULL,
+            emit_uf TEXT,
+            
+            -- Percurso (UFs de passagem separadas por vírgula)
+            uf_inicio TEXT NOT NULL,
+            uf_fim TEXT NOT NULL,
+            percurso TEXT,
+            
+            -- Modal rodoviário
+            rntrc TEXT,
+            placa_tracao TEXT,
+            veiculos_json TEXT,
+            condutores_json TEXT,
+            
+            -- Totais
+            valor_carga REAL NOT NULL,
+            codigo_unidade TEXT,
+            quantidade_carga REAL NOT NULL,
+            
+            informacoes_adicionais TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Documentos (CT-e/NF-e) vinculados aos municípios de descarregamento do MDF-e
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS manifesto_documentos (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            manifesto_id TEXT NOT NULL,
+            municipio_codigo TEXT NOT NULL,
+            municipio_nome TEXT NOT NULL,
+            tipo TEXT NOT NULL,
+            chave_acesso TEXT NOT NULL,
+            
+            FOREIGN KEY (manifesto_id) REFERENCES manifestos(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_mdfe_doc_chave ON manifesto_documentos(chave_acesso)",
+    )
+    .execute(pool)
+    .await?;
+
+    // Eventos fiscais (cancelamento, CC-e, manifestação, desacordo)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS eventos_fiscais (
+            id TEXT PRIMARY KEY,
+            chave_acesso TEXT NOT NULL,
+            codigo TEXT NOT NULL,
+            sequencia INTEGER NOT NULL,
+            data_evento DATETIME NOT NULL,
+            descricao TEXT NOT NULL,
+            autor_cnpj_cpf TEXT,
+            justificativa TEXT,
+            correcao TEXT,
+            protocolo TEXT,
+            protocolo_cstat TEXT,
+            data_registro DATETIME,
+            created_at DATETIME NOT NULL,
+            
+            UNIQUE (chave_acesso, codigo, sequencia)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Notas Fiscais de Serviço (NFS-e Padrão Nacional e ABRASF)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS notas_servico (
+            id TEXT PRIMARY KEY,
+            layout TEXT NOT NULL,
+            chave_acesso TEXT UNIQUE NOT NULL,
+            numero TEXT NOT NULL,
+            codigo_verificacao TEXT,
+            data_emissao DATETIME NOT NULL,
+            competencia DATE,
+            codigo_municipio_emissor TEXT,
+            numero_rps TEXT,
+            serie_rps TEXT,
+            status TEXT NOT NULL,
+            created_at DATETIME NOT NULL,
+            
+            -- Prestador
+            prest_cnpj_cpf TEXT NOT NULL,
+            prest_razao_social TEXT NOT NULL,
+            prest_inscricao_municipal TEXT,
+            prest_uf TEXT,
+            optante_simples_nacional BOOLEAN NOT NULL,
+            
+            -- Tomador
+            toma_cnpj_cpf TEXT,
+            toma_razao_social TEXT,
+            
+            -- Serviço
+            codigo_tributacao_nacional TEXT,
+            item_lista_servico TEXT,
+            codigo_tributacao_municipal TEXT,
+            codigo_cnae TEXT,
+            discriminacao TEXT,
+            codigo_municipio_prestacao TEXT,
+            codigo_municipio_incidencia TEXT,
+            
+            -- Valores e ISS
+            valor_servicos REAL NOT NULL,
+            valor_deducoes REAL NOT NULL,
+            desconto_incondicionado REAL NOT NULL,
+            desconto_condicionado REAL NOT NULL,
+            base_calculo REAL NOT NULL,
+            aliquota_iss REAL NOT NULL,
+            valor_iss REAL NOT NULL,
+            iss_retido BOOLEAN NOT NULL,
+            valor_liquido REAL NOT NULL,
+            
+            -- Retenções federais
+            ret_pis REAL NOT NULL,
+            ret_cofins REAL NOT NULL,
+            ret_inss REAL NOT NULL,
+            ret_irrf REAL NOT NULL,
+            ret_csll REAL NOT NULL,
+            ret_outras REAL NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_nfse_prestador ON notas_servico(prest_cnpj_cpf)")
+        .execute(pool)
+        .await?;
+
     // Índices para otimização
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_nf_chave ON notas_fiscais(chave_acesso)")
         .execute(pool)
@@ -184,6 +489,8 @@ pub async fn create_tables_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
         .execute(pool)
         .await?;
 
+    adicionar_colunas_sqlite(pool).await?;
+
     Ok(())
 }
 
@@ -197,6 +504,7 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
             chave_acesso VARCHAR(44) UNIQUE NOT NULL,
             numero VARCHAR(20) NOT NULL,
             serie VARCHAR(10) NOT NULL,
+            modelo VARCHAR(2) NOT NULL DEFAULT '55',
             data_emissao TIMESTAMPTZ NOT NULL,
             tipo_nota VARCHAR(20) NOT NULL,
             protocolo_autorizacao VARCHAR(50),
@@ -231,6 +539,33 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
             valor_ipi NUMERIC(15,2),
             valor_pis NUMERIC(15,2),
             valor_cofins NUMERIC(15,2),
+            base_calculo_icms NUMERIC(15,2),
+            valor_icms_desonerado NUMERIC(15,2),
+            valor_fcp NUMERIC(15,2),
+            base_calculo_icms_st NUMERIC(15,2),
+            valor_icms_st NUMERIC(15,2),
+            valor_frete NUMERIC(15,2),
+            valor_seguro NUMERIC(15,2),
+            valor_desconto NUMERIC(15,2),
+            valor_ii NUMERIC(15,2),
+            outras_despesas NUMERIC(15,2),
+            valor_total_tributos NUMERIC(15,2),
+            
+            -- Transporte (grupo transp)
+            modalidade_frete VARCHAR(30),
+            transp_cnpj_cpf VARCHAR(14),
+            transp_razao_social VARCHAR(200),
+            veiculo_placa VARCHAR(8),
+            peso_liquido NUMERIC(15,3),
+            peso_bruto NUMERIC(15,3),
+            
+            -- Protocolo de autorização
+            protocolo_cstat VARCHAR(3),
+            data_autorizacao TIMESTAMPTZ,
+            
+            -- NFC-e (infNFeSupl)
+            qr_code TEXT,
+            url_chave VARCHAR(85),
             
             informacoes_adicionais TEXT
         )
@@ -257,6 +592,23 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
             ean VARCHAR(14),
             informacoes_adicionais TEXT,
             
+            -- Tributos do item
+            icms_cst VARCHAR(3),
+            icms_base_calculo NUMERIC(15,2),
+            icms_aliquota NUMERIC(7,4),
+            icms_valor NUMERIC(15,2),
+            icms_st_base_calculo NUMERIC(15,2),
+            icms_st_valor NUMERIC(15,2),
+            icms_fcp_valor NUMERIC(15,2),
+            ipi_cst VARCHAR(2),
+            ipi_aliquota NUMERIC(7,4),
+            ipi_valor NUMERIC(15,2),
+            pis_cst VARCHAR(2),
+            pis_valor NUMERIC(15,2),
+            cofins_cst VARCHAR(2),
+            cofins_valor NUMERIC(15,2),
+            impostos_json TEXT,
+            
             FOREIGN KEY (nota_fiscal_id) REFERENCES notas_fiscais(id)
         )
         "#,
@@ -265,6 +617,47 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
     .await?;
 
     // Tabela de Conhecimentos de Transporte
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS pagamentos_nota (
+            id SERIAL PRIMARY KEY,
+            nota_fiscal_id UUID NOT NULL,
+            meio_pagamento VARCHAR(30) NOT NULL,
+            descricao VARCHAR(60),
+            valor NUMERIC(15,2) NOT NULL,
+            a_prazo BOOLEAN NOT NULL,
+            cartao_cnpj VARCHAR(14),
+            cartao_bandeira VARCHAR(2),
+            cartao_autorizacao VARCHAR(128),
+            
+            FOREIGN KEY (nota_fiscal_id) REFERENCES notas_fiscais(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS duplicatas (
+            id SERIAL PRIMARY KEY,
+            nota_fiscal_id UUID NOT NULL,
+            numero_fatura VARCHAR(60),
+            numero VARCHAR(60) NOT NULL,
+            vencimento DATE,
+            valor NUMERIC(15,2) NOT NULL,
+            
+            FOREIGN KEY (nota_fiscal_id) REFERENCES notas_fiscais(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_dup_vencimento ON duplicatas(vencimento)")
+        .execute(pool)
+        .await?;
+
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS conhecimentos_transporte (
@@ -272,6 +665,8 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
             chave_acesso VARCHAR(44) UNIQUE NOT NULL,
             numero VARCHAR(20) NOT NULL,
             serie VARCHAR(10) NOT NULL,
+            modelo VARCHAR(2) NOT NULL DEFAULT '57',
+            tipo_cte VARCHAR(1) NOT NULL DEFAULT '0',
             data_emissao TIMESTAMPTZ NOT NULL,
             tipo_servico VARCHAR(50) NOT NULL,
             modal VARCHAR(20) NOT NULL,
@@ -296,12 +691,50 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
             dest_municipio VARCHAR(100),
             dest_uf VARCHAR(2),
             
+            -- Expedidor e Recebedor
+            exped_cnpj_cpf VARCHAR(14),
+            exped_razao_social VARCHAR(200),
+            exped_municipio VARCHAR(100),
+            exped_uf VARCHAR(2),
+            receb_cnpj_cpf VARCHAR(14),
+            receb_razao_social VARCHAR(200),
+            receb_municipio VARCHAR(100),
+            receb_uf VARCHAR(2),
+            
+            -- Tomador do serviço
+            toma_tipo VARCHAR(20),
+            toma_cnpj_cpf VARCHAR(14),
+            toma_razao_social VARCHAR(200),
+            toma_municipio VARCHAR(100),
+            toma_uf VARCHAR(2),
+            
             -- Valores
             valor_total NUMERIC(15,2) NOT NULL,
             valor_receber NUMERIC(15,2) NOT NULL,
+            icms_cst VARCHAR(3),
+            icms_base_calculo NUMERIC(15,2),
+            icms_aliquota NUMERIC(5,2),
+            icms_valor NUMERIC(15,2),
+            valor_total_tributos NUMERIC(15,2),
+            impostos_json TEXT,
             valor_carga NUMERIC(15,2) NOT NULL,
             produto_predominante VARCHAR(200) NOT NULL,
             peso_bruto NUMERIC(15,3) NOT NULL,
+            peso_cubado NUMERIC(15,3),
+            volume_m3 NUMERIC(15,4),
+            quantidade_volumes NUMERIC(15,4),
+            
+            -- Modal (RNTRC e grupo infModal em JSON)
+            rntrc VARCHAR(8),
+            dados_modal_json TEXT,
+            
+            -- Protocolo de autorização
+            protocolo_cstat VARCHAR(3),
+            data_autorizacao TIMESTAMPTZ,
+            
+            -- CT-e OS (infServico) e CT-e Simplificado (det em JSON)
+            descricao_servico TEXT,
+            entregas_json TEXT,
             
             informacoes_adicionais TEXT
         )
@@ -310,6 +743,203 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
     .execute(pool)
     .await?;
 
+    // Tabela de Documentos Referenciados
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS documentos_referenciados (
+            id SERIAL PRIMARY KEY,
+            cte_id UUID NOT NULL,
+            tipo VARCHAR(30) NOT NULL,
+            chave_acesso VARCHAR(44),
+            numero VARCHAR(20),
+            serie VARCHAR(10),
+            
+            FOREIGN KEY (cte_id) REFERENCES conhecimentos_transporte(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_docref_chave ON documentos_referenciados(chave_acesso)",
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS componentes_prestacao (
+            id SERIAL PRIMARY KEY,
+            cte_id UUID NOT NULL,
+            nome VARCHAR(15) NOT NULL,
+            valor NUMERIC(15,2) NOT NULL,
+            
+            FOREIGN KEY (cte_id) REFERENCES conhecimentos_transporte(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Tabela de Manifestos (MDF-e)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS manifestos (
+            id UUID PRIMARY KEY,
+            chave_acesso VARCHAR(44) UNIQUE NOT NULL,
+            numero VARCHAR(20) NOT NULL,
+            serie VARCHAR(10) NOT NULL,
+            data_emissao TIMESTAMPTZ NOT NULL,
+            data_inicio_viagem TIMESTAMPTZ,
+            modal VARCHAR(20) NOT NULL,
+            protocolo_autorizacao VARCHAR(50),
+            protocolo_cstat VARCHAR(3),
+            status VARCHAR(20) NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL,
+            
+            -- Emitente
+            emit_cnpj_cpf VARCHAR(14) NOT NULL,
+            emit_razao_social VARCHAR(200) NOT NULL,
+            emit_uf VARCHAR(2),
+            
+            -- Percurso (UFs de passagem separadas por vírgula)
+            uf_inicio VARCHAR(2) NOT NULL,
+            uf_fim VARCHAR(2) NOT NULL,
+            percurso VARCHAR(100),
+            
+            -- Modal rodoviário
+            rntrc VARCHAR(8),
+            placa_tracao VARCHAR(7),
+            veiculos_json TEXT,
+            condutores_json TEXT,
+            
+            -- Totais
+            valor_carga NUMERIC(15,2) NOT NULL,
+            codigo_unidade VARCHAR(2),
+            quantidade_carga NUMERIC(15,4) NOT NULL,
+            
+            informacoes_adicionais TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Documentos (CT-e/NF-e) vinculados aos municípios de descarregamento do MDF-e
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS manifesto_documentos (
+            id SERIAL PRIMARY KEY,
+            manifesto_id UUID NOT NULL,
+            municipio_codigo VARCHAR(7) NOT NULL,
+            municipio_nome VARCHAR(100) NOT NULL,
+            tipo VARCHAR(4) NOT NULL,
+            chave_acesso VARCHAR(44) NOT NULL,
+            
+            FOREIGN KEY (manifesto_id) REFERENCES manifestos(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_mdfe_doc_chave ON manifesto_documentos(chave_acesso)",
+    )
+    .execute(pool)
+    .await?;
+
+    // Eventos fiscais (cancelamento, CC-e, manifestação, desacordo)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS eventos_fiscais (
+            id UUID PRIMARY KEY,
+            chave_acesso VARCHAR(44) NOT NULL,
+            codigo VARCHAR(6) NOT NULL,
+            sequencia INTEGER NOT NULL,
+            data_evento TIMESTAMPTZ NOT NULL,
+            descricao VARCHAR(60) NOT NULL,
+            autor_cnpj_cpf VARCHAR(14),
+            justificativa TEXT,
+            correcao TEXT,
+            protocolo VARCHAR(50),
+            protocolo_cstat VARCHAR(3),
+            data_registro TIMESTAMPTZ,
+            created_at TIMESTAMPTZ NOT NULL,
+            
+            UNIQUE (chave_acesso, codigo, sequencia)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Notas Fiscais de Serviço (NFS-e Padrão Nacional e ABRASF)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS notas_servico (
+            id UUID PRIMARY KEY,
+            layout VARCHAR(10) NOT NULL,
+            chave_acesso VARCHAR(50) UNIQUE NOT NULL,
+            numero VARCHAR(20) NOT NULL,
+            codigo_verificacao VARCHAR(50),
+            data_emissao TIMESTAMPTZ NOT NULL,
+            competencia DATE,
+            codigo_municipio_emissor VARCHAR(7),
+            numero_rps VARCHAR(20),
+            serie_rps VARCHAR(10),
+            status VARCHAR(20) NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL,
+            
+            -- Prestador
+            prest_cnpj_cpf VARCHAR(14) NOT NULL,
+            prest_razao_social VARCHAR(200) NOT NULL,
+            prest_inscricao_municipal VARCHAR(20),
+            prest_uf VARCHAR(2),
+            optante_simples_nacional BOOLEAN NOT NULL,
+            
+            -- Tomador
+            toma_cnpj_cpf VARCHAR(14),
+            toma_razao_social VARCHAR(200),
+            
+            -- Serviço
+            codigo_tributacao_nacional VARCHAR(6),
+            item_lista_servico VARCHAR(5),
+            codigo_tributacao_municipal VARCHAR(20),
+            codigo_cnae VARCHAR(7),
+            discriminacao TEXT,
+            codigo_municipio_prestacao VARCHAR(7),
+            codigo_municipio_incidencia VARCHAR(7),
+            
+            -- Valores e ISS
+            valor_servicos NUMERIC(15,2) NOT NULL,
+            valor_deducoes NUMERIC(15,2) NOT NULL,
+            desconto_incondicionado NUMERIC(15,2) NOT NULL,
+            desconto_condicionado NUMERIC(15,2) NOT NULL,
+            base_calculo NUMERIC(15,2) NOT NULL,
+            aliquota_iss NUMERIC(7,4) NOT NULL,
+            valor_iss NUMERIC(15,2) NOT NULL,
+            iss_retido BOOLEAN NOT NULL,
+            valor_liquido NUMERIC(15,2) NOT NULL,
+            
+            -- Retenções federais
+            ret_pis NUMERIC(15,2) NOT NULL,
+            ret_cofins NUMERIC(15,2) NOT NULL,
+            ret_inss NUMERIC(15,2) NOT NULL,
+            ret_irrf NUMERIC(15,2) NOT NULL,
+            ret_csll NUMERIC(15,2) NOT NULL,
+            ret_outras NUMERIC(15,2) NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_nfse_prestador ON notas_servico(prest_cnpj_cpf)")
+        .execute(pool)
+        .await?;
+
     // Índices para otimização
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_nf_chave ON notas_fiscais(chave_acesso)")
         .execute(pool)
@@ -452,5 +1082,183 @@ pub async fn create_tables_postgres(pool: &PgPool) -> Result<()> {
     .execute(pool)
     .await?;
 
+    adicionar_colunas_postgres(pool).await?;
+
+    Ok(())
+}
+
+/// Colunas incluídas depois da criação de tabelas já existentes
+///
+/// `CREATE TABLE IF NOT EXISTS` não altera bancos criados antes delas; cada entrada é
+/// `(tabela, definição)` e vira um `ALTER TABLE ... ADD COLUMN` quando a coluna falta.
+const COLUNAS_SQLITE: &[(&str, &str)] = &[
+    ("notas_fiscais", "modelo TEXT NOT NULL DEFAULT '55'"),
+    ("notas_fiscais", "base_calculo_icms REAL"),
+    ("notas_fiscais", "valor_icms_desonerado REAL"),
+    ("notas_fiscais", "valor_fcp REAL"),
+    ("notas_fiscais", "base_calculo_icms_st REAL"),
+    ("notas_fiscais", "valor_icms_st REAL"),
+    ("notas_fiscais", "valor_frete REAL"),
+    ("notas_fiscais", "valor_seguro REAL"),
+    ("notas_fiscais", "valor_desconto REAL"),
+    ("notas_fiscais", "valor_ii REAL"),
+    ("notas_fiscais", "outras_despesas REAL"),
+    ("notas_fiscais", "valor_total_tributos REAL"),
+    ("notas_fiscais", "modalidade_frete TEXT"),
+    ("notas_fiscais", "transp_cnpj_cpf TEXT"),
+    ("notas_fiscais", "transp_razao_social TEXT"),
+    ("notas_fiscais", "veiculo_placa TEXT"),
+    ("notas_fiscais", "peso_liquido REAL"),
+    ("notas_fiscais", "peso_bruto REAL"),
+    ("notas_fiscais", "protocolo_cstat TEXT"),
+    ("notas_fiscais", "data_autorizacao DATETIME"),
+    ("notas_fiscais", "qr_code TEXT"),
+    ("notas_fiscais", "url_chave TEXT"),
+    ("itens_nota_fiscal", "icms_cst TEXT"),
+    ("itens_nota_fiscal", "icms_base_calculo REAL"),
+    ("itens_nota_fiscal", "icms_aliquota REAL"),
+    ("itens_nota_fiscal", "icms_valor REAL"),
+    ("itens_nota_fiscal", "icms_st_base_calculo REAL"),
+    ("itens_nota_fiscal", "icms_st_valor REAL"),
+    ("itens_nota_fiscal", "icms_fcp_valor REAL"),
+    ("itens_nota_fiscal", "ipi_cst TEXT"),
+    ("itens_nota_fiscal", "ipi_aliquota REAL"),
+    ("itens_nota_fiscal", "ipi_valor REAL"),
+    ("itens_nota_fiscal", "pis_cst TEXT"),
+    ("itens_nota_fiscal", "pis_valor REAL"),
+    ("itens_nota_fiscal", "cofins_cst TEXT"),
+    ("itens_nota_fiscal", "cofins_valor REAL"),
+    ("itens_nota_fiscal", "impostos_json TEXT"),
+    ("conhecimentos_transporte", "modelo TEXT NOT NULL DEFAULT '57'"),
+    ("conhecimentos_transporte", "tipo_cte TEXT NOT NULL DEFAULT '0'"),
+    ("conhecimentos_transporte", "exped_cnpj_cpf TEXT"),
+    ("conhecimentos_transporte", "exped_razao_social TEXT"),
+    ("conhecimentos_transporte", "exped_municipio TEXT"),
+    ("conhecimentos_transporte", "exped_uf TEXT"),
+    ("conhecimentos_transporte", "receb_cnpj_cpf TEXT"),
+    ("conhecimentos_transporte", "receb_razao_social TEXT"),
+    ("conhecimentos_transporte", "receb_municipio TEXT"),
+    ("conhecimentos_transporte", "receb_uf TEXT"),
+    ("conhecimentos_transporte", "toma_tipo TEXT"),
+    ("conhecimentos_transporte", "toma_cnpj_cpf TEXT"),
+    ("conhecimentos_transporte", "toma_razao_social TEXT"),
+    ("conhecimentos_transporte", "toma_municipio TEXT"),
+    ("conhecimentos_transporte", "toma_uf TEXT"),
+    ("conhecimentos_transporte", "icms_cst TEXT"),
+    ("conhecimentos_transporte", "icms_base_calculo REAL"),
+    ("conhecimentos_transporte", "icms_aliquota REAL"),
+    ("conhecimentos_transporte", "icms_valor REAL"),
+    ("conhecimentos_transporte", "valor_total_tributos REAL"),
+    ("conhecimentos_transporte", "impostos_json TEXT"),
+    ("conhecimentos_transporte", "peso_cubado REAL"),
+    ("conhecimentos_transporte", "volume_m3 REAL"),
+    ("conhecimentos_transporte", "quantidade_volumes REAL"),
+    ("conhecimentos_transporte", "rntrc TEXT"),
+    ("conhecimentos_transporte", "dados_modal_json TEXT"),
+    ("conhecimentos_transporte", "protocolo_cstat TEXT"),
+    ("conhecimentos_transporte", "data_autorizacao DATETIME"),
+    ("conhecimentos_transporte", "descricao_servico TEXT"),
+    ("conhecimentos_transporte", "entregas_json TEXT"),
+];
+
+const COLUNAS_POSTGRES: &[(&str, &str)] = &[
+    ("notas_fiscais", "modelo VARCHAR(2) NOT NULL DEFAULT '55'"),
+    ("notas_fiscais", "base_calculo_icms NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_icms_desonerado NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_fcp NUMERIC(15,2)"),
+    ("notas_fiscais", "base_calculo_icms_st NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_icms_st NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_frete NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_seguro NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_desconto NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_ii NUMERIC(15,2)"),
+    ("notas_fiscais", "outras_despesas NUMERIC(15,2)"),
+    ("notas_fiscais", "valor_total_tributos NUMERIC(15,2)"),
+    ("notas_fiscais", "modalidade_frete VARCHAR(30)"),
+    ("notas_fiscais", "transp_cnpj_cpf VARCHAR(14)"),
+    ("notas_fiscais", "transp_razao_social VARCHAR(200)"),
+    ("notas_fiscais", "veiculo_placa VARCHAR(8)"),
+    ("notas_fiscais", "peso_liquido NUMERIC(15,3)"),
+    ("notas_fiscais", "peso_bruto NUMERIC(15,3)"),
+    ("notas_fiscais", "protocolo_cstat VARCHAR(3)"),
+    ("notas_fiscais", "data_autorizacao TIMESTAMPTZ"),
+    ("notas_fiscais", "qr_code TEXT"),
+    ("notas_fiscais", "url_chave VARCHAR(85)"),
+    ("itens_nota_fiscal", "icms_cst VARCHAR(3)"),
+    ("itens_nota_fiscal", "icms_base_calculo NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "icms_aliquota NUMERIC(7,4)"),
+    ("itens_nota_fiscal", "icms_valor NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "icms_st_base_calculo NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "icms_st_valor NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "icms_fcp_valor NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "ipi_cst VARCHAR(2)"),
+    ("itens_nota_fiscal", "ipi_aliquota NUMERIC(7,4)"),
+    ("itens_nota_fiscal", "ipi_valor NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "pis_cst VARCHAR(2)"),
+    ("itens_nota_fiscal", "pis_valor NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "cofins_cst VARCHAR(2)"),
+    ("itens_nota_fiscal", "cofins_valor NUMERIC(15,2)"),
+    ("itens_nota_fiscal", "impostos_json TEXT"),
+    ("conhecimentos_transporte", "modelo VARCHAR(2) NOT NULL DEFAULT '57'"),
+    ("conhecimentos_transporte", "tipo_cte VARCHAR(1) NOT NULL DEFAULT '0'"),
+    ("conhecimentos_transporte", "exped_cnpj_cpf VARCHAR(14)"),
+    ("conhecimentos_transporte", "exped_razao_social VARCHAR(200)"),
+    ("conhecimentos_transporte", "exped_municipio VARCHAR(100)"),
+    ("conhecimentos_transporte", "exped_uf VARCHAR(2)"),
+    ("conhecimentos_transporte", "receb_cnpj_cpf VARCHAR(14)"),
+    ("conhecimentos_transporte", "receb_razao_social VARCHAR(200)"),
+    ("conhecimentos_transporte", "receb_municipio VARCHAR(100)"),
+    ("conhecimentos_transporte", "receb_uf VARCHAR(2)"),
+    ("conhecimentos_transporte", "toma_tipo VARCHAR(20)"),
+    ("conhecimentos_transporte", "toma_cnpj_cpf VARCHAR(14)"),
+    ("conhecimentos_transporte", "toma_razao_social VARCHAR(200)"),
+    ("conhecimentos_transporte", "toma_municipio VARCHAR(100)"),
+    ("conhecimentos_transporte", "toma_uf VARCHAR(2)"),
+    ("conhecimentos_transporte", "icms_cst VARCHAR(3)"),
+    ("conhecimentos_transporte", "icms_base_calculo NUMERIC(15,2)"),
+    ("conhecimentos_transporte", "icms_aliquota NUMERIC(5,2)"),
+    ("conhecimentos_transporte", "icms_valor NUMERIC(15,2)"),
+    ("conhecimentos_transporte", "valor_total_tributos NUMERIC(15,2)"),
+    ("conhecimentos_transporte", "impostos_json TEXT"),
+    ("conhecimentos_transporte", "peso_cubado NUMERIC(15,3)"),
+    ("conhecimentos_transporte", "volume_m3 NUMERIC(15,4)"),
+    ("conhecimentos_transporte", "quantidade_volumes NUMERIC(15,4)"),
+    ("conhecimentos_transporte", "rntrc VARCHAR(8)"),
+    ("conhecimentos_transporte", "dados_modal_json TEXT"),
+    ("conhecimentos_transporte", "protocolo_cstat VARCHAR(3)"),
+    ("conhecimentos_transporte", "data_autorizacao TIMESTAMPTZ"),
+    ("conhecimentos_transporte", "descricao_servico TEXT"),
+    ("conhecimentos_transporte", "entregas_json TEXT"),
+];
+
+/// Acrescenta as colunas de `COLUNAS_SQLITE` que ainda não existem
+async fn adicionar_colunas_sqlite(pool: &Pool<Sqlite>) -> Result<()> {
+    for (tabela, definicao) in COLUNAS_SQLITE {
+        let coluna = definicao.split_whitespace().next().unwrap_or_default();
+        let existe: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+                .bind(tabela)
+                .bind(coluna)
+                .fetch_one(pool)
+                .await?;
+        if existe == 0 {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {}", tabela, definicao))
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Acrescenta as colunas de `COLUNAS_POSTGRES` que ainda não existem
+async fn adicionar_colunas_postgres(pool: &PgPool) -> Result<()> {
+    for (tabela, definicao) in COLUNAS_POSTGRES {
+        sqlx::query(&format!(
+            "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {}",
+            tabela, definicao
+        ))
+        .execute(pool)
+        .await?;
+    }
     Ok(())
 }
@@ -37,6 +37,12 @@ pub enum GeolocationError {
 
     #[error("Documento já existente (chave duplicada): {0}")]
     DuplicateDocument(String),
+
+    #[error("Documento sem autorização de uso: {0}")]
+    DocumentNotAuthorized(String),
+
+    #[error("Tabela de referência inválida: {0}")]
+    InvalidReferenceTable(String),
 }
 
 impl From<quick_xml::Error> for GeolocationError {
@@ -14,8 +14,14 @@ pub mod wasm;
 
 pub use database::{Database, DatabasePool};
 pub use error::{GeolocationError, Result};
-pub use models::{ConhecimentoTransporte, DocumentType, NotaFiscal};
-pub use parsers::{cte::CTeParser, nfe::NFeParser, FiscalDocumentParser};
+pub use models::{
+    ChaveAcesso, ConhecimentoTransporte, DocumentType, EventoFiscal, ManifestoDocumentos,
+    NotaFiscal, NotaFiscalServico,
+};
+pub use parsers::{
+    cte::CTeParser, evento::EventoParser, mdfe::MDFeParser, nfe::NFeParser, nfse::NFSeParser,
+    FiscalDocumentParser,
+};
 
 /// Versão do software
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -43,17 +49,21 @@ pub async fn process_document_content(
         )
     })?;
 
-    // Valida o documento fiscalmente ANTES de processar
-    let validation = validators::FiscalValidator::validate_document(xml_content, &doc_type);
-
     let result = match doc_type.as_str() {
-        "NFe" => {
+        "NFe" | "NFCe" => {
             let parser = NFeParser::new();
-            let nf = parser.parse_string(xml_content)?;
+            let mut nf = parser.parse_string(xml_content)?;
+            ensure_authorized(nf.protocolo.as_ref(), &nf.chave_acesso)?;
+            let validation = validators::FiscalValidator::validate_nota_fiscal(&nf);
             let chave = nf.chave_acesso.clone();
+            let document_type = nf.document_type();
             let already = mongo.find_nota_fiscal_by_chave(&chave).await?;
             let mut duplicate = false;
             if already.is_none() {
+                // Eventos recebidos antes do documento
+                for evento in mongo.find_eventos_by_chave(&chave).await? {
+                    nf.aplicar_evento(&evento);
+                }
                 if let Err(e) = mongo.insert_nota_fiscal(&nf).await {
                     // Tratar erro de duplicata do MongoDB
                     if e.to_string().contains("duplicate") {
@@ -66,7 +76,7 @@ pub async fn process_document_content(
                 duplicate = true;
             }
             if duplicate {
-                tracing::info!(chave = %chave, "NF-e duplicada detectada");
+                tracing::info!(chave = %chave, tipo = %document_type, "Nota fiscal duplicada detectada");
                 if let Some(counter) = crate::utils::metrics::docs_duplicate_counter() {
                     counter.inc();
                 }
@@ -76,25 +86,31 @@ pub async fn process_document_content(
                 }
             }
             ProcessingResult {
-                document_type: DocumentType::NotaFiscal,
                 chave_acesso: chave,
                 success: true,
                 message: if duplicate {
-                    "NF-e já existente".to_string()
+                    format!("{} já existente", document_type)
                 } else {
-                    "NF-e processada com sucesso".to_string()
+                    format!("{} processada com sucesso", document_type)
                 },
+                document_type,
                 validation: Some(validation),
                 duplicate,
             }
         }
-        "CTe" => {
+        "CTe" | "CTeOS" | "CTeSimp" => {
             let parser = CTeParser::new();
-            let cte = parser.parse_string(xml_content)?;
+            let mut cte = parser.parse_string(xml_content)?;
+            ensure_authorized(cte.protocolo.as_ref(), &cte.chave_acesso)?;
+            let validation = validators::FiscalValidator::validate_conhecimento(&cte);
             let chave = cte.chave_acesso.clone();
+            let document_type = cte.document_type();
             let already = mongo.find_conhecimento_by_chave(&chave).await?;
             let mut duplicate = false;
             if already.is_none() {
+                for evento in mongo.find_eventos_by_chave(&chave).await? {
+                    cte.aplicar_evento(&evento);
+                }
                 if let Err(e) = mongo.insert_conhecimento_transporte(&cte).await {
                     // Tratar erro de duplicata do MongoDB
                     if e.to_string().contains("duplicate") {
@@ -107,25 +123,152 @@ pub async fn process_document_content(
                 duplicate = true;
             }
             if duplicate {
-                tracing::info!(chave = %chave, "CT-e duplicado detectado");
+                tracing::info!(chave = %chave, tipo = %document_type, "CT-e duplicado detectado");
+                if let Some(counter) = crate::utils::metrics::docs_duplicate_counter() {
+                    counter.inc();
+                }
+            } else {
+                if let Some(counter) = crate::utils::metrics::docs_processed_counter() {
+                    counter.inc();
+                }
+            }
+            ProcessingResult {
+                chave_acesso: chave,
+                success: true,
+                message: if duplicate {
+                    format!("{} já existente", document_type)
+                } else {
+                    format!("{} processado com sucesso", document_type)
+                },
+                document_type,
+                validation: Some(validation),
+                duplicate,
+            }
+        }
+        "MDFe" => {
+            let parser = MDFeParser::new();
+            let mdfe = parser.parse_string(xml_content)?;
+            ensure_authorized(mdfe.protocolo.as_ref(), &mdfe.chave_acesso)?;
+            let validation = validators::FiscalValidator::validate_manifesto(&mdfe);
+            let chave = mdfe.chave_acesso.clone();
+            let already = mongo.find_manifesto_by_chave(&chave).await?;
+            let mut duplicate = false;
+            if already.is_none() {
+                if let Err(e) = mongo.insert_manifesto(&mdfe).await {
+                    // Tratar erro de duplicata do MongoDB
+                    if e.to_string().contains("duplicate") {
+                        duplicate = true;
+                    } else {
+                        return Err(GeolocationError::DatabaseError(e.to_string()));
+                    }
+                }
+            } else {
+                duplicate = true;
+            }
+            if duplicate {
+                tracing::info!(chave = %chave, "MDF-e duplicado detectado");
+                if let Some(counter) = crate::utils::metrics::docs_duplicate_counter() {
+                    counter.inc();
+                }
+            } else if let Some(counter) = crate::utils::metrics::docs_processed_counter() {
+                counter.inc();
+            }
+            ProcessingResult {
+                document_type: DocumentType::ManifestoDocumentos,
+                chave_acesso: chave,
+                success: true,
+                message: if duplicate {
+                    "MDF-e já existente".to_string()
+                } else {
+                    "MDF-e processado com sucesso".to_string()
+                },
+                validation: Some(validation),
+                duplicate,
+            }
+        }
+        "NFSe" => {
+            // NFS-e não tem protocolo de autorização: a nota já é emitida pelo município
+            let parser = NFSeParser::new();
+            let nfse = parser.parse_string(xml_content)?;
+            let validation = validators::FiscalValidator::validate_nota_servico(&nfse);
+            let chave = nfse.chave_acesso.clone();
+            let already = mongo.find_nota_servico_by_chave(&chave).await?;
+            let mut duplicate = false;
+            if already.is_none() {
+                if let Err(e) = mongo.insert_nota_servico(&nfse).await {
+                    // Tratar erro de duplicata do MongoDB
+                    if e.to_string().contains("duplicate") {
+                        duplicate = true;
+                    } else {
+                        return Err(GeolocationError::DatabaseError(e.to_string()));
+                    }
+                }
+            } else {
+                duplicate = true;
+            }
+            if duplicate {
+                tracing::info!(chave = %chave, "NFS-e duplicada detectada");
+                if let Some(counter) = crate::utils::metrics::docs_duplicate_counter() {
+                    counter.inc();
+                }
+            } else if let Some(counter) = crate::utils::metrics::docs_processed_counter() {
+                counter.inc();
+            }
+            ProcessingResult {
+                document_type: DocumentType::NotaFiscalServico,
+                chave_acesso: chave,
+                success: true,
+                message: if duplicate {
+                    "NFS-e já existente".to_string()
+                } else {
+                    "NFS-e processada com sucesso".to_string()
+                },
+                validation: Some(validation),
+                duplicate,
+            }
+        }
+        "Evento" => {
+            let parser = EventoParser::new();
+            let evento = parser.parse_string(xml_content)?;
+            if !evento.registrado() {
+                return Err(GeolocationError::DocumentNotAuthorized(format!(
+                    "{} (evento {} não registrado)",
+                    evento.chave_acesso, evento.codigo
+                )));
+            }
+            let chave = evento.chave_acesso.clone();
+            let duplicate = mongo
+                .find_evento(&chave, &evento.codigo, evento.sequencia)
+                .await?
+                .is_some();
+            if duplicate {
+                tracing::info!(chave = %chave, evento = %evento.codigo, "Evento duplicado detectado");
                 if let Some(counter) = crate::utils::metrics::docs_duplicate_counter() {
                     counter.inc();
                 }
             } else {
+                mongo
+                    .insert_evento(&evento)
+                    .await
+                    .map_err(|e| GeolocationError::DatabaseError(e.to_string()))?;
+                if !mongo.aplicar_evento(&evento).await? {
+                    tracing::warn!(chave = %chave, evento = %evento.codigo, "Evento de documento ainda não processado; será aplicado na chegada do documento");
+                }
                 if let Some(counter) = crate::utils::metrics::docs_processed_counter() {
                     counter.inc();
                 }
             }
             ProcessingResult {
-                document_type: DocumentType::ConhecimentoTransporte,
+                document_type: DocumentType::EventoFiscal,
                 chave_acesso: chave,
                 success: true,
                 message: if duplicate {
-                    "CT-e já existente".to_string()
+                    format!("Evento {} já existente", evento.codigo)
                 } else {
-                    "CT-e processado com sucesso".to_string()
+                    format!("Evento {} ({}) registrado", evento.codigo, evento.descricao)
                 },
-                validation: Some(validation.clone()),
+                // A validação fiscal é do documento, não do evento
+                validation: None,
                 duplicate,
             }
         }
@@ -153,6 +296,26 @@ pub async fn process_document_content(
     Ok(result)
 }
 
+/// Rejeita documentos sem protocolo, com protocolo de outra chave ou cujo `cStat` não seja de uso
+/// autorizado
+fn ensure_authorized(protocolo: Option<&models::ProtocoloAutorizacao>, chave: &str) -> Result<()> {
+    match protocolo {
+        Some(p) if p.chave_acesso != chave => Err(GeolocationError::DocumentNotAuthorized(format!(
+            "{} (protocolo pertence à chave {})",
+            chave, p.chave_acesso
+        ))),
+        Some(p) if p.autorizado() => Ok(()),
+        Some(p) => Err(GeolocationError::DocumentNotAuthorized(format!(
+            "{} (cStat {} - {})",
+            chave, p.codigo_status, p.motivo
+        ))),
+        None => Err(GeolocationError::DocumentNotAuthorized(format!(
+            "{} (protocolo de autorização ausente)",
+            chave
+        ))),
+    }
+}
+
 /// Resultado do processamento de um documento
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProcessingResult {
@@ -163,3 +326,27 @@ pub struct ProcessingResult {
     pub validation: Option<validators::ValidationResult>,
     pub duplicate: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProtocoloAutorizacao;
+
+    #[test]
+    fn test_ensure_authorized() {
+        let chave = "35210112345678901234567890123456789012345678";
+        let mut protocolo = ProtocoloAutorizacao {
+            chave_acesso: chave.to_string(),
+            codigo_status: "100".to_string(),
+            ..Default::default()
+        };
+        assert!(ensure_authorized(Some(&protocolo), chave).is_ok());
+        assert!(ensure_authorized(None, chave).is_err());
+
+        // Protocolo autorizado de outra nota
+        protocolo.chave_acesso = "35210112345678000190550010000123451123456788".to_string();
+        let erro = ensure_authorized(Some(&protocolo), chave).unwrap_err();
+        assert!(matches!(erro, GeolocationError::DocumentNotAuthorized(_)));
+        assert!(erro.to_string().contains("protocolo pertence à chave"));
+    }
+}
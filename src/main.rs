@@ -1,6 +1,6 @@
 use clap::{Parser, Subcommand};
 use colored::*;
-use geolocation::{Database, process_document_file, APP_NAME, VERSION};
+use geolocation::{ChaveAcesso, Database, process_document_file, APP_NAME, VERSION};
 use geolocation::database::mongodb::MongoDB;
 use log::{error, info};
 use std::path::PathBuf;
@@ -44,6 +44,12 @@ enum Commands {
         chave: Option<String>,
     },
     
+    /// Valida o dígito verificador e decompõe uma chave de acesso
+    Chave {
+        /// Chave de acesso de 44 dígitos
+        chave: String,
+    },
+    
     /// Exporta dados do banco de dados
     Export {
         /// Formato de exportação (json, csv)
@@ -112,6 +118,7 @@ async fn main() {
         }
         
         Commands::Process { doc_type } => {
+            conferir_tipi();
             let mongo = connect_mongo().await;
             match doc_type {
                 ProcessType::Nfe { file } => {
@@ -134,6 +141,25 @@ async fn main() {
             println!("Tipo: {}, Chave: {:?}", tipo, chave);
         }
         
+        Commands::Chave { chave } => match ChaveAcesso::parse(chave.trim()) {
+            Ok(chave) => {
+                println!("{} {}", "✓".green(), "Chave de acesso válida".green());
+                println!("  {} {}", "UF:".bright_black(), chave.uf);
+                println!("  {} {:02}/{}", "Emissão:".bright_black(), chave.mes(), chave.ano());
+                println!("  {} {}", "CNPJ/CPF:".bright_black(), chave.cnpj_cpf);
+                println!("  {} {}", "Modelo:".bright_black(), chave.modelo);
+                println!("  {} {}", "Série:".bright_black(), chave.serie);
+                println!("  {} {}", "Número:".bright_black(), chave.numero);
+                println!("  {} {}", "Tipo de emissão:".bright_black(), chave.tipo_emissao);
+                println!("  {} {}", "Código numérico:".bright_black(), chave.codigo_numerico);
+                println!("  {} {}", "DV:".bright_black(), chave.digito_verificador);
+            }
+            Err(e) => {
+                eprintln!("{} {}", "✗".red(), format!("Erro: {}", e).red());
+                std::process::exit(1);
+            }
+        },
+        
         Commands::Export { format, output } => {
             println!("{}", "Funcionalidade de exportação em desenvolvimento...".yellow());
             println!("Formato: {}, Saída: {}", format, output);
@@ -219,6 +245,18 @@ async fn connect_database(cli: &Cli) -> Database {
     }
 }
 
+/// Sem a TIPI completa o validador não aponta NCM inexistente
+fn conferir_tipi() {
+    let Err(e) = geolocation::validators::TabelaNcm::padrao().exigir_completa() else {
+        return;
+    };
+    if geolocation::validators::TabelaNcm::obrigatoria() {
+        eprintln!("{} {}", "✗".red(), format!("Erro: {}", e).red());
+        std::process::exit(1);
+    }
+    println!("{} {}", "⚠".yellow(), format!("{}; NCM_NOT_FOUND desativado", e).yellow());
+}
+
 async fn connect_mongo() -> MongoDB {
     let uri = std::env::var("MONGODB_URI").unwrap_or_else(|_| {
         eprintln!("{} {}", "✗".red(), "Variável MONGODB_URI não definida".red());
@@ -0,0 +1,145 @@
+use crate::error::{GeolocationError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Chave de acesso de 44 dígitos de NF-e, NFC-e, CT-e, CT-e OS e MDF-e
+///
+/// Layout: cUF (2) + AAMM (4) + CNPJ/CPF (14) + modelo (2) + série (3) + número (9)
+/// + tpEmis (1) + cNF (8) + DV (1).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChaveAcesso {
+    chave: String,
+    /// Código IBGE da UF do emitente
+    pub uf: String,
+    /// Ano e mês de emissão (AAMM)
+    pub ano_mes: String,
+    /// CNPJ do emitente ou CPF precedido de três zeros
+    pub cnpj_cpf: String,
+    pub modelo: String,
+    pub serie: u32,
+    pub numero: u32,
+    /// Forma de emissão (`tpEmis`)
+    pub tipo_emissao: u8,
+    /// Código numérico (`cNF`/`cCT`/`cMDF`)
+    pub codigo_numerico: String,
+    pub digito_verificador: u8,
+}
+
+impl ChaveAcesso {
+    /// Decompõe a chave, exigindo 44 dígitos e dígito verificador correto
+    pub fn parse(chave: &str) -> Result<Self> {
+        if chave.len() != 44 || !chave.chars().all(|c| c.is_ascii_digit()) {
+            return Err(GeolocationError::InvalidAccessKey(format!(
+                "{} (deve ter 44 dígitos numéricos)",
+                chave
+            )));
+        }
+        let esperado = Self::calcular_digito(&chave[..43]).unwrap_or_default();
+        let informado = chave.as_bytes()[43] - b'0';
+        if esperado != informado {
+            return Err(GeolocationError::InvalidAccessKey(format!(
+                "{} (dígito verificador {} difere do calculado {})",
+                chave, informado, esperado
+            )));
+        }
+
+        Ok(ChaveAcesso {
+            chave: chave.to_string(),
+            uf: chave[0..2].to_string(),
+            ano_mes: chave[2..6].to_string(),
+            cnpj_cpf: chave[6..20].to_string(),
+            modelo: chave[20..22].to_string(),
+            serie: chave[22..25].parse().unwrap_or_default(),
+            numero: chave[25..34].parse().unwrap_or_default(),
+            tipo_emissao: chave.as_bytes()[34] - b'0',
+            codigo_numerico: chave[35..43].to_string(),
+            digito_verificador: informado,
+        })
+    }
+
+    /// Dígito verificador em módulo 11 sobre os 43 primeiros dígitos
+    ///
+    /// Pesos de 2 a 9 aplicados da direita para a esquerda; restos 0 e 1 resultam em DV 0.
+    pub fn calcular_digito(base: &str) -> Option<u8> {
+        if base.len() != 43 || !base.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let soma: u32 = base
+            .bytes()
+            .rev()
+            .zip((2..=9).cycle())
+            .map(|(d, peso)| (d - b'0') as u32 * peso)
+            .sum();
+        let digito = 11 - soma % 11;
+        Some(if digito >= 10 { 0 } else { digito as u8 })
+    }
+
+    /// Verifica formato e dígito verificador sem montar a estrutura
+    pub fn valida(chave: &str) -> bool {
+        Self::parse(chave).is_ok()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.chave
+    }
+
+    /// Ano de emissão com quatro dígitos
+    pub fn ano(&self) -> u16 {
+        2000 + self.ano_mes[0..2].parse::<u16>().unwrap_or_default()
+    }
+
+    pub fn mes(&self) -> u8 {
+        self.ano_mes[2..4].parse().unwrap_or_default()
+    }
+}
+
+impl FromStr for ChaveAcesso {
+    type Err = GeolocationError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for ChaveAcesso {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.chave)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompoe_chave() {
+        let chave = ChaveAcesso::parse("35210112345678000190570010000000021123456789").unwrap();
+        assert_eq!(chave.uf, "35");
+        assert_eq!((chave.ano(), chave.mes()), (2021, 1));
+        assert_eq!(chave.cnpj_cpf, "12345678000190");
+        assert_eq!(chave.modelo, "57");
+        assert_eq!((chave.serie, chave.numero), (1, 2));
+        assert_eq!(chave.tipo_emissao, 1);
+        assert_eq!(chave.codigo_numerico, "12345678");
+        assert_eq!(chave.digito_verificador, 9);
+        assert_eq!(chave.to_string(), "35210112345678000190570010000000021123456789");
+    }
+
+    #[test]
+    fn test_digito_verificador() {
+        // Exemplo do Manual de Orientação do Contribuinte
+        assert_eq!(
+            ChaveAcesso::calcular_digito("3508059999909091027055001000000001518005127"),
+            Some(3)
+        );
+        assert!(ChaveAcesso::valida("35080599999090910270550010000000015180051273"));
+        assert!(!ChaveAcesso::valida("35080599999090910270550010000000015180051274"));
+        assert!(!ChaveAcesso::valida("3508059999909091027055001000000001518005127X"));
+        assert!(!ChaveAcesso::valida("123"));
+        assert_eq!(ChaveAcesso::calcular_digito("123"), None);
+
+        let erro = ChaveAcesso::parse("35080599999090910270550010000000015180051274").unwrap_err();
+        assert!(erro.to_string().contains("calculado 3"));
+    }
+}
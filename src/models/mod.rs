@@ -1,7 +1,11 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+mod chave_acesso;
+
+pub use chave_acesso::ChaveAcesso;
+
 /// Tipos de documentos fiscais suportados
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DocumentType {
@@ -9,15 +13,48 @@ pub enum DocumentType {
     NotaFiscal,
     #[serde(rename = "CTe")]
     ConhecimentoTransporte,
+    #[serde(rename = "NFCe")]
+    NotaFiscalConsumidor,
+    #[serde(rename = "MDFe")]
+    ManifestoDocumentos,
+    #[serde(rename = "CTeOS")]
+    ConhecimentoTransporteOS,
+    #[serde(rename = "CTeSimp")]
+    ConhecimentoTransporteSimplificado,
+    #[serde(rename = "Evento")]
+    EventoFiscal,
+    #[serde(rename = "NFSe")]
+    NotaFiscalServico,
+}
+
+impl DocumentType {
+    /// Código curto usado na detecção, na API e nos filtros de listagem
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            DocumentType::NotaFiscal => "NFe",
+            DocumentType::ConhecimentoTransporte => "CTe",
+            DocumentType::NotaFiscalConsumidor => "NFCe",
+            DocumentType::ManifestoDocumentos => "MDFe",
+            DocumentType::ConhecimentoTransporteOS => "CTeOS",
+            DocumentType::ConhecimentoTransporteSimplificado => "CTeSimp",
+            DocumentType::EventoFiscal => "Evento",
+            DocumentType::NotaFiscalServico => "NFSe",
+        }
+    }
 }
 
 /// Status do processamento do documento
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ProcessingStatus {
+    #[default]
     Pending,
     Processing,
     Completed,
     Failed,
+    /// Documento sem protocolo de autorização ou com `cStat` diferente de autorizado
+    Unauthorized,
+    /// Documento cancelado por evento registrado (110111/110112)
+    Cancelled,
 }
 
 /// Estrutura base para Nota Fiscal Eletrônica (NF-e)
@@ -27,18 +64,398 @@ pub struct NotaFiscal {
     pub chave_acesso: String,
     pub numero: String,
     pub serie: String,
+    #[serde(default)]
+    pub modelo: ModeloNotaFiscal,
+    #[serde(default)]
+    pub identificacao: IdentificacaoEmissao,
+    /// `indPres` = 1: operação presencial no estabelecimento
+    #[serde(default)]
+    pub presencial: bool,
     pub data_emissao: DateTime<Utc>,
     pub tipo_nota: TipoNota,
     pub emitente: Participante,
     pub destinatario: Participante,
     pub itens: Vec<ItemNota>,
     pub totais: Totais,
+    #[serde(default)]
+    pub transporte: TransporteNota,
+    #[serde(default)]
+    pub pagamento: PagamentoNota,
+    #[serde(default)]
+    pub cobranca: CobrancaNota,
     pub informacoes_adicionais: Option<String>,
+    /// Grupo `infNFeSupl` (somente NFC-e)
+    #[serde(default)]
+    pub informacoes_suplementares: Option<InformacoesSuplementares>,
     pub protocolo_autorizacao: Option<String>,
+    #[serde(default)]
+    pub protocolo: Option<ProtocoloAutorizacao>,
     pub status: ProcessingStatus,
+    /// Alterações de status por eventos (cancelamento, CC-e, manifestação...)
+    #[serde(default)]
+    pub historico_status: Vec<HistoricoStatus>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl NotaFiscal {
+    pub fn aplicar_evento(&mut self, evento: &EventoFiscal) {
+        evento.aplicar(&mut self.status, &mut self.historico_status);
+    }
+    
+    pub fn document_type(&self) -> DocumentType {
+        match self.modelo {
+            ModeloNotaFiscal::NFe => DocumentType::NotaFiscal,
+            ModeloNotaFiscal::NFCe => DocumentType::NotaFiscalConsumidor,
+        }
+    }
+}
+
+/// Modelo da Nota Fiscal (`mod` do `ide`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ModeloNotaFiscal {
+    /// Modelo 55 - NF-e
+    #[default]
+    NFe,
+    /// Modelo 65 - NFC-e
+    NFCe,
+}
+
+impl ModeloNotaFiscal {
+    pub fn from_codigo(codigo: &str) -> Option<Self> {
+        match codigo {
+            "55" => Some(ModeloNotaFiscal::NFe),
+            "65" => Some(ModeloNotaFiscal::NFCe),
+            _ => None,
+        }
+    }
+    
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            ModeloNotaFiscal::NFe => "55",
+            ModeloNotaFiscal::NFCe => "65",
+        }
+    }
+}
+
+/// Campos do `ide` que compõem a chave de acesso (NF-e, CT-e e MDF-e)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdentificacaoEmissao {
+    /// Código IBGE da UF do emitente (`cUF`)
+    pub codigo_uf: String,
+    /// Forma de emissão (`tpEmis`)
+    pub tipo_emissao: String,
+    /// Código numérico (`cNF`/`cCT`/`cMDF`)
+    pub codigo_numerico: String,
+}
+
+/// Informações Suplementares da NFC-e (`infNFeSupl`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InformacoesSuplementares {
+    pub qr_code: String,
+    pub url_chave: String,
+}
+
+/// Transporte da NF-e (grupo `transp`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransporteNota {
+    pub modalidade_frete: ModalidadeFrete,
+    pub transportadora: Option<Participante>,
+    pub veiculo: Option<VeiculoTransporte>,
+    pub reboques: Vec<VeiculoTransporte>,
+    pub volumes: Vec<VolumeTransportado>,
+}
+
+impl TransporteNota {
+    /// Soma do peso bruto (kg) declarado nos volumes
+    pub fn peso_bruto_total(&self) -> f64 {
+        self.volumes.iter().filter_map(|v| v.peso_bruto).sum()
+    }
+    
+    /// Soma do peso líquido (kg) declarado nos volumes
+    pub fn peso_liquido_total(&self) -> f64 {
+        self.volumes.iter().filter_map(|v| v.peso_liquido).sum()
+    }
+}
+
+/// Modalidade do Frete (`modFrete`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ModalidadeFrete {
+    /// 0 - Contratação por conta do remetente (CIF)
+    Remetente,
+    /// 1 - Contratação por conta do destinatário (FOB)
+    Destinatario,
+    /// 2 - Contratação por conta de terceiros
+    Terceiros,
+    /// 3 - Transporte próprio por conta do remetente
+    ProprioRemetente,
+    /// 4 - Transporte próprio por conta do destinatário
+    ProprioDestinatario,
+    /// 9 - Sem ocorrência de transporte
+    #[default]
+    SemFrete,
+}
+
+/// Veículo de Transporte (`veicTransp` ou `reboque`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VeiculoTransporte {
+    pub placa: String,
+    pub uf: Option<String>,
+    pub rntc: Option<String>,
+}
+
+/// Volume Transportado (`vol`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VolumeTransportado {
+    pub quantidade: Option<f64>,
+    pub especie: Option<String>,
+    pub marca: Option<String>,
+    pub numeracao: Option<String>,
+    pub peso_liquido: Option<f64>,
+    pub peso_bruto: Option<f64>,
+    pub lacres: Vec<String>,
+}
+
+/// Pagamento da NF-e (grupo `pag`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PagamentoNota {
+    pub formas: Vec<FormaPagamento>,
+    pub valor_troco: f64,
+}
+
+/// Forma de Pagamento (`detPag`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FormaPagamento {
+    pub meio: MeioPagamento,
+    /// Descrição do meio de pagamento quando `tPag` = 99
+    pub descricao: Option<String>,
+    pub valor: f64,
+    /// `indPag` = 1 (pagamento a prazo)
+    pub a_prazo: bool,
+    pub cartao: Option<CartaoPagamento>,
+}
+
+/// Meio de Pagamento (`tPag`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MeioPagamento {
+    Dinheiro,
+    Cheque,
+    CartaoCredito,
+    CartaoDebito,
+    CreditoLoja,
+    ValeAlimentacao,
+    ValeRefeicao,
+    ValePresente,
+    ValeCombustivel,
+    BoletoBancario,
+    DepositoBancario,
+    Pix,
+    TransferenciaBancaria,
+    ProgramaFidelidade,
+    SemPagamento,
+    #[default]
+    Outros,
+}
+
+impl MeioPagamento {
+    /// Converte o código `tPag` do leiaute da NF-e
+    pub fn from_codigo(codigo: &str) -> Self {
+        match codigo {
+            "01" => MeioPagamento::Dinheiro,
+            "02" => MeioPagamento::Cheque,
+            "03" => MeioPagamento::CartaoCredito,
+            "04" => MeioPagamento::CartaoDebito,
+            "05" => MeioPagamento::CreditoLoja,
+            "10" => MeioPagamento::ValeAlimentacao,
+            "11" => MeioPagamento::ValeRefeicao,
+            "12" => MeioPagamento::ValePresente,
+            "13" => MeioPagamento::ValeCombustivel,
+            "15" => MeioPagamento::BoletoBancario,
+            "16" => MeioPagamento::DepositoBancario,
+            "17" | "20" => MeioPagamento::Pix,
+            "18" => MeioPagamento::TransferenciaBancaria,
+            "19" => MeioPagamento::ProgramaFidelidade,
+            "90" => MeioPagamento::SemPagamento,
+            _ => MeioPagamento::Outros,
+        }
+    }
+}
+
+/// Dados do Cartão (`card`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CartaoPagamento {
+    pub tipo_integracao: Option<String>,
+    pub cnpj_credenciadora: Option<String>,
+    pub bandeira: Option<String>,
+    pub autorizacao: Option<String>,
+}
+
+/// Cobrança da NF-e (grupo `cobr`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CobrancaNota {
+    pub fatura: Option<Fatura>,
+    pub duplicatas: Vec<Duplicata>,
+}
+
+/// Fatura (`fat`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fatura {
+    pub numero: Option<String>,
+    pub valor_original: f64,
+    pub valor_desconto: f64,
+    pub valor_liquido: f64,
+}
+
+/// Duplicata (`dup`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Duplicata {
+    pub numero: String,
+    pub vencimento: Option<NaiveDate>,
+    pub valor: f64,
+}
+
+/// Protocolo de Autorização (`protNFe`/`protCTe`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProtocoloAutorizacao {
+    pub ambiente: Option<String>,
+    pub versao_aplicativo: Option<String>,
+    pub chave_acesso: String,
+    pub data_recebimento: Option<DateTime<Utc>>,
+    /// Ausente quando o documento é rejeitado
+    pub numero: Option<String>,
+    pub digest_value: Option<String>,
+    pub codigo_status: String,
+    pub motivo: String,
+}
+
+impl ProtocoloAutorizacao {
+    /// `cStat` 100 (autorizado o uso) ou 150 (autorizado fora de prazo)
+    pub fn autorizado(&self) -> bool {
+        matches!(self.codigo_status.as_str(), "100" | "150")
+    }
+}
+
+/// Evento vinculado a uma NF-e ou CT-e (`procEventoNFe`/`procEventoCTe`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventoFiscal {
+    pub id: Uuid,
+    /// Chave de acesso do documento ao qual o evento se refere
+    pub chave_acesso: String,
+    pub tipo: TipoEvento,
+    /// Código do evento (`tpEvento`)
+    pub codigo: String,
+    pub sequencia: u32,
+    pub data_evento: DateTime<Utc>,
+    pub orgao: String,
+    pub autor_cnpj_cpf: String,
+    pub descricao: String,
+    /// Protocolo de autorização do documento informado no cancelamento
+    pub protocolo_documento: Option<String>,
+    /// `xJust` do cancelamento/manifestação ou `xObs` do desacordo
+    pub justificativa: Option<String>,
+    /// Texto da carta de correção da NF-e (`xCorrecao`)
+    pub correcao: Option<String>,
+    /// Grupos alterados pela carta de correção do CT-e (`infCorrecao`)
+    pub correcoes: Vec<CorrecaoCTe>,
+    /// Retorno do registro do evento (`retEvento`)
+    pub protocolo: Option<ProtocoloAutorizacao>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Tipo do evento conforme `tpEvento`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TipoEvento {
+    /// 110110
+    CartaCorrecao,
+    /// 110111
+    Cancelamento,
+    /// 110112 - cancelamento por substituição (NFC-e)
+    CancelamentoSubstituicao,
+    /// 210200
+    ConfirmacaoOperacao,
+    /// 210210
+    CienciaOperacao,
+    /// 210220
+    DesconhecimentoOperacao,
+    /// 210240
+    OperacaoNaoRealizada,
+    /// 610110 - prestação do serviço em desacordo (CT-e)
+    PrestacaoDesacordo,
+    Outro,
+}
+
+impl TipoEvento {
+    pub fn from_codigo(codigo: &str) -> Self {
+        match codigo {
+            "110110" => TipoEvento::CartaCorrecao,
+            "110111" => TipoEvento::Cancelamento,
+            "110112" => TipoEvento::CancelamentoSubstituicao,
+            "210200" => TipoEvento::ConfirmacaoOperacao,
+            "210210" => TipoEvento::CienciaOperacao,
+            "210220" => TipoEvento::DesconhecimentoOperacao,
+            "210240" => TipoEvento::OperacaoNaoRealizada,
+            "610110" => TipoEvento::PrestacaoDesacordo,
+            _ => TipoEvento::Outro,
+        }
+    }
+    
+    pub fn cancela_documento(&self) -> bool {
+        matches!(self, TipoEvento::Cancelamento | TipoEvento::CancelamentoSubstituicao)
+    }
+}
+
+/// Alteração da carta de correção do CT-e (`infCorrecao`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorrecaoCTe {
+    pub grupo: String,
+    pub campo: String,
+    pub valor: String,
+    pub numero_item: Option<u32>,
+}
+
+/// Entrada do histórico de status de um documento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoricoStatus {
+    pub status: ProcessingStatus,
+    pub data: DateTime<Utc>,
+    pub codigo_evento: String,
+    pub sequencia: u32,
+    pub descricao: String,
+}
+
+impl EventoFiscal {
+    /// `cStat` 135 (registrado e vinculado), 136 (registrado sem vínculo) ou 155 (cancelamento fora de prazo)
+    pub fn registrado(&self) -> bool {
+        self.protocolo
+            .as_ref()
+            .is_some_and(|p| matches!(p.codigo_status.as_str(), "135" | "136" | "155"))
+    }
+    
+    /// Status que o documento passa a ter depois do evento, quando ele muda
+    pub fn status_resultante(&self) -> Option<ProcessingStatus> {
+        self.tipo.cancela_documento().then_some(ProcessingStatus::Cancelled)
+    }
+    
+    /// Registra o evento no histórico e atualiza o status; eventos já aplicados são ignorados
+    pub fn aplicar(&self, status: &mut ProcessingStatus, historico: &mut Vec<HistoricoStatus>) {
+        let aplicado = historico
+            .iter()
+            .any(|h| h.codigo_evento == self.codigo && h.sequencia == self.sequencia);
+        if aplicado {
+            return;
+        }
+        if let Some(novo) = self.status_resultante() {
+            *status = novo;
+        }
+        historico.push(HistoricoStatus {
+            status: status.clone(),
+            data: self.data_evento,
+            codigo_evento: self.codigo.clone(),
+            sequencia: self.sequencia,
+            descricao: self.descricao.clone(),
+        });
+    }
+}
+
 /// Tipo de Nota Fiscal
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TipoNota {
@@ -46,20 +463,44 @@ pub enum TipoNota {
     Saida,
 }
 
+impl TipoNota {
+    /// Converte o `tpNF` do `ide` (0 = entrada, 1 = saída)
+    pub fn from_codigo(codigo: &str) -> Option<Self> {
+        match codigo {
+            "0" => Some(TipoNota::Entrada),
+            "1" => Some(TipoNota::Saida),
+            _ => None,
+        }
+    }
+
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            TipoNota::Entrada => "0",
+            TipoNota::Saida => "1",
+        }
+    }
+}
+
 /// Participante (Emitente ou Destinatário)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Participante {
     pub cnpj_cpf: String,
+    /// Identificação de destinatário estrangeiro (sem CNPJ/CPF)
+    #[serde(default)]
+    pub id_estrangeiro: Option<String>,
     pub razao_social: String,
     pub nome_fantasia: Option<String>,
     pub endereco: Endereco,
     pub inscricao_estadual: Option<String>,
+    /// Inscrição municipal, informada no prestador e tomador da NFS-e
+    #[serde(default)]
+    pub inscricao_municipal: Option<String>,
     pub telefone: Option<String>,
     pub email: Option<String>,
 }
 
 /// Endereço
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Endereco {
     pub logradouro: String,
     pub numero: String,
@@ -74,7 +515,7 @@ pub struct Endereco {
 }
 
 /// Item da Nota Fiscal
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ItemNota {
     pub numero_item: i32,
     pub codigo_produto: String,
@@ -85,12 +526,156 @@ pub struct ItemNota {
     pub quantidade_comercial: f64,
     pub valor_unitario: f64,
     pub valor_total: f64,
+    /// Rateio de frete, seguro, desconto e outras despesas (`vFrete`, `vSeg`, `vDesc`, `vOutro`)
+    #[serde(default)]
+    pub valor_frete: f64,
+    #[serde(default)]
+    pub valor_seguro: f64,
+    #[serde(default)]
+    pub valor_desconto: f64,
+    #[serde(default)]
+    pub valor_outros: f64,
+    /// `indTot` = 0: o `vProd` do item não compõe o total da nota
+    #[serde(default)]
+    pub fora_do_total: bool,
+    /// IPI devolvido (`impostoDevol/IPI/vIPIDevol`)
+    #[serde(default)]
+    pub valor_ipi_devolvido: f64,
     pub ean: Option<String>,
     pub informacoes_adicionais: Option<String>,
+    #[serde(default)]
+    pub impostos: ImpostosItem,
+}
+
+/// Tributos do item (grupo `imposto` do `det`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImpostosItem {
+    pub valor_total_tributos: f64,
+    pub icms: Option<IcmsItem>,
+    pub ipi: Option<IpiItem>,
+    pub ii: Option<IiItem>,
+    pub pis: Option<PisCofinsItem>,
+    pub cofins: Option<PisCofinsItem>,
+    /// Presente nos itens de serviço da NF-e conjugada, que ficam fora do ICMS
+    #[serde(default)]
+    pub issqn: Option<IssqnItem>,
+}
+
+/// ICMS do item, cobrindo os grupos ICMS00–ICMS90, ICMSPart, ICMSST e ICMSSN101–ICMSSN900
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IcmsItem {
+    /// Nome do grupo no XML (ex.: "ICMS00", "ICMSSN101")
+    pub grupo: String,
+    pub origem: String,
+    /// CST (regime normal) — ausente para Simples Nacional
+    pub cst: Option<String>,
+    /// CSOSN (Simples Nacional) — ausente para regime normal
+    pub csosn: Option<String>,
+    pub modalidade_bc: Option<String>,
+    pub percentual_reducao_bc: f64,
+    pub base_calculo: f64,
+    pub aliquota: f64,
+    pub valor_icms_operacao: f64,
+    pub percentual_diferimento: f64,
+    pub valor_icms_diferido: f64,
+    pub valor: f64,
+
+    // Fundo de Combate à Pobreza
+    pub base_calculo_fcp: f64,
+    pub aliquota_fcp: f64,
+    pub valor_fcp: f64,
+
+    // Substituição tributária
+    pub modalidade_bc_st: Option<String>,
+    pub percentual_mva_st: f64,
+    pub percentual_reducao_bc_st: f64,
+    pub base_calculo_st: f64,
+    pub aliquota_st: f64,
+    pub valor_st: f64,
+    pub base_calculo_fcp_st: f64,
+    pub aliquota_fcp_st: f64,
+    pub valor_fcp_st: f64,
+
+    // ST retido anteriormente (CST 60 / CSOSN 500)
+    pub base_calculo_st_retido: f64,
+    pub aliquota_st_retido: f64,
+    pub valor_st_retido: f64,
+    pub valor_fcp_st_retido: f64,
+
+    // Monofásico sobre combustíveis (CST 02, 15, 53, 61)
+    pub quantidade_bc_mono: f64,
+    pub aliquota_ad_rem: f64,
+    pub valor_icms_mono: f64,
+
+    // Desoneração
+    pub valor_desonerado: f64,
+    pub motivo_desoneracao: Option<String>,
+
+    // Partilha entre UFs (ICMSPart)
+    pub percentual_bc_operacao_propria: f64,
+    pub uf_st: Option<String>,
+
+    // Crédito do Simples Nacional (CSOSN 101, 201, 900)
+    pub aliquota_credito_sn: f64,
+    pub valor_credito_sn: f64,
+}
+
+/// IPI do item (IPITrib ou IPINT)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpiItem {
+    pub codigo_enquadramento: String,
+    pub cst: String,
+    pub base_calculo: f64,
+    pub aliquota: f64,
+    pub quantidade_unidade: f64,
+    pub valor_unidade: f64,
+    pub valor: f64,
+}
+
+/// Imposto de Importação do item
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IiItem {
+    pub base_calculo: f64,
+    pub despesas_aduaneiras: f64,
+    pub valor: f64,
+    pub valor_iof: f64,
+}
+
+/// PIS ou COFINS do item (grupos Aliq, Qtde, NT e Outr)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PisCofinsItem {
+    pub cst: String,
+    pub base_calculo: f64,
+    pub aliquota: f64,
+    pub quantidade_bc: f64,
+    pub aliquota_valor: f64,
+    pub valor: f64,
+}
+
+/// ISSQN do item de serviço
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssqnItem {
+    pub base_calculo: f64,
+    pub aliquota: f64,
+    pub valor: f64,
+    /// Código IBGE do município de ocorrência do fato gerador (`cMunFG`)
+    pub codigo_municipio: String,
+    /// Item da Lista de Serviços da LC 116/2003 (`cListServ`)
+    pub item_lista_servico: String,
+}
+
+impl IcmsItem {
+    /// CST ou CSOSN, o que estiver preenchido
+    pub fn situacao_tributaria(&self) -> &str {
+        self.cst
+            .as_deref()
+            .or(self.csosn.as_deref())
+            .unwrap_or_default()
+    }
 }
 
 /// Totais da Nota Fiscal
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Totais {
     pub base_calculo_icms: f64,
     pub valor_icms: f64,
@@ -98,16 +683,26 @@ pub struct Totais {
     pub valor_fcp: f64,
     pub base_calculo_icms_st: f64,
     pub valor_icms_st: f64,
+    #[serde(default)]
+    pub valor_fcp_st: f64,
     pub valor_produtos: f64,
     pub valor_frete: f64,
     pub valor_seguro: f64,
     pub valor_desconto: f64,
     pub valor_ii: f64,
     pub valor_ipi: f64,
+    #[serde(default)]
+    pub valor_ipi_devolvido: f64,
     pub valor_pis: f64,
     pub valor_cofins: f64,
     pub outras_despesas: f64,
+    /// Serviços não tributados pelo ICMS (`ISSQNtot/vServ`)
+    #[serde(default)]
+    pub valor_servicos: f64,
     pub valor_total: f64,
+    /// Valor aproximado total de tributos (Lei da Transparência, `vTotTrib`)
+    #[serde(default)]
+    pub valor_total_tributos: f64,
 }
 
 /// Estrutura para Conhecimento de Transporte Eletrônico (CT-e)
@@ -118,22 +713,113 @@ pub struct ConhecimentoTransporte {
     pub numero: String,
     pub serie: String,
     pub data_emissao: DateTime<Utc>,
+    #[serde(default)]
+    pub modelo: ModeloConhecimento,
+    #[serde(default)]
+    pub identificacao: IdentificacaoEmissao,
+    #[serde(default)]
+    pub tipo_cte: TipoCTe,
     pub tipo_servico: TipoServicoCTe,
     pub emitente: Participante,
     pub remetente: Participante,
     pub destinatario: Participante,
     pub expedidor: Option<Participante>,
     pub recebedor: Option<Participante>,
+    #[serde(default)]
+    pub tomador: Option<TomadorServico>,
     pub valores_prestacao: ValoresPrestacaoCTe,
+    #[serde(default)]
+    pub impostos: ImpostosCTe,
     pub informacoes_carga: InformacoesCarga,
     pub documentos_referenciados: Vec<DocumentoReferenciado>,
+    /// Serviço prestado no CT-e OS (`infServico`)
+    #[serde(default)]
+    pub servico: Option<ServicoCTeOS>,
+    /// Entregas do CT-e Simplificado (`det`)
+    #[serde(default)]
+    pub entregas: Vec<EntregaCTeSimplificado>,
     pub modal: Modal,
+    #[serde(default)]
+    pub dados_modal: Option<DadosModal>,
     pub informacoes_adicionais: Option<String>,
     pub protocolo_autorizacao: Option<String>,
+    #[serde(default)]
+    pub protocolo: Option<ProtocoloAutorizacao>,
     pub status: ProcessingStatus,
+    /// Alterações de status por eventos (cancelamento, CC-e, manifestação...)
+    #[serde(default)]
+    pub historico_status: Vec<HistoricoStatus>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Modelo do Conhecimento de Transporte (`mod` do `ide`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ModeloConhecimento {
+    /// Modelo 57 - CT-e (inclusive o CT-e Simplificado)
+    #[default]
+    CTe,
+    /// Modelo 67 - CT-e OS (outros serviços)
+    CTeOS,
+}
+
+impl ModeloConhecimento {
+    pub fn from_codigo(codigo: &str) -> Option<Self> {
+        match codigo {
+            "57" => Some(ModeloConhecimento::CTe),
+            "67" => Some(ModeloConhecimento::CTeOS),
+            _ => None,
+        }
+    }
+    
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            ModeloConhecimento::CTe => "57",
+            ModeloConhecimento::CTeOS => "67",
+        }
+    }
+}
+
+/// Tipo do CT-e (`tpCTe`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TipoCTe {
+    #[default]
+    Normal,
+    Complementar,
+    Anulacao,
+    Substituto,
+    Simplificado,
+    SubstituicaoSimplificado,
+}
+
+impl TipoCTe {
+    pub fn from_codigo(codigo: &str) -> Option<Self> {
+        match codigo {
+            "0" => Some(TipoCTe::Normal),
+            "1" => Some(TipoCTe::Complementar),
+            "2" => Some(TipoCTe::Anulacao),
+            "3" => Some(TipoCTe::Substituto),
+            "5" => Some(TipoCTe::Simplificado),
+            "6" => Some(TipoCTe::SubstituicaoSimplificado),
+            _ => None,
+        }
+    }
+    
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            TipoCTe::Normal => "0",
+            TipoCTe::Complementar => "1",
+            TipoCTe::Anulacao => "2",
+            TipoCTe::Substituto => "3",
+            TipoCTe::Simplificado => "5",
+            TipoCTe::SubstituicaoSimplificado => "6",
+        }
+    }
+    
+    pub fn simplificado(&self) -> bool {
+        matches!(self, TipoCTe::Simplificado | TipoCTe::SubstituicaoSimplificado)
+    }
+}
+
 /// Tipo de Serviço do CT-e
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TipoServicoCTe {
@@ -142,6 +828,48 @@ pub enum TipoServicoCTe {
     Redespacho,
     RedespachIntermediario,
     ServicoVinculadoMultimodal,
+    /// Serviços exclusivos do CT-e OS
+    TransportePessoas,
+    TransporteValores,
+    ExcessoBagagem,
+}
+
+/// Serviço prestado no CT-e OS (`infServico`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServicoCTeOS {
+    pub descricao: String,
+    /// Quantidade transportada (passageiros ou volumes), `infQ/qCarga`
+    pub quantidade: Option<f64>,
+}
+
+/// Entrega do CT-e Simplificado (`det`), com percurso e valores próprios
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntregaCTeSimplificado {
+    pub codigo_municipio_inicio: String,
+    pub municipio_inicio: String,
+    pub codigo_municipio_fim: String,
+    pub municipio_fim: String,
+    pub valor_prestacao: f64,
+    pub valor_receber: f64,
+    pub componentes: Vec<ComponentePrestacao>,
+    pub chaves_nfe: Vec<String>,
+}
+
+/// Tomador do serviço (quem paga o frete), informado em `toma3` ou `toma4`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TomadorServico {
+    pub tipo: TipoTomador,
+    pub participante: Participante,
+}
+
+/// Papel do tomador no CT-e (campo `toma`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TipoTomador {
+    Remetente,
+    Expedidor,
+    Recebedor,
+    Destinatario,
+    Outros,
 }
 
 /// Valores da Prestação do Serviço
@@ -152,6 +880,55 @@ pub struct ValoresPrestacaoCTe {
     pub valor_total_carga: f64,
     pub produto_predominante: String,
     pub outras_caracteristicas_carga: Option<String>,
+    /// Componentes do valor da prestação (`Comp`)
+    #[serde(default)]
+    pub componentes: Vec<ComponentePrestacao>,
+}
+
+/// Componente do Valor da Prestação (frete peso, pedágio, GRIS, ad valorem, etc.)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComponentePrestacao {
+    pub nome: String,
+    pub valor: f64,
+}
+
+/// Tributos da Prestação do CT-e (grupo `imp`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImpostosCTe {
+    pub icms: Option<IcmsCTe>,
+    pub icms_uf_fim: Option<IcmsUfFimCTe>,
+    pub valor_total_tributos: f64,
+    pub informacoes_fisco: Option<String>,
+}
+
+/// ICMS da Prestação (ICMS00, ICMS20, ICMS45, ICMS60, ICMS90, ICMSOutraUF ou ICMSSN)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IcmsCTe {
+    pub grupo: String,
+    pub cst: String,
+    pub percentual_reducao_bc: Option<f64>,
+    pub base_calculo: f64,
+    pub aliquota: f64,
+    pub valor: f64,
+    pub valor_credito: Option<f64>,
+    pub base_calculo_st_retido: Option<f64>,
+    pub aliquota_st_retido: Option<f64>,
+    pub valor_st_retido: Option<f64>,
+    pub valor_desonerado: Option<f64>,
+    pub codigo_beneficio: Option<String>,
+    pub simples_nacional: bool,
+}
+
+/// Partilha do ICMS para consumidor final não contribuinte (ICMSUFFim)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IcmsUfFimCTe {
+    pub base_calculo: f64,
+    pub percentual_fcp: f64,
+    pub aliquota_interna: f64,
+    pub aliquota_interestadual: f64,
+    pub valor_fcp: f64,
+    pub valor_uf_fim: f64,
+    pub valor_uf_ini: f64,
 }
 
 /// Informações da Carga
@@ -159,13 +936,21 @@ pub struct ValoresPrestacaoCTe {
 pub struct InformacoesCarga {
     pub valor_carga: f64,
     pub produto_predominante: String,
+    /// Peso bruto em kg, derivado dos `infQ` de peso
     pub peso_bruto: f64,
+    /// Peso cubado em kg, quando informado
     pub peso_cubado: Option<f64>,
+    /// Volume em m³ (`cUnid` 00)
+    #[serde(default)]
+    pub volume_m3: Option<f64>,
+    /// Quantidade de volumes/unidades (`cUnid` 03)
+    #[serde(default)]
+    pub quantidade_volumes: Option<f64>,
     pub quantidades: Vec<QuantidadeCarga>,
 }
 
 /// Quantidade de Carga
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QuantidadeCarga {
     pub codigo_unidade: String,
     pub tipo_medida: String,
@@ -196,11 +981,298 @@ pub enum Modal {
     Aquaviario,
     Ferroviario,
     Dutoviario,
+    Multimodal,
+}
+
+/// Informações específicas do modal (grupo `infModal`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DadosModal {
+    Rodoviario(ModalRodoviario),
+    Aereo(ModalAereo),
+    Aquaviario(ModalAquaviario),
+    Ferroviario(ModalFerroviario),
+    Dutoviario(ModalDutoviario),
+    /// Modal rodoviário do CT-e OS (`rodoOS`)
+    RodoviarioOS(ModalRodoviarioOS),
+}
+
+/// Modal rodoviário (`rodo`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModalRodoviario {
+    pub rntrc: String,
+    pub ciot: Option<String>,
+    pub veiculos: Vec<VeiculoRodoviario>,
 }
 
-impl Default for ProcessingStatus {
-    fn default() -> Self {
-        ProcessingStatus::Pending
+/// Modal rodoviário do CT-e OS (`rodoOS`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModalRodoviarioOS {
+    /// Termo de Autorização de Fretamento
+    pub taf: Option<String>,
+    pub numero_registro_estadual: Option<String>,
+    pub veiculo: Option<VeiculoRodoviario>,
+    pub tipo_fretamento: Option<String>,
+    pub data_viagem: Option<String>,
+}
+
+/// Veículo do modal rodoviário (`veic`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VeiculoRodoviario {
+    pub placa: String,
+    pub renavam: Option<String>,
+    pub uf: String,
+    pub tara_kg: f64,
+    pub capacidade_kg: f64,
+    pub capacidade_m3: f64,
+    pub tipo_veiculo: Option<String>,
+    pub tipo_rodado: Option<String>,
+    pub tipo_carroceria: Option<String>,
+}
+
+/// Modal aéreo (`aereo`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModalAereo {
+    pub numero_minuta: Option<String>,
+    /// Número operacional do conhecimento aéreo (AWB)
+    pub numero_awb: Option<String>,
+    pub data_prevista_entrega: Option<String>,
+    pub classe_tarifa: Option<String>,
+    pub codigo_tarifa: Option<String>,
+    pub valor_tarifa: f64,
+}
+
+/// Modal aquaviário (`aquav`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModalAquaviario {
+    pub nome_navio: String,
+    pub numero_viagem: Option<String>,
+    pub direcao: Option<String>,
+    pub irin: Option<String>,
+    pub porto_embarque: Option<String>,
+    pub porto_transbordo: Option<String>,
+    pub porto_destino: Option<String>,
+    pub valor_prestacao: f64,
+    pub valor_afrmm: f64,
+}
+
+/// Modal ferroviário (`ferrov`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModalFerroviario {
+    pub tipo_trafego: Option<String>,
+    pub fluxo: Option<String>,
+}
+
+/// Modal dutoviário (`duto`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModalDutoviario {
+    pub valor_tarifa: f64,
+    pub data_inicio: Option<String>,
+    pub data_fim: Option<String>,
+}
+
+/// Estrutura para Manifesto Eletrônico de Documentos Fiscais (MDF-e)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestoDocumentos {
+    pub id: Uuid,
+    pub chave_acesso: String,
+    pub numero: String,
+    pub serie: String,
+    pub data_emissao: DateTime<Utc>,
+    #[serde(default)]
+    pub identificacao: IdentificacaoEmissao,
+    pub data_inicio_viagem: Option<DateTime<Utc>>,
+    pub modal: Modal,
+    pub emitente: Participante,
+    pub uf_inicio: String,
+    pub uf_fim: String,
+    /// UFs de percurso entre a UF de início e a de fim (`infPercurso`)
+    pub percurso_ufs: Vec<String>,
+    pub municipios_carregamento: Vec<MunicipioManifesto>,
+    pub municipios_descarregamento: Vec<MunicipioDescarregamento>,
+    pub rntrc: Option<String>,
+    pub veiculo_tracao: Option<VeiculoRodoviario>,
+    pub veiculos_reboque: Vec<VeiculoRodoviario>,
+    pub condutores: Vec<Condutor>,
+    pub totais: TotaisManifesto,
+    pub informacoes_adicionais: Option<String>,
+    pub protocolo_autorizacao: Option<String>,
+    pub protocolo: Option<ProtocoloAutorizacao>,
+    pub status: ProcessingStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Município de carregamento do MDF-e
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MunicipioManifesto {
+    pub codigo: String,
+    pub nome: String,
+}
+
+/// Município de descarregamento com os documentos entregues nele (`infMunDescarga`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MunicipioDescarregamento {
+    pub codigo: String,
+    pub nome: String,
+    pub chaves_cte: Vec<String>,
+    pub chaves_nfe: Vec<String>,
+}
+
+/// Condutor do veículo de tração
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Condutor {
+    pub nome: String,
+    pub cpf: String,
+}
+
+/// Totalizadores do MDF-e (`tot`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TotaisManifesto {
+    pub quantidade_cte: u32,
+    pub quantidade_nfe: u32,
+    pub valor_carga: f64,
+    /// 01 = KG, 02 = TON
+    pub codigo_unidade: String,
+    pub quantidade_carga: f64,
+}
+
+/// Estrutura para Nota Fiscal de Serviço Eletrônica (NFS-e), no Padrão Nacional ou ABRASF 2.x
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotaFiscalServico {
+    pub id: Uuid,
+    pub layout: LayoutNFSe,
+    /// Chave de 50 dígitos no Padrão Nacional; no ABRASF, município gerador + CNPJ do prestador + número
+    pub chave_acesso: String,
+    pub numero: String,
+    pub codigo_verificacao: Option<String>,
+    pub data_emissao: DateTime<Utc>,
+    pub competencia: Option<NaiveDate>,
+    /// Código IBGE do município emissor (`cLocEmi` ou `OrgaoGerador`)
+    pub codigo_municipio_emissor: String,
+    /// Número e série da DPS/RPS que originou a nota
+    pub numero_rps: Option<String>,
+    pub serie_rps: Option<String>,
+    pub prestador: Participante,
+    pub tomador: Option<Participante>,
+    pub optante_simples_nacional: bool,
+    pub servico: ServicoNFSe,
+    pub valores: ValoresNFSe,
+    pub retencoes: RetencoesNFSe,
+    pub status: ProcessingStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Leiaute do XML da NFS-e
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LayoutNFSe {
+    /// Padrão Nacional (`NFSe`/`infNFSe` com a `DPS`)
+    #[default]
+    Nacional,
+    /// ABRASF 2.x (`CompNfse`/`InfNfse`)
+    Abrasf,
+}
+
+/// Serviço prestado na NFS-e
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServicoNFSe {
+    /// Código de tributação nacional (`cTribNac`)
+    pub codigo_tributacao_nacional: Option<String>,
+    /// Item da lista da LC 116/2003 (`ItemListaServico`)
+    pub item_lista_servico: Option<String>,
+    pub codigo_tributacao_municipal: Option<String>,
+    pub codigo_cnae: Option<String>,
+    pub codigo_nbs: Option<String>,
+    pub discriminacao: String,
+    pub codigo_municipio_prestacao: String,
+    /// Município onde o ISS é devido
+    pub codigo_municipio_incidencia: Option<String>,
+}
+
+/// Valores e ISS da NFS-e
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValoresNFSe {
+    pub valor_servicos: f64,
+    pub valor_deducoes: f64,
+    pub desconto_incondicionado: f64,
+    pub desconto_condicionado: f64,
+    pub base_calculo: f64,
+    /// Alíquota do ISS em percentual
+    pub aliquota_iss: f64,
+    pub valor_iss: f64,
+    /// ISS retido pelo tomador ou intermediário
+    pub iss_retido: bool,
+    pub valor_liquido: f64,
+}
+
+/// Retenções federais da NFS-e
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetencoesNFSe {
+    pub pis: f64,
+    pub cofins: f64,
+    pub inss: f64,
+    pub irrf: f64,
+    pub csll: f64,
+    pub outras: f64,
+}
+
+impl RetencoesNFSe {
+    /// Soma das retenções federais
+    pub fn total(&self) -> f64 {
+        self.pis + self.cofins + self.inss + self.irrf + self.csll + self.outras
+    }
+}
+
+impl ManifestoDocumentos {
+    /// Chaves de todos os CT-e vinculados, em ordem de descarregamento
+    pub fn chaves_cte(&self) -> impl Iterator<Item = &str> {
+        self.municipios_descarregamento
+            .iter()
+            .flat_map(|m| m.chaves_cte.iter().map(String::as_str))
+    }
+    
+    /// Chaves de todas as NF-e vinculadas, em ordem de descarregamento
+    pub fn chaves_nfe(&self) -> impl Iterator<Item = &str> {
+        self.municipios_descarregamento
+            .iter()
+            .flat_map(|m| m.chaves_nfe.iter().map(String::as_str))
+    }
+}
+
+impl QuantidadeCarga {
+    /// Quantidade em kg, quando a unidade (`cUnid`) é de peso (01 = KG, 02 = TON)
+    pub fn peso_kg(&self) -> Option<f64> {
+        match self.codigo_unidade.as_str() {
+            "01" => Some(self.quantidade),
+            "02" => Some(self.quantidade * 1000.0),
+            _ => None,
+        }
+    }
+}
+
+impl ConhecimentoTransporte {
+    pub fn aplicar_evento(&mut self, evento: &EventoFiscal) {
+        evento.aplicar(&mut self.status, &mut self.historico_status);
+    }
+    
+    pub fn document_type(&self) -> DocumentType {
+        match self.modelo {
+            ModeloConhecimento::CTeOS => DocumentType::ConhecimentoTransporteOS,
+            ModeloConhecimento::CTe if self.tipo_cte.simplificado() => {
+                DocumentType::ConhecimentoTransporteSimplificado
+            }
+            ModeloConhecimento::CTe => DocumentType::ConhecimentoTransporte,
+        }
+    }
+    
+    /// Peso taxado: o maior entre peso bruto e peso cubado
+    pub fn peso_taxado(&self) -> f64 {
+        let carga = &self.informacoes_carga;
+        carga.peso_cubado.unwrap_or(0.0).max(carga.peso_bruto)
+    }
+
+    /// Valor da prestação por kg taxado, para análise de frete
+    pub fn valor_frete_por_kg(&self) -> Option<f64> {
+        let peso = self.peso_taxado();
+        (peso > 0.0).then(|| self.valores_prestacao.valor_total / peso)
     }
 }
 
@@ -209,6 +1281,12 @@ impl std::fmt::Display for DocumentType {
         match self {
             DocumentType::NotaFiscal => write!(f, "NF-e"),
             DocumentType::ConhecimentoTransporte => write!(f, "CT-e"),
+            DocumentType::NotaFiscalConsumidor => write!(f, "NFC-e"),
+            DocumentType::ManifestoDocumentos => write!(f, "MDF-e"),
+            DocumentType::ConhecimentoTransporteOS => write!(f, "CT-e OS"),
+            DocumentType::ConhecimentoTransporteSimplificado => write!(f, "CT-e Simplificado"),
+            DocumentType::EventoFiscal => write!(f, "Evento"),
+            DocumentType::NotaFiscalServico => write!(f, "NFS-e"),
         }
     }
 }
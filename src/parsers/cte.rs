@@ -1,6 +1,9 @@
 use crate::error::{GeolocationError, Result};
 use crate::models::*;
-use crate::parsers::{extract_access_key, FiscalDocumentParser};
+use crate::parsers::{
+    chave_documento, finalize_participante, parse_identificacao_field, parse_participante_field,
+    parse_protocolo_field, parse_veiculo_field, status_protocolo, FiscalDocumentParser,
+};
 use chrono::{DateTime, Utc};
 use quick_xml::events::Event;
 use quick_xml::Reader;
@@ -23,73 +26,226 @@ impl CTeParser {
         let mut current_path = Vec::new();
         
         // Dados principais
-        let mut chave_acesso = String::new();
         let mut numero = String::new();
         let mut serie = String::new();
         let mut data_emissao = String::new();
-        let tipo_servico = TipoServicoCTe::Normal;
+        let mut modelo: Option<ModeloConhecimento> = None;
+        let mut identificacao = IdentificacaoEmissao::default();
+        let mut tipo_cte = TipoCTe::Normal;
+        let mut tipo_servico = TipoServicoCTe::Normal;
         
-        // Emitente
-        let mut emit_cnpj = String::new();
-        let mut emit_razao = String::new();
-        
-        // Remetente
-        let mut rem_cnpj = String::new();
-        let mut rem_razao = String::new();
-        
-        // Destinatário
-        let mut dest_cnpj = String::new();
-        let mut dest_razao = String::new();
+        // Participantes
+        let mut emitente = Participante::default();
+        let mut remetente = Participante::default();
+        let mut destinatario = Participante::default();
+        let mut expedidor: Option<Participante> = None;
+        let mut recebedor: Option<Participante> = None;
+        let mut tipo_tomador: Option<TipoTomador> = None;
+        let mut outro_tomador = Participante::default();
         
         // Valores
         let mut valor_total = 0.0;
         let mut valor_receber = 0.0;
         let mut valor_carga = 0.0;
         let mut produto_predominante = String::new();
+        let mut componentes: Vec<ComponentePrestacao> = Vec::new();
+        let mut impostos = ImpostosCTe::default();
         
         // Carga
-        let mut peso_bruto = 0.0;
+        let mut quantidades: Vec<QuantidadeCarga> = Vec::new();
+        
+        // Modal
+        let mut modal = Modal::Rodoviario;
+        let mut dados_modal: Option<DadosModal> = None;
+        
+        // Documentos transportados (infDoc)
+        let mut documentos_referenciados = Vec::new();
+        let mut documento_atual: Option<DocumentoReferenciado> = None;
+        
+        // CT-e OS (infServico) e CT-e Simplificado (det)
+        let mut servico: Option<ServicoCTeOS> = None;
+        let mut entregas: Vec<EntregaCTeSimplificado> = Vec::new();
+        
+        // Protocolo de autorização (protNFe/protCTe)
+        let mut protocolo: Option<ProtocoloAutorizacao> = None;
         
         loop {
             match reader.read_event_into(&mut buf) {
                 Ok(Event::Start(e)) => {
                     let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    match current_path.last().map(String::as_str) {
+                        Some("infDoc") => {
+                            documento_atual = Self::novo_documento_referenciado(&name)
+                        }
+                        Some("infModal") => dados_modal = Self::novo_dados_modal(&name),
+                        Some("vPrest") if name == "Comp" => {
+                            componentes.push(ComponentePrestacao::default())
+                        }
+                        Some("ICMS") if current_path.contains(&"imp".to_string()) => {
+                            impostos.icms = Some(IcmsCTe {
+                                grupo: name.clone(),
+                                simples_nacional: name == "ICMSSN",
+                                ..Default::default()
+                            })
+                        }
+                        Some("imp") if name == "ICMSUFFim" => {
+                            impostos.icms_uf_fim = Some(IcmsUfFimCTe::default())
+                        }
+                        Some("infCarga") if name == "infQ" => {
+                            quantidades.push(QuantidadeCarga::default())
+                        }
+                        Some("rodo") if name == "veic" => {
+                            if let Some(DadosModal::Rodoviario(rodo)) = dados_modal.as_mut() {
+                                rodo.veiculos.push(VeiculoRodoviario::default());
+                            }
+                        }
+                        Some("rodoOS") if name == "veic" => {
+                            if let Some(DadosModal::RodoviarioOS(rodo)) = dados_modal.as_mut() {
+                                rodo.veiculo = Some(VeiculoRodoviario::default());
+                            }
+                        }
+                        // CT-e OS e Simplificado trazem o tomador em grupo próprio
+                        Some("infCte") if name == "toma" => {
+                            tipo_tomador = Some(TipoTomador::Outros)
+                        }
+                        Some("infCte") if name == "det" => {
+                            entregas.push(EntregaCTeSimplificado::default())
+                        }
+                        Some("det") if name == "Comp" => {
+                            if let Some(entrega) = entregas.last_mut() {
+                                entrega.componentes.push(ComponentePrestacao::default());
+                            }
+                        }
+                        Some("infCTeNorm") if name == "infServico" => {
+                            servico = Some(ServicoCTeOS::default())
+                        }
+                        _ => {}
+                    }
                     current_path.push(name);
                 }
                 Ok(Event::End(_)) => {
                     current_path.pop();
+                    if current_path.last().map(String::as_str) == Some("infDoc") {
+                        documentos_referenciados.extend(documento_atual.take());
+                    }
                 }
                 Ok(Event::Text(e)) => {
                     let text = e.unescape().unwrap_or_default().to_string();
                     
-                    if let Some(tag) = current_path.last() {
+                    if let Some(documento) = documento_atual.as_mut() {
+                        if let Some(tag) = current_path.last() {
+                            Self::parse_documento_field(tag, &text, documento);
+                        }
+                    } else if let Some(tag) = current_path.last() {
+                        // `serie`, `nCT`, `cUF` etc. também aparecem nos documentos referenciados
+                        let em_ide = current_path.iter().rev().nth(1).map(String::as_str) == Some("ide");
                         match tag.as_str() {
-                            "nCT" => numero = text,
-                            "serie" => serie = text,
-                            "dhEmi" => data_emissao = text,
-                            "CNPJ" if current_path.contains(&"emit".to_string()) => {
-                                emit_cnpj = text
+                            "nCT" if em_ide => numero = text,
+                            "serie" if em_ide => serie = text,
+                            "dhEmi" if em_ide => data_emissao = text,
+                            "cUF" | "tpEmis" | "cCT" if em_ide => {
+                                parse_identificacao_field(tag, &text, &mut identificacao)
                             }
-                            "xNome" if current_path.contains(&"emit".to_string()) => {
-                                emit_razao = text
+                            "mod" if current_path.contains(&"ide".to_string()) => {
+                                modelo = ModeloConhecimento::from_codigo(&text)
                             }
-                            "CNPJ" if current_path.contains(&"rem".to_string()) => {
-                                rem_cnpj = text
+                            "tpCTe" => {
+                                if let Some(tipo) = TipoCTe::from_codigo(&text) {
+                                    tipo_cte = tipo;
+                                }
                             }
-                            "xNome" if current_path.contains(&"rem".to_string()) => {
-                                rem_razao = text
+                            "tpServ" => {
+                                if let Some(tipo) = Self::parse_tipo_servico(&text) {
+                                    tipo_servico = tipo;
+                                }
                             }
-                            "CNPJ" if current_path.contains(&"dest".to_string()) => {
-                                dest_cnpj = text
+                            "toma" => tipo_tomador = Self::parse_tipo_tomador(&text),
+                            "modal" => {
+                                if let Some(m) = Self::parse_modal(&text) {
+                                    modal = m;
+                                }
                             }
-                            "xNome" if current_path.contains(&"dest".to_string()) => {
-                                dest_razao = text
+                            _ if current_path.contains(&"infProt".to_string()) => {
+                                let protocolo = protocolo.get_or_insert_with(Default::default);
+                                parse_protocolo_field(tag, &text, protocolo)
+                            }
+                            _ if current_path.contains(&"infModal".to_string()) => {
+                                if let Some(dados) = dados_modal.as_mut() {
+                                    Self::parse_modal_field(&current_path, &text, dados);
+                                }
+                            }
+                            _ if current_path.contains(&"toma4".to_string())
+                                || current_path.contains(&"toma".to_string()) =>
+                            {
+                                parse_participante_field(tag, &text, &mut outro_tomador)
+                            }
+                            _ if current_path.contains(&"emit".to_string()) => {
+                                parse_participante_field(tag, &text, &mut emitente)
+                            }
+                            _ if current_path.contains(&"rem".to_string()) => {
+                                parse_participante_field(tag, &text, &mut remetente)
+                            }
+                            _ if current_path.contains(&"dest".to_string()) => {
+                                parse_participante_field(tag, &text, &mut destinatario)
+                            }
+                            _ if current_path.contains(&"exped".to_string()) => {
+                                let exped = expedidor.get_or_insert_with(Participante::default);
+                                parse_participante_field(tag, &text, exped)
+                            }
+                            _ if current_path.contains(&"receb".to_string()) => {
+                                let receb = recebedor.get_or_insert_with(Participante::default);
+                                parse_participante_field(tag, &text, receb)
+                            }
+                            _ if current_path.contains(&"det".to_string()) => {
+                                if let Some(entrega) = entregas.last_mut() {
+                                    if tag == "chNFe" {
+                                        documentos_referenciados.push(DocumentoReferenciado {
+                                            tipo: TipoDocumentoReferenciado::NotaFiscal,
+                                            chave_acesso: Some(text.clone()),
+                                            numero: None,
+                                            serie: None,
+                                        });
+                                    }
+                                    Self::parse_entrega_field(tag, text, entrega);
+                                }
+                            }
+                            _ if current_path.contains(&"infServico".to_string()) => {
+                                if let Some(servico) = servico.as_mut() {
+                                    match tag.as_str() {
+                                        "xDescServ" => servico.descricao = text,
+                                        "qCarga" => servico.quantidade = text.parse().ok(),
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            _ if current_path.contains(&"Comp".to_string()) => {
+                                if let Some(componente) = componentes.last_mut() {
+                                    match tag.as_str() {
+                                        "xNome" => componente.nome = text,
+                                        "vComp" => componente.valor = text.parse().unwrap_or(0.0),
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            _ if current_path.contains(&"imp".to_string()) => {
+                                Self::parse_imposto_field(tag, &text, &mut impostos)
                             }
                             "vTPrest" => valor_total = text.parse().unwrap_or(0.0),
-                            "vRec" => valor_receber = text.parse().unwrap_or(0.0),
+                            "vRec" | "vTRec" => valor_receber = text.parse().unwrap_or(0.0),
                             "vCarga" => valor_carga = text.parse().unwrap_or(0.0),
                             "proPred" => produto_predominante = text,
-                            "qCarga" => peso_bruto = text.parse().unwrap_or(0.0),
+                            _ if current_path.contains(&"infQ".to_string()) => {
+                                if let Some(quantidade) = quantidades.last_mut() {
+                                    match tag.as_str() {
+                                        "cUnid" => quantidade.codigo_unidade = text,
+                                        "tpMed" => quantidade.tipo_medida = text,
+                                        "qCarga" => {
+                                            quantidade.quantidade = text.parse().unwrap_or(0.0)
+                                        }
+                                        _ => {}
+                                    }
+                                }
+                            }
                             _ => {}
                         }
                     }
@@ -106,73 +262,30 @@ impl CTeParser {
             buf.clear();
         }
         
-        // Extrai chave de acesso
-        chave_acesso = extract_access_key(xml)
-            .ok_or_else(|| GeolocationError::InvalidAccessKey("Chave não encontrada".into()))?;
-        
-        // Cria estruturas básicas
-        let emitente = Participante {
-            cnpj_cpf: emit_cnpj,
-            razao_social: emit_razao,
-            nome_fantasia: None,
-            endereco: Endereco {
-                logradouro: String::new(),
-                numero: String::new(),
-                complemento: None,
-                bairro: String::new(),
-                codigo_municipio: String::new(),
-                municipio: String::new(),
-                uf: String::new(),
-                cep: String::new(),
-                codigo_pais: "1058".to_string(),
-                pais: "Brasil".to_string(),
-            },
-            inscricao_estadual: None,
-            telefone: None,
-            email: None,
-        };
+        // Extrai a chave de acesso
+        let chave_acesso = chave_documento(xml)?;
         
-        let remetente = Participante {
-            cnpj_cpf: rem_cnpj,
-            razao_social: rem_razao,
-            nome_fantasia: None,
-            endereco: Endereco {
-                logradouro: String::new(),
-                numero: String::new(),
-                complemento: None,
-                bairro: String::new(),
-                codigo_municipio: String::new(),
-                municipio: String::new(),
-                uf: String::new(),
-                cep: String::new(),
-                codigo_pais: "1058".to_string(),
-                pais: "Brasil".to_string(),
-            },
-            inscricao_estadual: None,
-            telefone: None,
-            email: None,
-        };
+        for participante in [&mut emitente, &mut remetente, &mut destinatario]
+            .into_iter()
+            .chain(expedidor.as_mut())
+            .chain(recebedor.as_mut())
+        {
+            finalize_participante(participante);
+        }
         
-        let destinatario = Participante {
-            cnpj_cpf: dest_cnpj,
-            razao_social: dest_razao,
-            nome_fantasia: None,
-            endereco: Endereco {
-                logradouro: String::new(),
-                numero: String::new(),
-                complemento: None,
-                bairro: String::new(),
-                codigo_municipio: String::new(),
-                municipio: String::new(),
-                uf: String::new(),
-                cep: String::new(),
-                codigo_pais: "1058".to_string(),
-                pais: "Brasil".to_string(),
-            },
-            inscricao_estadual: None,
-            telefone: None,
-            email: None,
-        };
+        let tomador = tipo_tomador.map(|tipo| {
+            finalize_participante(&mut outro_tomador);
+            let participante = match tipo {
+                // No CT-e OS e no Simplificado não há rem/dest: os dados vêm do grupo `toma`
+                _ if !outro_tomador.cnpj_cpf.is_empty() => outro_tomador,
+                TipoTomador::Remetente => remetente.clone(),
+                TipoTomador::Expedidor => expedidor.clone().unwrap_or_default(),
+                TipoTomador::Recebedor => recebedor.clone().unwrap_or_default(),
+                TipoTomador::Destinatario => destinatario.clone(),
+                TipoTomador::Outros => outro_tomador,
+            };
+            TomadorServico { tipo, participante }
+        });
         
         let valores_prestacao = ValoresPrestacaoCTe {
             valor_total,
@@ -180,43 +293,329 @@ impl CTeParser {
             valor_total_carga: valor_carga,
             produto_predominante: produto_predominante.clone(),
             outras_caracteristicas_carga: None,
+            componentes,
         };
         
-        let informacoes_carga = InformacoesCarga {
-            valor_carga,
-            produto_predominante,
-            peso_bruto,
-            peso_cubado: None,
-            quantidades: Vec::new(),
-        };
+        let informacoes_carga = Self::resumir_carga(valor_carga, produto_predominante, quantidades);
         
         // Parse data de emissão
         let data_emissao_parsed = DateTime::parse_from_rfc3339(&data_emissao)
             .map(|dt| dt.with_timezone(&Utc))
             .unwrap_or_else(|_| Utc::now());
         
+        // Modelo do `ide` ou, na falta dele, posições 21-22 da chave
+        let modelo = modelo
+            .or_else(|| ModeloConhecimento::from_codigo(&chave_acesso[20..22]))
+            .unwrap_or_default();
+        
+        let status = status_protocolo(protocolo.as_ref(), &chave_acesso);
+        
         Ok(ConhecimentoTransporte {
             id: Uuid::new_v4(),
             chave_acesso,
             numero,
             serie,
             data_emissao: data_emissao_parsed,
+            modelo,
+            identificacao,
+            tipo_cte,
             tipo_servico,
             emitente,
             remetente,
             destinatario,
-            expedidor: None,
-            recebedor: None,
+            expedidor,
+            recebedor,
+            tomador,
             valores_prestacao,
+            impostos,
             informacoes_carga,
-            documentos_referenciados: Vec::new(),
-            modal: Modal::Rodoviario,
+            documentos_referenciados,
+            servico,
+            entregas,
+            modal,
+            dados_modal,
             informacoes_adicionais: None,
-            protocolo_autorizacao: None,
-            status: ProcessingStatus::Completed,
+            protocolo_autorizacao: protocolo.as_ref().and_then(|p| p.numero.clone()),
+            protocolo,
+            status,
+            historico_status: Vec::new(),
             created_at: Utc::now(),
         })
     }
+    
+    /// Cria o documento referenciado correspondente a um filho de `infDoc`
+    fn novo_documento_referenciado(grupo: &str) -> Option<DocumentoReferenciado> {
+        let tipo = match grupo {
+            "infNFe" | "infNF" => TipoDocumentoReferenciado::NotaFiscal,
+            "infOutros" => TipoDocumentoReferenciado::OutrosDocumentos,
+            _ => return None,
+        };
+        Some(DocumentoReferenciado {
+            tipo,
+            chave_acesso: None,
+            numero: None,
+            serie: None,
+        })
+    }
+    
+    /// Preenche um campo de `infNFe`, `infNF` ou `infOutros`
+    fn parse_documento_field(tag: &str, text: &str, documento: &mut DocumentoReferenciado) {
+        match tag {
+            "chave" => documento.chave_acesso = Some(text.to_string()),
+            "nDoc" => documento.numero = Some(text.to_string()),
+            "serie" => documento.serie = Some(text.to_string()),
+            // Modelo 04 identifica a nota fiscal de produtor rural
+            "mod" if text == "04" => documento.tipo = TipoDocumentoReferenciado::NotaFiscalProdutor,
+            _ => {}
+        }
+    }
+    
+    /// Preenche um campo de uma entrega (`det`) do CT-e Simplificado
+    fn parse_entrega_field(tag: &str, text: String, entrega: &mut EntregaCTeSimplificado) {
+        let valor = || text.parse().unwrap_or(0.0);
+        match tag {
+            "cMunIni" => entrega.codigo_municipio_inicio = text,
+            "xMunIni" => entrega.municipio_inicio = text,
+            "cMunFim" => entrega.codigo_municipio_fim = text,
+            "xMunFim" => entrega.municipio_fim = text,
+            "vPrest" => entrega.valor_prestacao = valor(),
+            "vRec" => entrega.valor_receber = valor(),
+            "chNFe" => entrega.chaves_nfe.push(text),
+            "xNome" | "vComp" => {
+                if let Some(componente) = entrega.componentes.last_mut() {
+                    if tag == "xNome" {
+                        componente.nome = text;
+                    } else {
+                        componente.valor = valor();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    /// Mapeia os campos do grupo `imp` (ICMS da prestação, ICMSUFFim e totais)
+    fn parse_imposto_field(tag: &str, text: &str, impostos: &mut ImpostosCTe) {
+        let valor = || text.parse::<f64>().unwrap_or(0.0);
+        match tag {
+            "vTotTrib" => impostos.valor_total_tributos = valor(),
+            "infAdFisco" => impostos.informacoes_fisco = Some(text.to_string()),
+            _ => {}
+        }
+        
+        if let Some(uf_fim) = impostos.icms_uf_fim.as_mut() {
+            match tag {
+                "vBCUFFim" => uf_fim.base_calculo = valor(),
+                "pFCPUFFim" => uf_fim.percentual_fcp = valor(),
+                "pICMSUFFim" => uf_fim.aliquota_interna = valor(),
+                "pICMSInter" => uf_fim.aliquota_interestadual = valor(),
+                "vFCPUFFim" => uf_fim.valor_fcp = valor(),
+                "vICMSUFFim" => uf_fim.valor_uf_fim = valor(),
+                "vICMSUFIni" => uf_fim.valor_uf_ini = valor(),
+                _ => {}
+            }
+        }
+        
+        if let Some(icms) = impostos.icms.as_mut() {
+            match tag {
+                "CST" => icms.cst = text.to_string(),
+                "pRedBC" | "pRedBCOutraUF" => icms.percentual_reducao_bc = Some(valor()),
+                "vBC" | "vBCOutraUF" => icms.base_calculo = valor(),
+                "pICMS" | "pICMSOutraUF" => icms.aliquota = valor(),
+                "vICMS" | "vICMSOutraUF" => icms.valor = valor(),
+                "vCred" => icms.valor_credito = Some(valor()),
+                "vBCSTRet" => icms.base_calculo_st_retido = Some(valor()),
+                "pICMSSTRet" => icms.aliquota_st_retido = Some(valor()),
+                "vICMSSTRet" => icms.valor_st_retido = Some(valor()),
+                "vICMSDeson" => icms.valor_desonerado = Some(valor()),
+                "cBenef" => icms.codigo_beneficio = Some(text.to_string()),
+                "indSN" => icms.simples_nacional = text == "1",
+                _ => {}
+            }
+        }
+    }
+    
+    /// Deriva peso bruto, peso cubado, volume e quantidade de volumes a partir dos `infQ`
+    fn resumir_carga(
+        valor_carga: f64,
+        produto_predominante: String,
+        quantidades: Vec<QuantidadeCarga>,
+    ) -> InformacoesCarga {
+        let medida = |q: &QuantidadeCarga| q.tipo_medida.to_uppercase();
+        let pesos: Vec<(String, f64)> = quantidades
+            .iter()
+            .filter_map(|q| Some((medida(q), q.peso_kg()?)))
+            .collect();
+        let peso_com = |termo: &str| {
+            pesos
+                .iter()
+                .find(|(tipo, _)| tipo.contains(termo))
+                .map(|(_, kg)| *kg)
+        };
+        
+        let peso_cubado = peso_com("CUBADO").or_else(|| peso_com("CUBAGEM"));
+        // Preferência: peso bruto > aferido > declarado > qualquer outro peso real
+        let peso_bruto = peso_com("BRUTO")
+            .or_else(|| peso_com("AFERIDO"))
+            .or_else(|| peso_com("DECLARADO"))
+            .or_else(|| {
+                pesos
+                    .iter()
+                    .find(|(tipo, _)| !tipo.contains("CUB") && !tipo.contains("BASE"))
+                    .map(|(_, kg)| *kg)
+            })
+            .unwrap_or(0.0);
+        
+        let soma_unidade = |codigo: &str| {
+            quantidades
+                .iter()
+                .filter(|q| q.codigo_unidade == codigo)
+                .map(|q| q.quantidade)
+                .reduce(|a, b| a + b)
+        };
+        
+        InformacoesCarga {
+            valor_carga,
+            produto_predominante,
+            peso_bruto,
+            peso_cubado,
+            volume_m3: soma_unidade("00"),
+            quantidade_volumes: soma_unidade("03"),
+            quantidades,
+        }
+    }
+    
+    /// Converte o código `modal` do `ide` (01 a 06)
+    fn parse_modal(codigo: &str) -> Option<Modal> {
+        match codigo {
+            "01" | "1" => Some(Modal::Rodoviario),
+            "02" | "2" => Some(Modal::Aereo),
+            "03" | "3" => Some(Modal::Aquaviario),
+            "04" | "4" => Some(Modal::Ferroviario),
+            "05" | "5" => Some(Modal::Dutoviario),
+            "06" | "6" => Some(Modal::Multimodal),
+            _ => None,
+        }
+    }
+    
+    /// Cria o grupo específico do modal a partir do filho de `infModal`
+    fn novo_dados_modal(grupo: &str) -> Option<DadosModal> {
+        match grupo {
+            "rodo" => Some(DadosModal::Rodoviario(ModalRodoviario::default())),
+            "aereo" => Some(DadosModal::Aereo(ModalAereo::default())),
+            "aquav" => Some(DadosModal::Aquaviario(ModalAquaviario::default())),
+            "ferrov" => Some(DadosModal::Ferroviario(ModalFerroviario::default())),
+            "duto" => Some(DadosModal::Dutoviario(ModalDutoviario::default())),
+            "rodoOS" => Some(DadosModal::RodoviarioOS(ModalRodoviarioOS::default())),
+            _ => None,
+        }
+    }
+    
+    /// Preenche um campo do grupo específico do modal
+    fn parse_modal_field(path: &[String], text: &str, dados: &mut DadosModal) {
+        let Some(tag) = path.last() else {
+            return;
+        };
+        let valor = || text.parse().unwrap_or(0.0);
+        let texto = || Some(text.to_string());
+        
+        match dados {
+            DadosModal::Rodoviario(rodo) => {
+                if path.contains(&"veic".to_string()) {
+                    // Campos do proprietário (prop) não pertencem ao veículo
+                    if path.contains(&"prop".to_string()) {
+                        return;
+                    }
+                    if let Some(veiculo) = rodo.veiculos.last_mut() {
+                        parse_veiculo_field(tag, text, veiculo);
+                    }
+                    return;
+                }
+                match tag.as_str() {
+                    "RNTRC" => rodo.rntrc = text.to_string(),
+                    "CIOT" => rodo.ciot = texto(),
+                    _ => {}
+                }
+            }
+            DadosModal::Aereo(aereo) => match tag.as_str() {
+                "nMinu" => aereo.numero_minuta = texto(),
+                "nOCA" => aereo.numero_awb = texto(),
+                "dPrevAereo" => aereo.data_prevista_entrega = texto(),
+                "CL" => aereo.classe_tarifa = texto(),
+                "cTar" => aereo.codigo_tarifa = texto(),
+                "vTarifa" => aereo.valor_tarifa = valor(),
+                _ => {}
+            },
+            DadosModal::Aquaviario(aquav) => match tag.as_str() {
+                "xNavio" => aquav.nome_navio = text.to_string(),
+                "nViag" => aquav.numero_viagem = texto(),
+                "direc" => aquav.direcao = texto(),
+                "irin" => aquav.irin = texto(),
+                "prtEmb" | "cPrtEmb" => aquav.porto_embarque = texto(),
+                "prtTrans" => aquav.porto_transbordo = texto(),
+                "prtDest" | "cPrtDest" => aquav.porto_destino = texto(),
+                "vPrest" => aquav.valor_prestacao = valor(),
+                "vAFRMM" => aquav.valor_afrmm = valor(),
+                _ => {}
+            },
+            DadosModal::Ferroviario(ferrov) => match tag.as_str() {
+                "tpTraf" => ferrov.tipo_trafego = texto(),
+                "fluxo" => ferrov.fluxo = texto(),
+                _ => {}
+            },
+            DadosModal::RodoviarioOS(rodo) => {
+                if path.contains(&"veic".to_string()) {
+                    if path.contains(&"prop".to_string()) {
+                        return;
+                    }
+                    if let Some(veiculo) = rodo.veiculo.as_mut() {
+                        parse_veiculo_field(tag, text, veiculo);
+                    }
+                    return;
+                }
+                match tag.as_str() {
+                    "TAF" => rodo.taf = texto(),
+                    "NroRegEstadual" => rodo.numero_registro_estadual = texto(),
+                    "tpFretamento" => rodo.tipo_fretamento = texto(),
+                    "dhViagem" => rodo.data_viagem = texto(),
+                    _ => {}
+                }
+            }
+            DadosModal::Dutoviario(duto) => match tag.as_str() {
+                "vTar" => duto.valor_tarifa = valor(),
+                "dIni" => duto.data_inicio = texto(),
+                "dFim" => duto.data_fim = texto(),
+                _ => {}
+            },
+        }
+    }
+    
+    /// Converte o código `tpServ` no tipo de serviço
+    fn parse_tipo_servico(codigo: &str) -> Option<TipoServicoCTe> {
+        match codigo {
+            "0" => Some(TipoServicoCTe::Normal),
+            "1" => Some(TipoServicoCTe::Subcontratacao),
+            "2" => Some(TipoServicoCTe::Redespacho),
+            "3" => Some(TipoServicoCTe::RedespachIntermediario),
+            "4" => Some(TipoServicoCTe::ServicoVinculadoMultimodal),
+            "6" => Some(TipoServicoCTe::TransportePessoas),
+            "7" => Some(TipoServicoCTe::TransporteValores),
+            "8" => Some(TipoServicoCTe::ExcessoBagagem),
+            _ => None,
+        }
+    }
+    
+    /// Converte o código `toma` (toma3/toma4) no papel do tomador
+    fn parse_tipo_tomador(codigo: &str) -> Option<TipoTomador> {
+        match codigo {
+            "0" => Some(TipoTomador::Remetente),
+            "1" => Some(TipoTomador::Expedidor),
+            "2" => Some(TipoTomador::Recebedor),
+            "3" => Some(TipoTomador::Destinatario),
+            "4" => Some(TipoTomador::Outros),
+            _ => None,
+        }
+    }
 }
 
 impl FiscalDocumentParser for CTeParser {
@@ -294,4 +693,391 @@ mod tests {
         assert_eq!(cte.serie, "1");
         assert_eq!(cte.valores_prestacao.valor_total, 500.0);
     }
+
+    #[test]
+    fn test_cte_parser_documento_anterior() {
+        // A série e o número do documento anterior em papel não são os do CT-e
+        let xml = include_str!("../../test_data/sample_cte.xml").replace(
+            "</infDoc>",
+            "</infDoc><docAnt><emiDocAnt><CNPJ>11222333000181</CNPJ><IE>ISENTO</IE>\
+             <UF>SP</UF><xNome>Transportadora Anterior</xNome><idDocAnt><idDocAntPap>\
+             <tpDoc>07</tpDoc><serie>9</serie><nDoc>55</nDoc><dEmi>2021-01-10</dEmi>\
+             </idDocAntPap></idDocAnt></emiDocAnt></docAnt>",
+        );
+        let cte = CTeParser::new().parse_string(&xml).unwrap();
+        assert_eq!(cte.numero, "12345");
+        assert_eq!(cte.serie, "1");
+
+        let result = crate::validators::FiscalValidator::validate_conhecimento(&cte);
+        assert!(!result.errors.iter().any(|e| e.code.starts_with("KEY_MISMATCH")));
+    }
+
+    #[test]
+    fn test_cte_parser_participantes() {
+        let parser = CTeParser::new();
+        let cte = parser
+            .parse_string(include_str!("../../test_data/sample_cte.xml"))
+            .unwrap();
+        
+        assert_eq!(cte.emitente.inscricao_estadual.as_deref(), Some("123456785909"));
+        assert_eq!(cte.emitente.telefone.as_deref(), Some("1133334444"));
+        assert_eq!(cte.emitente.endereco.logradouro, "Rua Transportadora");
+        assert_eq!(cte.remetente.endereco.municipio, "São Paulo");
+        assert_eq!(cte.destinatario.endereco.municipio, "Porto Alegre");
+        assert_eq!(cte.destinatario.endereco.uf, "RS");
+        assert_eq!(cte.destinatario.endereco.codigo_pais, "1058");
+        assert!(cte.expedidor.is_none());
+        
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <exped>
+                <CNPJ>33333333000100</CNPJ>
+                <xNome>Expedidor Teste</xNome>
+                <enderExped><xMun>Campinas</xMun><UF>SP</UF></enderExped>
+            </exped>
+            <receb>
+                <CPF>11144477735</CPF>
+                <xNome>Recebedor Teste</xNome>
+                <enderReceb><xMun>Curitiba</xMun><UF>PR</UF></enderReceb>
+            </receb>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        let exped = cte.expedidor.unwrap();
+        assert_eq!(exped.cnpj_cpf, "33333333000100");
+        assert_eq!(exped.endereco.municipio, "Campinas");
+        let receb = cte.recebedor.unwrap();
+        assert_eq!(receb.cnpj_cpf, "11144477735");
+        assert_eq!(receb.endereco.uf, "PR");
+    }
+
+    #[test]
+    fn test_cte_parser_tomador() {
+        let parser = CTeParser::new();
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <ide>
+                <tpServ>2</tpServ>
+                <toma3><toma>3</toma></toma3>
+            </ide>
+            <dest>
+                <CNPJ>22222222000100</CNPJ>
+                <xNome>Destinatario Teste</xNome>
+            </dest>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        assert_eq!(cte.tipo_servico, TipoServicoCTe::Redespacho);
+        let tomador = cte.tomador.unwrap();
+        assert_eq!(tomador.tipo, TipoTomador::Destinatario);
+        assert_eq!(tomador.participante.cnpj_cpf, "22222222000100");
+        
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <ide>
+                <tpServ>1</tpServ>
+                <toma4>
+                    <toma>4</toma>
+                    <CNPJ>44444444000100</CNPJ>
+                    <IE>ISENTO</IE>
+                    <xNome>Operador Logistico</xNome>
+                    <enderToma><xMun>Santos</xMun><UF>SP</UF></enderToma>
+                </toma4>
+            </ide>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        assert_eq!(cte.tipo_servico, TipoServicoCTe::Subcontratacao);
+        let tomador = cte.tomador.unwrap();
+        assert_eq!(tomador.tipo, TipoTomador::Outros);
+        assert_eq!(tomador.participante.razao_social, "Operador Logistico");
+        assert_eq!(tomador.participante.endereco.municipio, "Santos");
+        
+        let cte = parser
+            .parse_string(include_str!("../../test_data/sample_cte.xml"))
+            .unwrap();
+        assert_eq!(cte.tipo_servico, TipoServicoCTe::Normal);
+        assert!(cte.tomador.is_none());
+    }
+
+    #[test]
+    fn test_cte_parser_documentos_referenciados() {
+        let parser = CTeParser::new();
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <infCTeNorm>
+                <infDoc>
+                    <infNFe>
+                        <chave>35210112345678901234567890123456789012345678</chave>
+                    </infNFe>
+                    <infNF>
+                        <mod>04</mod>
+                        <serie>2</serie>
+                        <nDoc>778</nDoc>
+                        <vNF>150.00</vNF>
+                    </infNF>
+                    <infOutros>
+                        <tpDoc>99</tpDoc>
+                        <descOutros>Declaracao</descOutros>
+                        <nDoc>D-1</nDoc>
+                    </infOutros>
+                </infDoc>
+            </infCTeNorm>
+        </infCte></CTe>"#;
+        let docs = parser.parse_string(xml).unwrap().documentos_referenciados;
+        assert_eq!(docs.len(), 3);
+        assert_eq!(docs[0].tipo, TipoDocumentoReferenciado::NotaFiscal);
+        assert_eq!(
+            docs[0].chave_acesso.as_deref(),
+            Some("35210112345678901234567890123456789012345678")
+        );
+        assert_eq!(docs[1].tipo, TipoDocumentoReferenciado::NotaFiscalProdutor);
+        assert_eq!(docs[1].numero.as_deref(), Some("778"));
+        assert_eq!(docs[1].serie.as_deref(), Some("2"));
+        assert_eq!(docs[2].tipo, TipoDocumentoReferenciado::OutrosDocumentos);
+        assert_eq!(docs[2].numero.as_deref(), Some("D-1"));
+    }
+
+    #[test]
+    fn test_cte_parser_modal() {
+        let parser = CTeParser::new();
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <ide><modal>01</modal></ide>
+            <infCTeNorm>
+                <infModal versaoModal="3.00">
+                    <rodo>
+                        <RNTRC>12345678</RNTRC>
+                        <CIOT>123456789012</CIOT>
+                        <veic>
+                            <placa>ABC1D23</placa>
+                            <RENAVAM>123456789</RENAVAM>
+                            <tara>8000</tara>
+                            <capKG>25000</capKG>
+                            <tpRod>03</tpRod>
+                            <prop><CPF>11144477735</CPF><UF>MG</UF></prop>
+                            <UF>SP</UF>
+                        </veic>
+                        <veic>
+                            <placa>XYZ9A87</placa>
+                            <UF>PR</UF>
+                        </veic>
+                    </rodo>
+                </infModal>
+            </infCTeNorm>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        assert_eq!(cte.modal, Modal::Rodoviario);
+        let Some(DadosModal::Rodoviario(rodo)) = cte.dados_modal else {
+            panic!("modal rodoviário esperado");
+        };
+        assert_eq!(rodo.rntrc, "12345678");
+        assert_eq!(rodo.ciot.as_deref(), Some("123456789012"));
+        assert_eq!(rodo.veiculos.len(), 2);
+        assert_eq!(rodo.veiculos[0].placa, "ABC1D23");
+        assert_eq!(rodo.veiculos[0].uf, "SP");
+        assert_eq!(rodo.veiculos[0].capacidade_kg, 25000.0);
+        assert_eq!(rodo.veiculos[1].placa, "XYZ9A87");
+        
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <ide><modal>02</modal></ide>
+            <infCTeNorm>
+                <infModal versaoModal="3.00">
+                    <aereo>
+                        <nMinu>000123456</nMinu>
+                        <nOCA>12345678901</nOCA>
+                        <dPrevAereo>2021-01-20</dPrevAereo>
+                        <tarifa><CL>G</CL><cTar>0001</cTar><vTarifa>12.50</vTarifa></tarifa>
+                    </aereo>
+                </infModal>
+            </infCTeNorm>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        assert_eq!(cte.modal, Modal::Aereo);
+        let Some(DadosModal::Aereo(aereo)) = cte.dados_modal else {
+            panic!("modal aéreo esperado");
+        };
+        assert_eq!(aereo.numero_awb.as_deref(), Some("12345678901"));
+        assert_eq!(aereo.classe_tarifa.as_deref(), Some("G"));
+        assert_eq!(aereo.valor_tarifa, 12.5);
+        
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <ide><modal>03</modal></ide>
+            <infCTeNorm>
+                <infModal versaoModal="3.00">
+                    <aquav>
+                        <vPrest>1500.00</vPrest>
+                        <vAFRMM>375.00</vAFRMM>
+                        <xNavio>Navio Teste</xNavio>
+                        <nViag>42</nViag>
+                        <direc>N</direc>
+                        <prtEmb>BRSSZ</prtEmb>
+                        <prtDest>BRMAO</prtDest>
+                    </aquav>
+                </infModal>
+            </infCTeNorm>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        assert_eq!(cte.modal, Modal::Aquaviario);
+        let Some(DadosModal::Aquaviario(aquav)) = cte.dados_modal else {
+            panic!("modal aquaviário esperado");
+        };
+        assert_eq!(aquav.nome_navio, "Navio Teste");
+        assert_eq!(aquav.porto_embarque.as_deref(), Some("BRSSZ"));
+        assert_eq!(aquav.porto_destino.as_deref(), Some("BRMAO"));
+        assert_eq!(aquav.valor_afrmm, 375.0);
+    }
+    
+    #[test]
+    fn test_cte_parser_protocolo() {
+        let parser = CTeParser::new();
+        let cte = parser.parse_string(include_str!("../../test_data/sample_cte.xml")).unwrap();
+        assert_eq!(cte.status, ProcessingStatus::Completed);
+        assert_eq!(cte.protocolo_autorizacao.as_deref(), Some("135210012345679"));
+        let protocolo = cte.protocolo.as_ref().unwrap();
+        assert_eq!(protocolo.chave_acesso, cte.chave_acesso);
+        assert_eq!(protocolo.motivo, "Autorizado o uso do CT-e");
+        
+        let rejeitado = r#"<cteProc>
+            <CTe><infCte Id="CTe35210112345678901234567890123456789012345678"></infCte></CTe>
+            <protCTe><infProt><cStat>539</cStat><xMotivo>Rejeição: Duplicidade de CT-e</xMotivo></infProt></protCTe>
+        </cteProc>"#;
+        let cte = parser.parse_string(rejeitado).unwrap();
+        assert_eq!(cte.status, ProcessingStatus::Unauthorized);
+        assert!(cte.protocolo_autorizacao.is_none());
+    }
+    
+    #[test]
+    fn test_cte_parser_prestacao_e_impostos() {
+        let parser = CTeParser::new();
+        let cte = parser.parse_string(include_str!("../../test_data/sample_cte.xml")).unwrap();
+        let componentes = &cte.valores_prestacao.componentes;
+        assert_eq!(componentes.len(), 2);
+        assert_eq!(componentes[0].nome, "FRETE PESO");
+        assert_eq!(componentes[0].valor, 450.0);
+        assert_eq!(componentes[1].nome, "PEDAGIO");
+        let icms = cte.impostos.icms.as_ref().unwrap();
+        assert_eq!(icms.grupo, "ICMS00");
+        assert_eq!(icms.cst, "00");
+        assert_eq!(icms.base_calculo, 500.0);
+        assert_eq!(icms.aliquota, 12.0);
+        assert_eq!(icms.valor, 60.0);
+        
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <imp>
+                <ICMS>
+                    <ICMSOutraUF>
+                        <CST>90</CST>
+                        <pRedBCOutraUF>10.00</pRedBCOutraUF>
+                        <vBCOutraUF>900.00</vBCOutraUF>
+                        <pICMSOutraUF>7.00</pICMSOutraUF>
+                        <vICMSOutraUF>63.00</vICMSOutraUF>
+                    </ICMSOutraUF>
+                </ICMS>
+                <vTotTrib>120.50</vTotTrib>
+                <ICMSUFFim>
+                    <vBCUFFim>1000.00</vBCUFFim>
+                    <pFCPUFFim>2.00</pFCPUFFim>
+                    <pICMSUFFim>18.00</pICMSUFFim>
+                    <pICMSInter>7.00</pICMSInter>
+                    <vFCPUFFim>20.00</vFCPUFFim>
+                    <vICMSUFFim>110.00</vICMSUFFim>
+                    <vICMSUFIni>0.00</vICMSUFIni>
+                </ICMSUFFim>
+            </imp>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        let icms = cte.impostos.icms.as_ref().unwrap();
+        assert_eq!(icms.grupo, "ICMSOutraUF");
+        assert_eq!(icms.percentual_reducao_bc, Some(10.0));
+        assert_eq!(icms.base_calculo, 900.0);
+        assert_eq!(icms.valor, 63.0);
+        assert_eq!(cte.impostos.valor_total_tributos, 120.5);
+        let uf_fim = cte.impostos.icms_uf_fim.as_ref().unwrap();
+        assert_eq!(uf_fim.valor_uf_fim, 110.0);
+        assert_eq!(uf_fim.aliquota_interestadual, 7.0);
+    }
+    
+    #[test]
+    fn test_cte_parser_quantidades_carga() {
+        let parser = CTeParser::new();
+        let cte = parser.parse_string(include_str!("../../test_data/sample_cte.xml")).unwrap();
+        assert_eq!(cte.informacoes_carga.quantidades.len(), 1);
+        assert_eq!(cte.informacoes_carga.peso_bruto, 1000.0);
+        assert_eq!(cte.valor_frete_por_kg(), Some(0.5));
+        
+        let xml = r#"<CTe><infCte Id="CTe35210112345678901234567890123456789012345678">
+            <vPrest><vTPrest>900.00</vTPrest></vPrest>
+            <infCTeNorm>
+                <infCarga>
+                    <vCarga>10000.00</vCarga>
+                    <infQ><cUnid>01</cUnid><tpMed>PESO DECLARADO</tpMed><qCarga>1200.0000</qCarga></infQ>
+                    <infQ><cUnid>02</cUnid><tpMed>PESO BRUTO</tpMed><qCarga>1.2500</qCarga></infQ>
+                    <infQ><cUnid>01</cUnid><tpMed>PESO CUBADO</tpMed><qCarga>1800.0000</qCarga></infQ>
+                    <infQ><cUnid>00</cUnid><tpMed>M3</tpMed><qCarga>6.0000</qCarga></infQ>
+                    <infQ><cUnid>03</cUnid><tpMed>VOLUMES</tpMed><qCarga>40.0000</qCarga></infQ>
+                </infCarga>
+            </infCTeNorm>
+        </infCte></CTe>"#;
+        let cte = parser.parse_string(xml).unwrap();
+        let carga = &cte.informacoes_carga;
+        assert_eq!(carga.quantidades.len(), 5);
+        assert_eq!(carga.quantidades[1].tipo_medida, "PESO BRUTO");
+        assert_eq!(carga.peso_bruto, 1250.0);
+        assert_eq!(carga.peso_cubado, Some(1800.0));
+        assert_eq!(carga.volume_m3, Some(6.0));
+        assert_eq!(carga.quantidade_volumes, Some(40.0));
+        assert_eq!(cte.valor_frete_por_kg(), Some(0.5));
+    }
+    
+    #[test]
+    fn test_cte_os_parser() {
+        let parser = CTeParser::new();
+        let cte = parser.parse_string(include_str!("../../test_data/sample_cte_os.xml")).unwrap();
+        
+        assert_eq!(cte.modelo, ModeloConhecimento::CTeOS);
+        assert_eq!(cte.document_type(), DocumentType::ConhecimentoTransporteOS);
+        assert_eq!(cte.tipo_servico, TipoServicoCTe::TransportePessoas);
+        assert_eq!(cte.status, ProcessingStatus::Completed);
+        
+        let tomador = cte.tomador.as_ref().unwrap();
+        assert_eq!(tomador.participante.cnpj_cpf, "98765432000198");
+        assert_eq!(tomador.participante.endereco.municipio, "Campinas");
+        
+        let servico = cte.servico.as_ref().unwrap();
+        assert_eq!(servico.descricao, "Fretamento eventual de passageiros");
+        assert_eq!(servico.quantidade, Some(42.0));
+        assert_eq!(cte.valores_prestacao.valor_total, 1200.0);
+        assert_eq!(cte.impostos.icms.as_ref().unwrap().valor, 144.0);
+        
+        let Some(DadosModal::RodoviarioOS(rodo)) = cte.dados_modal else {
+            panic!("modal rodoOS esperado");
+        };
+        assert_eq!(rodo.taf.as_deref(), Some("123456789012"));
+        assert_eq!(rodo.tipo_fretamento.as_deref(), Some("1"));
+        let veiculo = rodo.veiculo.unwrap();
+        assert_eq!(veiculo.placa, "BUS2A34");
+        // UF do proprietário (prop) não sobrescreve a do veículo
+        assert_eq!(veiculo.uf, "SP");
+    }
+    
+    #[test]
+    fn test_cte_simplificado_parser() {
+        let parser = CTeParser::new();
+        let cte = parser.parse_string(include_str!("../../test_data/sample_cte_simp.xml")).unwrap();
+        
+        assert_eq!(cte.modelo, ModeloConhecimento::CTe);
+        assert_eq!(cte.tipo_cte, TipoCTe::Simplificado);
+        assert_eq!(cte.document_type(), DocumentType::ConhecimentoTransporteSimplificado);
+        
+        let tomador = cte.tomador.as_ref().unwrap();
+        assert_eq!(tomador.tipo, TipoTomador::Remetente);
+        assert_eq!(tomador.participante.cnpj_cpf, "11222333000181");
+        
+        assert_eq!(cte.entregas.len(), 2);
+        let santos = &cte.entregas[1];
+        assert_eq!(santos.municipio_fim, "Santos");
+        assert_eq!(santos.valor_prestacao, 200.0);
+        assert_eq!(santos.componentes.len(), 2);
+        assert_eq!(santos.componentes[1].nome, "PEDAGIO");
+        assert_eq!(santos.chaves_nfe, vec!["35210112345678000190550010000000021123456781"]);
+        assert_eq!(cte.documentos_referenciados.len(), 2);
+        assert!(cte.valores_prestacao.componentes.is_empty());
+        
+        assert_eq!(cte.valores_prestacao.valor_total, 500.0);
+        assert_eq!(cte.valores_prestacao.valor_receber, 500.0);
+        assert_eq!(cte.informacoes_carga.peso_bruto, 600.0);
+    }
 }
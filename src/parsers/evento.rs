@@ -0,0 +1,216 @@
+use crate::error::{GeolocationError, Result};
+use crate::models::*;
+use crate::parsers::{parse_protocolo_field, FiscalDocumentParser};
+use chrono::{DateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Parser de eventos de NF-e/NFC-e (`procEventoNFe`) e CT-e (`procEventoCTe`)
+pub struct EventoParser;
+
+impl EventoParser {
+    pub fn new() -> Self {
+        EventoParser
+    }
+
+    fn parse_xml_content(&self, xml: &str) -> Result<EventoFiscal> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut current_path = Vec::new();
+
+        let mut chave_acesso = String::new();
+        let mut codigo = String::new();
+        let mut sequencia = 1;
+        let mut data_evento = String::new();
+        let mut orgao = String::new();
+        let mut autor_cnpj_cpf = String::new();
+        let mut descricao = String::new();
+        let mut protocolo_documento: Option<String> = None;
+        let mut justificativa: Option<String> = None;
+        let mut correcao: Option<String> = None;
+        let mut correcoes: Vec<CorrecaoCTe> = Vec::new();
+
+        // Retorno do registro (retEvento/retEventoCTe)
+        let mut protocolo: Option<ProtocoloAutorizacao> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    if name == "infCorrecao" {
+                        correcoes.push(CorrecaoCTe::default());
+                    }
+                    current_path.push(name);
+                }
+                Ok(Event::End(_)) => {
+                    current_path.pop();
+                }
+                Ok(Event::Text(e)) => {
+                    let text = e.unescape().unwrap_or_default().to_string();
+
+                    if let Some(tag) = current_path.last() {
+                        match tag.as_str() {
+                            _ if current_path.iter().any(|p| p.starts_with("retEvento")) => {
+                                let protocolo = protocolo.get_or_insert_with(Default::default);
+                                parse_protocolo_field(tag, &text, protocolo)
+                            }
+                            _ if current_path.contains(&"infCorrecao".to_string()) => {
+                                if let Some(item) = correcoes.last_mut() {
+                                    match tag.as_str() {
+                                        "grupoAlterado" => item.grupo = text,
+                                        "campoAlterado" => item.campo = text,
+                                        "valorAlterado" => item.valor = text,
+                                        "nroItemAlterado" => item.numero_item = text.parse().ok(),
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            "chNFe" | "chCTe" => chave_acesso = text,
+                            "tpEvento" => codigo = text,
+                            "nSeqEvento" => sequencia = text.parse().unwrap_or(1),
+                            "dhEvento" => data_evento = text,
+                            "cOrgao" => orgao = text,
+                            "CNPJ" | "CPF" => autor_cnpj_cpf = text,
+                            "descEvento" => descricao = text,
+                            "nProt" => protocolo_documento = Some(text),
+                            "xJust" | "xObs" => justificativa = Some(text),
+                            "xCorrecao" => correcao = Some(text),
+                            _ => {}
+                        }
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(GeolocationError::XmlParseError(format!(
+                        "Erro ao parsear evento: {}",
+                        e
+                    )))
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if chave_acesso.is_empty() {
+            return Err(GeolocationError::InvalidAccessKey(
+                "Chave do documento do evento não encontrada".into(),
+            ));
+        }
+        ChaveAcesso::parse(&chave_acesso)?;
+
+        let data_evento = DateTime::parse_from_rfc3339(&data_evento)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+
+        Ok(EventoFiscal {
+            id: Uuid::new_v4(),
+            chave_acesso,
+            tipo: TipoEvento::from_codigo(&codigo),
+            codigo,
+            sequencia,
+            data_evento,
+            orgao,
+            autor_cnpj_cpf,
+            descricao,
+            protocolo_documento,
+            justificativa,
+            correcao,
+            correcoes,
+            protocolo,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+impl FiscalDocumentParser for EventoParser {
+    type Output = EventoFiscal;
+
+    fn parse_file(&self, path: &Path) -> Result<Self::Output> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| GeolocationError::XmlReadError(e.to_string()))?;
+        self.parse_string(&content)
+    }
+
+    fn parse_bytes(&self, data: &[u8]) -> Result<Self::Output> {
+        let content = String::from_utf8(data.to_vec())
+            .map_err(|e| GeolocationError::EncodingError(e.to_string()))?;
+        self.parse_string(&content)
+    }
+
+    fn parse_string(&self, xml: &str) -> Result<Self::Output> {
+        self.parse_xml_content(xml)
+    }
+}
+
+impl Default for EventoParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evento_cancelamento_nfe() {
+        let parser = EventoParser::new();
+        let evento = parser
+            .parse_string(include_str!("../../test_data/sample_evento_cancelamento.xml"))
+            .unwrap();
+
+        assert_eq!(evento.chave_acesso, "35210112345678000190550010000123451123456788");
+        assert_eq!(evento.tipo, TipoEvento::Cancelamento);
+        assert_eq!(evento.codigo, "110111");
+        assert_eq!(evento.sequencia, 1);
+        assert_eq!(evento.autor_cnpj_cpf, "12345678000190");
+        assert_eq!(evento.protocolo_documento.as_deref(), Some("135210000012345"));
+        assert_eq!(evento.justificativa.as_deref(), Some("Erro na emissão da nota fiscal"));
+        assert!(evento.registrado());
+        assert_eq!(evento.protocolo.as_ref().unwrap().numero.as_deref(), Some("135210000099999"));
+        assert_eq!(evento.status_resultante(), Some(ProcessingStatus::Cancelled));
+
+        let mut status = ProcessingStatus::Completed;
+        let mut historico = Vec::new();
+        evento.aplicar(&mut status, &mut historico);
+        evento.aplicar(&mut status, &mut historico);
+        assert_eq!(status, ProcessingStatus::Cancelled);
+        assert_eq!(historico.len(), 1);
+    }
+
+    #[test]
+    fn test_evento_carta_correcao_cte() {
+        let xml = r#"<procEventoCTe versao="4.00">
+            <eventoCTe versao="4.00"><infEvento Id="ID1101103521011234567890123456789012345678901234567801">
+                <cOrgao>35</cOrgao><tpAmb>1</tpAmb><CNPJ>12345678000190</CNPJ>
+                <chCTe>35210112345678901234567890123456789012345678</chCTe>
+                <dhEvento>2021-01-16T10:00:00-03:00</dhEvento><tpEvento>110110</tpEvento><nSeqEvento>2</nSeqEvento>
+                <detEvento versaoEvento="4.00"><evCCeCTe>
+                    <descEvento>Carta de Correcao</descEvento>
+                    <infCorrecao><grupoAlterado>ide</grupoAlterado><campoAlterado>natOp</campoAlterado><valorAlterado>Frete</valorAlterado></infCorrecao>
+                    <infCorrecao><grupoAlterado>compl</grupoAlterado><campoAlterado>xObs</campoAlterado><valorAlterado>Entrega agendada</valorAlterado></infCorrecao>
+                    <xCondUso>A Carta de Correcao e disciplinada...</xCondUso>
+                </evCCeCTe></detEvento>
+            </infEvento></eventoCTe>
+            <retEventoCTe versao="4.00"><infEvento>
+                <tpAmb>1</tpAmb><cOrgao>35</cOrgao><cStat>135</cStat><xMotivo>Evento registrado e vinculado ao CT-e</xMotivo>
+                <chCTe>35210112345678901234567890123456789012345678</chCTe><tpEvento>110110</tpEvento><nSeqEvento>2</nSeqEvento>
+                <dhRegEvento>2021-01-16T10:00:05-03:00</dhRegEvento><nProt>135210000088888</nProt>
+            </infEvento></retEventoCTe>
+        </procEventoCTe>"#;
+        let evento = EventoParser::new().parse_string(xml).unwrap();
+
+        assert_eq!(evento.tipo, TipoEvento::CartaCorrecao);
+        assert_eq!(evento.sequencia, 2);
+        assert_eq!(evento.correcoes.len(), 2);
+        assert_eq!(evento.correcoes[1].valor, "Entrega agendada");
+        assert!(evento.protocolo_documento.is_none());
+        assert!(evento.protocolo.as_ref().unwrap().data_recebimento.is_some());
+        assert_eq!(evento.status_resultante(), None);
+    }
+}
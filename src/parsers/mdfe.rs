@@ -0,0 +1,305 @@
+use crate::error::{GeolocationError, Result};
+use crate::models::*;
+use crate::parsers::{
+    chave_documento, finalize_participante, parse_identificacao_field, parse_participante_field,
+    parse_protocolo_field, parse_veiculo_field, status_protocolo, FiscalDocumentParser,
+};
+use chrono::{DateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+pub struct MDFeParser;
+
+impl MDFeParser {
+    pub fn new() -> Self {
+        MDFeParser
+    }
+
+    fn parse_xml_content(&self, xml: &str) -> Result<ManifestoDocumentos> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut current_path = Vec::new();
+
+        // Dados principais
+        let mut numero = String::new();
+        let mut serie = String::new();
+        let mut data_emissao = String::new();
+        let mut data_inicio_viagem: Option<DateTime<Utc>> = None;
+        let mut identificacao = IdentificacaoEmissao::default();
+        let mut modal = Modal::Rodoviario;
+        let mut emitente = Participante::default();
+        let mut informacoes_adicionais: Option<String> = None;
+
+        // Percurso
+        let mut uf_inicio = String::new();
+        let mut uf_fim = String::new();
+        let mut percurso_ufs = Vec::new();
+        let mut municipios_carregamento: Vec<MunicipioManifesto> = Vec::new();
+        let mut municipios_descarregamento: Vec<MunicipioDescarregamento> = Vec::new();
+
+        // Modal rodoviário
+        let mut rntrc: Option<String> = None;
+        let mut veiculo_tracao: Option<VeiculoRodoviario> = None;
+        let mut veiculos_reboque: Vec<VeiculoRodoviario> = Vec::new();
+        let mut condutores: Vec<Condutor> = Vec::new();
+
+        let mut totais = TotaisManifesto::default();
+
+        // Protocolo de autorização (protMDFe)
+        let mut protocolo: Option<ProtocoloAutorizacao> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    match (current_path.last().map(String::as_str), name.as_str()) {
+                        (Some("ide"), "infMunCarrega") => {
+                            municipios_carregamento.push(MunicipioManifesto::default())
+                        }
+                        (Some("infDoc"), "infMunDescarga") => {
+                            municipios_descarregamento.push(MunicipioDescarregamento::default())
+                        }
+                        (Some("rodo"), "veicTracao") => {
+                            veiculo_tracao = Some(VeiculoRodoviario::default())
+                        }
+                        (Some("rodo"), "veicReboque") => {
+                            veiculos_reboque.push(VeiculoRodoviario::default())
+                        }
+                        (Some("veicTracao"), "condutor") => condutores.push(Condutor::default()),
+                        _ => {}
+                    }
+                    current_path.push(name);
+                }
+                Ok(Event::End(_)) => {
+                    current_path.pop();
+                }
+                Ok(Event::Text(e)) => {
+                    let text = e.unescape().unwrap_or_default().to_string();
+
+                    if let Some(tag) = current_path.last() {
+                        match tag.as_str() {
+                            "nMDF" => numero = text,
+                            "serie" => serie = text,
+                            "dhEmi" => data_emissao = text,
+                            "cUF" | "tpEmis" | "cMDF"
+                                if current_path.iter().rev().nth(1).map(String::as_str) == Some("ide") =>
+                            {
+                                parse_identificacao_field(tag, &text, &mut identificacao)
+                            }
+                            "dhIniViagem" => {
+                                data_inicio_viagem = DateTime::parse_from_rfc3339(&text)
+                                    .map(|dt| dt.with_timezone(&Utc))
+                                    .ok()
+                            }
+                            "modal" => {
+                                if let Some(m) = Self::parse_modal(&text) {
+                                    modal = m;
+                                }
+                            }
+                            "UFIni" => uf_inicio = text,
+                            "UFFim" => uf_fim = text,
+                            "UFPer" => percurso_ufs.push(text),
+                            "cMunCarrega" | "xMunCarrega" => {
+                                if let Some(municipio) = municipios_carregamento.last_mut() {
+                                    if tag == "cMunCarrega" {
+                                        municipio.codigo = text;
+                                    } else {
+                                        municipio.nome = text;
+                                    }
+                                }
+                            }
+                            _ if current_path.contains(&"infProt".to_string()) => {
+                                let protocolo = protocolo.get_or_insert_with(Default::default);
+                                parse_protocolo_field(tag, &text, protocolo)
+                            }
+                            _ if current_path.contains(&"emit".to_string()) => {
+                                parse_participante_field(tag, &text, &mut emitente)
+                            }
+                            // Campos do proprietário (prop) não pertencem ao veículo
+                            _ if current_path.contains(&"prop".to_string()) => {}
+                            _ if current_path.contains(&"condutor".to_string()) => {
+                                if let Some(condutor) = condutores.last_mut() {
+                                    match tag.as_str() {
+                                        "xNome" => condutor.nome = text,
+                                        "CPF" => condutor.cpf = text,
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            _ if current_path.contains(&"veicTracao".to_string()) => {
+                                if let Some(veiculo) = veiculo_tracao.as_mut() {
+                                    parse_veiculo_field(tag, &text, veiculo);
+                                }
+                            }
+                            _ if current_path.contains(&"veicReboque".to_string()) => {
+                                if let Some(veiculo) = veiculos_reboque.last_mut() {
+                                    parse_veiculo_field(tag, &text, veiculo);
+                                }
+                            }
+                            "RNTRC" => rntrc = Some(text),
+                            _ if current_path.contains(&"infMunDescarga".to_string()) => {
+                                if let Some(municipio) = municipios_descarregamento.last_mut() {
+                                    match tag.as_str() {
+                                        "cMunDescarga" => municipio.codigo = text,
+                                        "xMunDescarga" => municipio.nome = text,
+                                        "chCTe" => municipio.chaves_cte.push(text),
+                                        "chNFe" => municipio.chaves_nfe.push(text),
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            _ if current_path.contains(&"tot".to_string()) => {
+                                match tag.as_str() {
+                                    "qCTe" => totais.quantidade_cte = text.parse().unwrap_or(0),
+                                    "qNFe" => totais.quantidade_nfe = text.parse().unwrap_or(0),
+                                    "vCarga" => totais.valor_carga = text.parse().unwrap_or(0.0),
+                                    "cUnid" => totais.codigo_unidade = text,
+                                    "qCarga" => totais.quantidade_carga = text.parse().unwrap_or(0.0),
+                                    _ => {}
+                                }
+                            }
+                            "infCpl" => informacoes_adicionais = Some(text),
+                            _ => {}
+                        }
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(GeolocationError::XmlParseError(format!(
+                        "Erro ao parsear MDF-e: {}",
+                        e
+                    )))
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        // Extrai a chave de acesso
+        let chave_acesso = chave_documento(xml)?;
+
+        finalize_participante(&mut emitente);
+
+        // Parse data de emissão
+        let data_emissao_parsed = DateTime::parse_from_rfc3339(&data_emissao)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+
+        let status = status_protocolo(protocolo.as_ref(), &chave_acesso);
+
+        Ok(ManifestoDocumentos {
+            id: Uuid::new_v4(),
+            chave_acesso,
+            numero,
+            serie,
+            data_emissao: data_emissao_parsed,
+            identificacao,
+            data_inicio_viagem,
+            modal,
+            emitente,
+            uf_inicio,
+            uf_fim,
+            percurso_ufs,
+            municipios_carregamento,
+            municipios_descarregamento,
+            rntrc,
+            veiculo_tracao,
+            veiculos_reboque,
+            condutores,
+            totais,
+            informacoes_adicionais,
+            protocolo_autorizacao: protocolo.as_ref().and_then(|p| p.numero.clone()),
+            protocolo,
+            status,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Converte o código `modal` do `ide` do MDF-e (1 a 4)
+    fn parse_modal(codigo: &str) -> Option<Modal> {
+        match codigo.trim_start_matches('0') {
+            "1" => Some(Modal::Rodoviario),
+            "2" => Some(Modal::Aereo),
+            "3" => Some(Modal::Aquaviario),
+            "4" => Some(Modal::Ferroviario),
+            _ => None,
+        }
+    }
+}
+
+impl FiscalDocumentParser for MDFeParser {
+    type Output = ManifestoDocumentos;
+
+    fn parse_file(&self, path: &Path) -> Result<Self::Output> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| GeolocationError::XmlReadError(e.to_string()))?;
+        self.parse_string(&content)
+    }
+
+    fn parse_bytes(&self, data: &[u8]) -> Result<Self::Output> {
+        let content = String::from_utf8(data.to_vec())
+            .map_err(|e| GeolocationError::EncodingError(e.to_string()))?;
+        self.parse_string(&content)
+    }
+
+    fn parse_string(&self, xml: &str) -> Result<Self::Output> {
+        self.parse_xml_content(xml)
+    }
+}
+
+impl Default for MDFeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mdfe_parser() {
+        let parser = MDFeParser::new();
+        let mdfe = parser.parse_string(include_str!("../../test_data/sample_mdfe.xml")).unwrap();
+
+        assert_eq!(mdfe.chave_acesso, "35210112345678000190580010000000011123456785");
+        assert_eq!(mdfe.numero, "1");
+        assert_eq!(mdfe.modal, Modal::Rodoviario);
+        assert_eq!(mdfe.status, ProcessingStatus::Completed);
+        assert_eq!(mdfe.protocolo_autorizacao.as_deref(), Some("935210000012345"));
+        assert_eq!(mdfe.emitente.cnpj_cpf, "12345678000190");
+        assert_eq!(mdfe.emitente.endereco.uf, "SP");
+
+        assert_eq!((mdfe.uf_inicio.as_str(), mdfe.uf_fim.as_str()), ("SP", "RS"));
+        assert_eq!(mdfe.percurso_ufs, vec!["PR", "SC"]);
+        assert_eq!(mdfe.municipios_carregamento.len(), 1);
+        assert_eq!(mdfe.municipios_carregamento[0].codigo, "3550308");
+        assert!(mdfe.data_inicio_viagem.is_some());
+
+        assert_eq!(mdfe.rntrc.as_deref(), Some("12345678"));
+        let tracao = mdfe.veiculo_tracao.as_ref().unwrap();
+        assert_eq!(tracao.placa, "ABC1D23");
+        assert_eq!(tracao.uf, "SP");
+        assert_eq!(tracao.capacidade_kg, 25000.0);
+        assert_eq!(mdfe.veiculos_reboque.len(), 1);
+        assert_eq!(mdfe.veiculos_reboque[0].uf, "PR");
+        assert_eq!(mdfe.condutores.len(), 1);
+        assert_eq!(mdfe.condutores[0].nome, "João da Silva");
+        assert_eq!(mdfe.condutores[0].cpf, "52998224725");
+
+        let descarga = &mdfe.municipios_descarregamento[0];
+        assert_eq!(descarga.nome, "Porto Alegre");
+        assert_eq!(mdfe.chaves_cte().collect::<Vec<_>>(), vec![
+            "35210112345678000190570010000123451123456785"
+        ]);
+        assert_eq!(mdfe.chaves_nfe().count(), 1);
+        assert_eq!(mdfe.totais.quantidade_cte, 1);
+        assert_eq!(mdfe.totais.quantidade_carga, 1000.0);
+        assert_eq!(mdfe.informacoes_adicionais.as_deref(), Some("Viagem de teste"));
+    }
+}
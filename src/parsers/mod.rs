@@ -1,8 +1,14 @@
 pub mod nfe;
 pub mod cte;
+pub mod mdfe;
+pub mod evento;
+pub mod nfse;
 
-use crate::error::Result;
-use crate::models::{ConhecimentoTransporte, NotaFiscal};
+use crate::error::{GeolocationError, Result};
+use crate::models::{
+    ChaveAcesso, IdentificacaoEmissao, Participante, ProtocoloAutorizacao, ProcessingStatus, VeiculoRodoviario,
+};
+use chrono::{DateTime, Utc};
 use std::path::Path;
 
 /// Parser trait para documentos fiscais
@@ -14,10 +20,131 @@ pub trait FiscalDocumentParser {
     fn parse_string(&self, xml: &str) -> Result<Self::Output>;
 }
 
+/// Preenche um campo de participante (emit, dest, rem, exped, receb) a partir da tag.
+/// Os campos do grupo de endereço (`enderEmit`, `enderDest`, `enderReme`...) também são
+/// tratados aqui, já que usam os mesmos nomes em NF-e e CT-e.
+pub(crate) fn parse_participante_field(tag: &str, text: &str, participante: &mut Participante) {
+    let endereco = &mut participante.endereco;
+    match tag {
+        "CNPJ" | "CPF" => participante.cnpj_cpf = text.to_string(),
+        "idEstrangeiro" => participante.id_estrangeiro = Some(text.to_string()),
+        "xNome" => participante.razao_social = text.to_string(),
+        "xFant" => participante.nome_fantasia = Some(text.to_string()),
+        "IE" => participante.inscricao_estadual = Some(text.to_string()),
+        "fone" => participante.telefone = Some(text.to_string()),
+        "email" => participante.email = Some(text.to_string()),
+        "xLgr" => endereco.logradouro = text.to_string(),
+        "nro" => endereco.numero = text.to_string(),
+        "xCpl" => endereco.complemento = Some(text.to_string()),
+        "xBairro" => endereco.bairro = text.to_string(),
+        "cMun" => endereco.codigo_municipio = text.to_string(),
+        "xMun" => endereco.municipio = text.to_string(),
+        "UF" => endereco.uf = text.to_string(),
+        "CEP" => endereco.cep = text.to_string(),
+        "cPais" => endereco.codigo_pais = text.to_string(),
+        "xPais" => endereco.pais = text.to_string(),
+        _ => {}
+    }
+}
+
+/// Completa o país de endereços nacionais, onde `cPais`/`xPais` são opcionais no XML
+pub(crate) fn finalize_participante(participante: &mut Participante) {
+    let endereco = &mut participante.endereco;
+    if endereco.codigo_pais.is_empty() && endereco.uf != "EX" {
+        endereco.codigo_pais = "1058".to_string();
+    }
+    if endereco.pais.is_empty() && endereco.codigo_pais == "1058" {
+        endereco.pais = "Brasil".to_string();
+    }
+}
+
+/// Preenche um campo do `infProt` do protocolo de autorização
+pub(crate) fn parse_protocolo_field(tag: &str, text: &str, protocolo: &mut ProtocoloAutorizacao) {
+    match tag {
+        "tpAmb" => protocolo.ambiente = Some(text.to_string()),
+        "verAplic" => protocolo.versao_aplicativo = Some(text.to_string()),
+        "chNFe" | "chCTe" | "chMDFe" => protocolo.chave_acesso = text.to_string(),
+        "dhRecbto" | "dhRegEvento" => {
+            protocolo.data_recebimento = DateTime::parse_from_rfc3339(text)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+        }
+        "nProt" => protocolo.numero = Some(text.to_string()),
+        "digVal" => protocolo.digest_value = Some(text.to_string()),
+        "cStat" => protocolo.codigo_status = text.to_string(),
+        "xMotivo" => protocolo.motivo = text.to_string(),
+        _ => {}
+    }
+}
+
+/// Preenche um campo de `IdentificacaoEmissao`. Deve ser chamado só para filhos diretos
+/// de `ide`, já que `cUF` também aparece nas referências (`NFref`).
+pub(crate) fn parse_identificacao_field(
+    tag: &str,
+    text: &str,
+    identificacao: &mut IdentificacaoEmissao,
+) {
+    match tag {
+        "cUF" => identificacao.codigo_uf = text.to_string(),
+        "tpEmis" => identificacao.tipo_emissao = text.to_string(),
+        "cNF" | "cCT" | "cMDF" => identificacao.codigo_numerico = text.to_string(),
+        _ => {}
+    }
+}
+
+/// Preenche um campo de veículo rodoviário (`veic`, `veicTracao`, `veicReboque`).
+/// Os campos do proprietário (`prop`) devem ser filtrados por quem chama.
+pub(crate) fn parse_veiculo_field(tag: &str, text: &str, veiculo: &mut VeiculoRodoviario) {
+    let texto = || Some(text.to_string());
+    let valor = || text.parse().unwrap_or(0.0);
+    match tag {
+        "placa" => veiculo.placa = text.to_string(),
+        "RENAVAM" => veiculo.renavam = texto(),
+        "UF" => veiculo.uf = text.to_string(),
+        "tara" => veiculo.tara_kg = valor(),
+        "capKG" => veiculo.capacidade_kg = valor(),
+        "capM3" => veiculo.capacidade_m3 = valor(),
+        "tpVeic" => veiculo.tipo_veiculo = texto(),
+        "tpRod" => veiculo.tipo_rodado = texto(),
+        "tpCar" => veiculo.tipo_carroceria = texto(),
+        _ => {}
+    }
+}
+
+/// Status do documento conforme o protocolo: sem protocolo, não autorizado ou com protocolo de
+/// outra chave fica `Unauthorized`
+pub(crate) fn status_protocolo(
+    protocolo: Option<&ProtocoloAutorizacao>,
+    chave: &str,
+) -> ProcessingStatus {
+    match protocolo {
+        Some(p) if p.autorizado() && p.chave_acesso == chave => ProcessingStatus::Completed,
+        _ => ProcessingStatus::Unauthorized,
+    }
+}
+
 /// Detecta o tipo de documento fiscal baseado no conteúdo XML
 pub fn detect_document_type(xml: &str) -> Option<String> {
-    if xml.contains("<nfeProc") || xml.contains("<NFe") {
-        Some("NFe".to_string())
+    // Eventos citam a chave em `chNFe`/`chCTe`, sem conter o documento em si
+    if xml.contains("<procEvento") || xml.contains("<evento") {
+        return Some("Evento".to_string());
+    }
+    // NFS-e não tem chave de 44 dígitos; `<NFSe` não colide com a busca por `<NFe`
+    if xml.contains("<NFSe") || xml.contains("CompNfse") || xml.contains("InfNfse") {
+        return Some("NFSe".to_string());
+    }
+    // MDF-e vem antes: o manifesto lista chaves de NF-e e CT-e em `infDoc`
+    if xml.contains("<mdfeProc") || xml.contains("<MDFe") {
+        Some("MDFe".to_string())
+    } else if xml.contains("<cteOSProc") || xml.contains("<CTeOS") {
+        Some("CTeOS".to_string())
+    } else if xml.contains("<cteSimpProc") || xml.contains("<CTeSimp") {
+        Some("CTeSimp".to_string())
+    } else if xml.contains("<nfeProc") || xml.contains("<NFe") {
+        match modelo_documento(xml).as_deref() {
+            Some("65") => Some("NFCe".to_string()),
+            _ => Some("NFe".to_string()),
+        }
     } else if xml.contains("<cteProc") || xml.contains("<CTe") {
         Some("CTe".to_string())
     } else {
@@ -25,33 +152,38 @@ pub fn detect_document_type(xml: &str) -> Option<String> {
     }
 }
 
-/// Valida a chave de acesso de documentos fiscais (44 dígitos)
+/// Modelo do documento: `mod` do `ide` ou, na falta dele, as posições 21-22 da chave
+pub fn modelo_documento(xml: &str) -> Option<String> {
+    xml.find("<mod>")
+        .and_then(|i| xml.get(i + 5..i + 7))
+        .map(str::to_string)
+        .or_else(|| extract_access_key(xml).map(|chave| chave[20..22].to_string()))
+}
+
+/// Valida a chave de acesso de documentos fiscais (44 dígitos e DV em módulo 11)
 pub fn validate_access_key(key: &str) -> bool {
-    if key.len() != 44 {
-        return false;
-    }
-    
-    key.chars().all(|c| c.is_ascii_digit())
+    ChaveAcesso::valida(key)
 }
 
 /// Extrai a chave de acesso do XML
 pub fn extract_access_key(xml: &str) -> Option<String> {
     // Procura por padrões comuns de chave de acesso
+    // O `Id` do documento vem antes: `chNFe`/`chCTe` do protocolo podem ser de outra nota
     let patterns = [
-        "<chNFe>",
-        "<chCTe>",
+        "Id=\"MDFe",
         "Id=\"NFe",
         "Id=\"CTe",
+        "<chNFe>",
+        "<chCTe>",
     ];
     
     for pattern in &patterns {
         if let Some(start) = xml.find(pattern) {
             let start_pos = start + pattern.len();
-            let end_pos = start_pos + 44;
             
-            if end_pos <= xml.len() {
-                let key = &xml[start_pos..end_pos];
-                if validate_access_key(key) {
+            // Só o formato: o dígito verificador é conferido pelo validador
+            if let Some(key) = xml.get(start_pos..start_pos + 44) {
+                if key.chars().all(|c| c.is_ascii_digit()) {
                     return Some(key.to_string());
                 }
             }
@@ -61,6 +193,13 @@ pub fn extract_access_key(xml: &str) -> Option<String> {
     None
 }
 
+/// Extrai a chave de acesso do documento; o dígito verificador fica para o validador
+/// (`KEY_INVALID_DIGIT`), que registra o erro junto com o documento
+pub(crate) fn chave_documento(xml: &str) -> Result<String> {
+    extract_access_key(xml)
+        .ok_or_else(|| GeolocationError::InvalidAccessKey("Chave não encontrada".into()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,6 +212,27 @@ mod tests {
         let cte_xml = r#"<?xml version="1.0"?><cteProc><CTe></CTe></cteProc>"#;
         assert_eq!(detect_document_type(cte_xml), Some("CTe".to_string()));
         
+        let cte_os_xml = r#"<cteOSProc><CTeOS><infCte></infCte></CTeOS></cteOSProc>"#;
+        assert_eq!(detect_document_type(cte_os_xml), Some("CTeOS".to_string()));
+        
+        let cte_simp_xml = r#"<cteSimpProc><CTeSimp><infCte></infCte></CTeSimp></cteSimpProc>"#;
+        assert_eq!(detect_document_type(cte_simp_xml), Some("CTeSimp".to_string()));
+        
+        let nfce_xml = r#"<nfeProc><NFe><infNFe><ide><mod>65</mod></ide></infNFe></NFe></nfeProc>"#;
+        assert_eq!(detect_document_type(nfce_xml), Some("NFCe".to_string()));
+        
+        let nfce_xml = r#"<NFe><infNFe Id="NFe35210112345678901234650010000000011000000010">"#;
+        assert_eq!(detect_document_type(nfce_xml), Some("NFCe".to_string()));
+        
+        let evento_xml = r#"<procEventoNFe><evento><infEvento><chNFe>35210112345678901234567890123456789012345678</chNFe></infEvento></evento></procEventoNFe>"#;
+        assert_eq!(detect_document_type(evento_xml), Some("Evento".to_string()));
+        
+        let nfse_xml = r#"<NFSe versao="1.00"><infNFSe Id="NFS123"></infNFSe></NFSe>"#;
+        assert_eq!(detect_document_type(nfse_xml), Some("NFSe".to_string()));
+        
+        let abrasf_xml = r#"<ns2:CompNfse><ns2:Nfse><ns2:InfNfse></ns2:InfNfse></ns2:Nfse></ns2:CompNfse>"#;
+        assert_eq!(detect_document_type(abrasf_xml), Some("NFSe".to_string()));
+        
         let invalid_xml = r#"<?xml version="1.0"?><root></root>"#;
         assert_eq!(detect_document_type(invalid_xml), None);
     }
@@ -82,5 +242,6 @@ mod tests {
         assert!(validate_access_key("35210112345678901234567890123456789012345678"));
         assert!(!validate_access_key("123"));
         assert!(!validate_access_key("3521011234567890123456789012345678901234567X"));
+        assert!(!validate_access_key("35210112345678901234567890123456789012345679"));
     }
 }
@@ -1,7 +1,10 @@
 use crate::error::{GeolocationError, Result};
 use crate::models::*;
-use crate::parsers::{extract_access_key, FiscalDocumentParser};
-use chrono::{DateTime, Utc};
+use crate::parsers::{
+    chave_documento, finalize_participante, parse_identificacao_field, parse_participante_field,
+    parse_protocolo_field, status_protocolo, FiscalDocumentParser,
+};
+use chrono::{DateTime, NaiveDate, Utc};
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use std::fs;
@@ -23,65 +26,157 @@ impl NFeParser {
         let mut current_path = Vec::new();
         
         // Dados principais
-        let mut chave_acesso = String::new();
         let mut numero = String::new();
         let mut serie = String::new();
         let mut data_emissao = String::new();
         let mut tipo_nota = TipoNota::Saida;
+        let mut modelo: Option<ModeloNotaFiscal> = None;
+        let mut identificacao = IdentificacaoEmissao::default();
+        let mut presencial = false;
+        let mut informacoes_suplementares: Option<InformacoesSuplementares> = None;
         
-        // Emitente
-        let mut emit_cnpj = String::new();
-        let mut emit_razao = String::new();
-        let mut emit_fantasia = None;
-        
-        // Destinatário
-        let mut dest_cnpj = String::new();
-        let mut dest_razao = String::new();
+        // Participantes
+        let mut emitente = Participante::default();
+        let mut destinatario = Participante::default();
         
         // Totais
-        let mut valor_total = 0.0;
-        let mut valor_produtos = 0.0;
+        let mut totais = Totais::default();
+        
+        // Transporte
+        let mut transporte = TransporteNota::default();
+        
+        // Pagamento e cobrança
+        let mut pagamento = PagamentoNota::default();
+        let mut cobranca = CobrancaNota::default();
+        
+        // Itens (det)
+        let mut itens = Vec::new();
+        let mut item_atual: Option<ItemNota> = None;
+        
+        // Protocolo de autorização (protNFe/protCTe)
+        let mut protocolo: Option<ProtocoloAutorizacao> = None;
         
         loop {
             match reader.read_event_into(&mut buf) {
                 Ok(Event::Start(e)) => {
                     let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    if name == "det" {
+                        let numero_item = e
+                            .try_get_attribute("nItem")
+                            .ok()
+                            .flatten()
+                            .and_then(|a| a.unescape_value().ok()?.parse().ok())
+                            .unwrap_or(itens.len() as i32 + 1);
+                        item_atual = Some(ItemNota {
+                            numero_item,
+                            ..Default::default()
+                        });
+                    } else {
+                        match (current_path.last().map(String::as_str), name.as_str()) {
+                            (Some("transp"), "transporta") => {
+                                transporte.transportadora = Some(Participante::default())
+                            }
+                            (Some("transp"), "veicTransp") => {
+                                transporte.veiculo = Some(VeiculoTransporte::default())
+                            }
+                            (Some("transp"), "reboque") => {
+                                transporte.reboques.push(VeiculoTransporte::default())
+                            }
+                            (Some("transp"), "vol") => {
+                                transporte.volumes.push(VolumeTransportado::default())
+                            }
+                            (Some("pag"), "detPag") => {
+                                pagamento.formas.push(FormaPagamento::default())
+                            }
+                            (Some("detPag"), "card") => {
+                                if let Some(forma) = pagamento.formas.last_mut() {
+                                    forma.cartao = Some(CartaoPagamento::default());
+                                }
+                            }
+                            (Some("cobr"), "fat") => cobranca.fatura = Some(Fatura::default()),
+                            (Some("cobr"), "dup") => cobranca.duplicatas.push(Duplicata::default()),
+                            _ => {}
+                        }
+                    }
                     current_path.push(name);
                 }
                 Ok(Event::End(_)) => {
+                    if current_path.last().map(String::as_str) == Some("det") {
+                        if let Some(item) = item_atual.take() {
+                            itens.push(item);
+                        }
+                    }
                     current_path.pop();
                 }
                 Ok(Event::Text(e)) => {
                     let text = e.unescape().unwrap_or_default().to_string();
                     
-                    if let Some(tag) = current_path.last() {
+                    if let Some(item) = item_atual.as_mut() {
+                        Self::parse_item_field(&current_path, &text, item);
+                    } else if let Some(tag) = current_path.last() {
+                        // `serie`, `nNF`, `cUF` etc. também aparecem nos documentos referenciados
+                        let em_ide = current_path.iter().rev().nth(1).map(String::as_str) == Some("ide");
                         match tag.as_str() {
-                            "nNF" => numero = text,
-                            "serie" => serie = text,
-                            "dhEmi" => data_emissao = text,
-                            "CNPJ" if current_path.contains(&"emit".to_string()) => {
-                                emit_cnpj = text
+                            "nNF" if em_ide => numero = text,
+                            "serie" if em_ide => serie = text,
+                            "dhEmi" if em_ide => data_emissao = text,
+                            "indPres" if em_ide => presencial = text == "1",
+                            "tpNF" => {
+                                if let Some(tipo) = TipoNota::from_codigo(&text) {
+                                    tipo_nota = tipo;
+                                }
+                            }
+                            "cUF" | "tpEmis" | "cNF" if em_ide => {
+                                parse_identificacao_field(tag, &text, &mut identificacao)
+                            }
+                            "mod" if em_ide => {
+                                modelo = ModeloNotaFiscal::from_codigo(&text)
                             }
-                            "xNome" if current_path.contains(&"emit".to_string()) => {
-                                emit_razao = text
+                            _ if current_path.contains(&"infNFeSupl".to_string()) => {
+                                let supl = informacoes_suplementares.get_or_insert_with(Default::default);
+                                Self::parse_suplementar_field(tag, text, supl)
                             }
-                            "xFant" if current_path.contains(&"emit".to_string()) => {
-                                emit_fantasia = Some(text)
+                            _ if current_path.contains(&"infProt".to_string()) => {
+                                let protocolo = protocolo.get_or_insert_with(Default::default);
+                                parse_protocolo_field(tag, &text, protocolo)
                             }
-                            "CNPJ" if current_path.contains(&"dest".to_string()) => {
-                                dest_cnpj = text
+                            _ if current_path.contains(&"emit".to_string()) => {
+                                parse_participante_field(tag, &text, &mut emitente)
                             }
-                            "xNome" if current_path.contains(&"dest".to_string()) => {
-                                dest_razao = text
+                            _ if current_path.contains(&"dest".to_string()) => {
+                                parse_participante_field(tag, &text, &mut destinatario)
                             }
-                            "vNF" => valor_total = text.parse().unwrap_or(0.0),
-                            "vProd" if current_path.contains(&"ICMSTot".to_string()) => {
-                                valor_produtos = text.parse().unwrap_or(0.0)
+                            _ if current_path.contains(&"ICMSTot".to_string()) => {
+                                Self::parse_totais_field(tag, &text, &mut totais)
+                            }
+                            _ if current_path.contains(&"ISSQNtot".to_string()) => {
+                                if tag == "vServ" {
+                                    totais.valor_servicos = text.parse().unwrap_or(0.0);
+                                }
+                            }
+                            _ if current_path.contains(&"transp".to_string()) => {
+                                Self::parse_transporte_field(&current_path, &text, &mut transporte)
+                            }
+                            _ if current_path.contains(&"pag".to_string()) => {
+                                Self::parse_pagamento_field(tag, &text, &mut pagamento)
+                            }
+                            _ if current_path.contains(&"cobr".to_string()) => {
+                                Self::parse_cobranca_field(&current_path, &text, &mut cobranca)
                             }
                             _ => {}
                         }
                     }
                 }
+                // O qrCode da NFC-e costuma vir em CDATA
+                Ok(Event::CData(e)) => {
+                    if current_path.contains(&"infNFeSupl".to_string()) {
+                        let text = String::from_utf8_lossy(&e.into_inner()).trim().to_string();
+                        if let Some(tag) = current_path.last() {
+                            let supl = informacoes_suplementares.get_or_insert_with(Default::default);
+                            Self::parse_suplementar_field(tag, text, supl);
+                        }
+                    }
+                }
                 Ok(Event::Eof) => break,
                 Err(e) => {
                     return Err(GeolocationError::XmlParseError(format!(
@@ -94,94 +189,373 @@ impl NFeParser {
             buf.clear();
         }
         
-        // Extrai chave de acesso
-        chave_acesso = extract_access_key(xml)
-            .ok_or_else(|| GeolocationError::InvalidAccessKey("Chave não encontrada".into()))?;
-        
-        // Cria estruturas básicas
-        let emitente = Participante {
-            cnpj_cpf: emit_cnpj,
-            razao_social: emit_razao,
-            nome_fantasia: emit_fantasia,
-            endereco: Endereco {
-                logradouro: String::new(),
-                numero: String::new(),
-                complemento: None,
-                bairro: String::new(),
-                codigo_municipio: String::new(),
-                municipio: String::new(),
-                uf: String::new(),
-                cep: String::new(),
-                codigo_pais: "1058".to_string(),
-                pais: "Brasil".to_string(),
-            },
-            inscricao_estadual: None,
-            telefone: None,
-            email: None,
-        };
+        // Extrai a chave de acesso
+        let chave_acesso = chave_documento(xml)?;
         
-        let destinatario = Participante {
-            cnpj_cpf: dest_cnpj,
-            razao_social: dest_razao,
-            nome_fantasia: None,
-            endereco: Endereco {
-                logradouro: String::new(),
-                numero: String::new(),
-                complemento: None,
-                bairro: String::new(),
-                codigo_municipio: String::new(),
-                municipio: String::new(),
-                uf: String::new(),
-                cep: String::new(),
-                codigo_pais: "1058".to_string(),
-                pais: "Brasil".to_string(),
-            },
-            inscricao_estadual: None,
-            telefone: None,
-            email: None,
-        };
+        let modelo = modelo
+            .or_else(|| ModeloNotaFiscal::from_codigo(&chave_acesso[20..22]))
+            .unwrap_or_default();
         
-        let totais = Totais {
-            base_calculo_icms: 0.0,
-            valor_icms: 0.0,
-            valor_icms_desonerado: 0.0,
-            valor_fcp: 0.0,
-            base_calculo_icms_st: 0.0,
-            valor_icms_st: 0.0,
-            valor_produtos,
-            valor_frete: 0.0,
-            valor_seguro: 0.0,
-            valor_desconto: 0.0,
-            valor_ii: 0.0,
-            valor_ipi: 0.0,
-            valor_pis: 0.0,
-            valor_cofins: 0.0,
-            outras_despesas: 0.0,
-            valor_total,
-        };
+        finalize_participante(&mut emitente);
+        finalize_participante(&mut destinatario);
+        if let Some(transportadora) = transporte.transportadora.as_mut() {
+            finalize_participante(transportadora);
+        }
         
         // Parse data de emissão
         let data_emissao_parsed = DateTime::parse_from_rfc3339(&data_emissao)
             .map(|dt| dt.with_timezone(&Utc))
             .unwrap_or_else(|_| Utc::now());
         
+        let status = status_protocolo(protocolo.as_ref(), &chave_acesso);
+        
         Ok(NotaFiscal {
             id: Uuid::new_v4(),
             chave_acesso,
             numero,
             serie,
+            modelo,
+            identificacao,
+            presencial,
             data_emissao: data_emissao_parsed,
             tipo_nota,
             emitente,
             destinatario,
-            itens: Vec::new(),
+            itens,
             totais,
+            transporte,
+            pagamento,
+            cobranca,
             informacoes_adicionais: None,
-            protocolo_autorizacao: None,
-            status: ProcessingStatus::Completed,
+            informacoes_suplementares,
+            protocolo_autorizacao: protocolo.as_ref().and_then(|p| p.numero.clone()),
+            protocolo,
+            status,
+            historico_status: Vec::new(),
             created_at: Utc::now(),
         })
     }
+    
+    /// Preenche um campo do grupo `transp` (transportadora, veículos e volumes)
+    fn parse_transporte_field(path: &[String], text: &str, transporte: &mut TransporteNota) {
+        let Some(tag) = path.last().map(String::as_str) else {
+            return;
+        };
+        let grupo = path.iter().rposition(|p| p == "transp").and_then(|i| path.get(i + 1));
+        match grupo.map(String::as_str) {
+            Some("modFrete") => {
+                transporte.modalidade_frete = match text {
+                    "0" => ModalidadeFrete::Remetente,
+                    "1" => ModalidadeFrete::Destinatario,
+                    "2" => ModalidadeFrete::Terceiros,
+                    "3" => ModalidadeFrete::ProprioRemetente,
+                    "4" => ModalidadeFrete::ProprioDestinatario,
+                    _ => ModalidadeFrete::SemFrete,
+                }
+            }
+            Some("transporta") => {
+                if let Some(transportadora) = transporte.transportadora.as_mut() {
+                    match tag {
+                        // Na NF-e o endereço da transportadora vem em linha única
+                        "xEnder" => transportadora.endereco.logradouro = text.to_string(),
+                        _ => parse_participante_field(tag, text, transportadora),
+                    }
+                }
+            }
+            Some("veicTransp") => {
+                if let Some(veiculo) = transporte.veiculo.as_mut() {
+                    Self::parse_veiculo_field(tag, text, veiculo);
+                }
+            }
+            Some("reboque") => {
+                if let Some(reboque) = transporte.reboques.last_mut() {
+                    Self::parse_veiculo_field(tag, text, reboque);
+                }
+            }
+            Some("vol") => {
+                if let Some(volume) = transporte.volumes.last_mut() {
+                    let valor = || text.parse::<f64>().ok();
+                    match tag {
+                        "qVol" => volume.quantidade = valor(),
+                        "esp" => volume.especie = Some(text.to_string()),
+                        "marca" => volume.marca = Some(text.to_string()),
+                        "nVol" => volume.numeracao = Some(text.to_string()),
+                        "pesoL" => volume.peso_liquido = valor(),
+                        "pesoB" => volume.peso_bruto = valor(),
+                        "nLacre" => volume.lacres.push(text.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    /// Preenche um campo do `infNFeSupl` da NFC-e
+    fn parse_suplementar_field(tag: &str, text: String, supl: &mut InformacoesSuplementares) {
+        match tag {
+            "qrCode" => supl.qr_code = text,
+            "urlChave" => supl.url_chave = text,
+            _ => {}
+        }
+    }
+    
+    /// Preenche um campo do grupo `pag` (formas de pagamento e cartão)
+    fn parse_pagamento_field(tag: &str, text: &str, pagamento: &mut PagamentoNota) {
+        if tag == "vTroco" {
+            pagamento.valor_troco = text.parse().unwrap_or(0.0);
+            return;
+        }
+        let Some(forma) = pagamento.formas.last_mut() else {
+            return;
+        };
+        match tag {
+            "indPag" => forma.a_prazo = text == "1",
+            "tPag" => forma.meio = MeioPagamento::from_codigo(text),
+            "xPag" => forma.descricao = Some(text.to_string()),
+            "vPag" => forma.valor = text.parse().unwrap_or(0.0),
+            _ => {
+                if let Some(cartao) = forma.cartao.as_mut() {
+                    match tag {
+                        "tpIntegra" => cartao.tipo_integracao = Some(text.to_string()),
+                        "CNPJ" => cartao.cnpj_credenciadora = Some(text.to_string()),
+                        "tBand" => cartao.bandeira = Some(text.to_string()),
+                        "cAut" => cartao.autorizacao = Some(text.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Preenche um campo do grupo `cobr` (fatura e duplicatas)
+    fn parse_cobranca_field(path: &[String], text: &str, cobranca: &mut CobrancaNota) {
+        let Some(tag) = path.last().map(String::as_str) else {
+            return;
+        };
+        let valor = || text.parse::<f64>().unwrap_or(0.0);
+        if path.iter().any(|p| p == "fat") {
+            if let Some(fatura) = cobranca.fatura.as_mut() {
+                match tag {
+                    "nFat" => fatura.numero = Some(text.to_string()),
+                    "vOrig" => fatura.valor_original = valor(),
+                    "vDesc" => fatura.valor_desconto = valor(),
+                    "vLiq" => fatura.valor_liquido = valor(),
+                    _ => {}
+                }
+            }
+        } else if let Some(duplicata) = cobranca.duplicatas.last_mut() {
+            match tag {
+                "nDup" => duplicata.numero = text.to_string(),
+                "dVenc" => duplicata.vencimento = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok(),
+                "vDup" => duplicata.valor = valor(),
+                _ => {}
+            }
+        }
+    }
+    
+    fn parse_veiculo_field(tag: &str, text: &str, veiculo: &mut VeiculoTransporte) {
+        match tag {
+            "placa" => veiculo.placa = text.to_string(),
+            "UF" => veiculo.uf = Some(text.to_string()),
+            "RNTC" => veiculo.rntc = Some(text.to_string()),
+            _ => {}
+        }
+    }
+    
+    /// Preenche um campo do grupo `total/ICMSTot`
+    fn parse_totais_field(tag: &str, text: &str, totais: &mut Totais) {
+        let valor = text.parse().unwrap_or(0.0);
+        match tag {
+            "vBC" => totais.base_calculo_icms = valor,
+            "vICMS" => totais.valor_icms = valor,
+            "vICMSDeson" => totais.valor_icms_desonerado = valor,
+            "vFCP" => totais.valor_fcp = valor,
+            "vBCST" => totais.base_calculo_icms_st = valor,
+            "vST" => totais.valor_icms_st = valor,
+            "vFCPST" => totais.valor_fcp_st = valor,
+            "vProd" => totais.valor_produtos = valor,
+            "vFrete" => totais.valor_frete = valor,
+            "vSeg" => totais.valor_seguro = valor,
+            "vDesc" => totais.valor_desconto = valor,
+            "vII" => totais.valor_ii = valor,
+            "vIPI" => totais.valor_ipi = valor,
+            "vIPIDevol" => totais.valor_ipi_devolvido = valor,
+            "vPIS" => totais.valor_pis = valor,
+            "vCOFINS" => totais.valor_cofins = valor,
+            "vOutro" => totais.outras_despesas = valor,
+            "vNF" => totais.valor_total = valor,
+            "vTotTrib" => totais.valor_total_tributos = valor,
+            _ => {}
+        }
+    }
+    
+    /// Preenche um campo do item (`det`) a partir do texto do elemento corrente
+    fn parse_item_field(path: &[String], text: &str, item: &mut ItemNota) {
+        let Some(tag) = path.last() else {
+            return;
+        };
+        
+        if tag == "infAdProd" {
+            item.informacoes_adicionais = Some(text.to_string());
+            return;
+        }
+        if tag == "vIPIDevol" {
+            item.valor_ipi_devolvido = text.parse().unwrap_or(0.0);
+            return;
+        }
+        
+        if let Some(pos) = path.iter().position(|p| p == "imposto") {
+            Self::parse_imposto_field(&path[pos + 1..], text, &mut item.impostos);
+            return;
+        }
+        
+        if path.len() < 2 || path[path.len() - 2] != "prod" {
+            return;
+        }
+        
+        match tag.as_str() {
+            "cProd" => item.codigo_produto = text.to_string(),
+            "cEAN" => {
+                // "SEM GTIN" indica produto sem código de barras
+                if !text.is_empty() && text != "SEM GTIN" {
+                    item.ean = Some(text.to_string());
+                }
+            }
+            "xProd" => item.descricao = text.to_string(),
+            "NCM" => item.ncm = text.to_string(),
+            "CFOP" => item.cfop = text.to_string(),
+            "uCom" => item.unidade_comercial = text.to_string(),
+            "qCom" => item.quantidade_comercial = text.parse().unwrap_or(0.0),
+            "vUnCom" => item.valor_unitario = text.parse().unwrap_or(0.0),
+            "vProd" => item.valor_total = text.parse().unwrap_or(0.0),
+            "vFrete" => item.valor_frete = text.parse().unwrap_or(0.0),
+            "vSeg" => item.valor_seguro = text.parse().unwrap_or(0.0),
+            "vDesc" => item.valor_desconto = text.parse().unwrap_or(0.0),
+            "vOutro" => item.valor_outros = text.parse().unwrap_or(0.0),
+            "indTot" => item.fora_do_total = text == "0",
+            _ => {}
+        }
+    }
+    
+    /// Preenche os tributos do item; `path` começa no filho de `imposto`
+    fn parse_imposto_field(path: &[String], text: &str, impostos: &mut ImpostosItem) {
+        let (Some(grupo), Some(tag)) = (path.first(), path.last()) else {
+            return;
+        };
+        let valor = || text.parse().unwrap_or(0.0);
+        
+        match grupo.as_str() {
+            "vTotTrib" => impostos.valor_total_tributos = valor(),
+            "ICMS" => {
+                // ICMS > ICMSxx > campo
+                let Some(variante) = path.get(1).filter(|_| path.len() == 3) else {
+                    return;
+                };
+                let icms = impostos.icms.get_or_insert_with(|| IcmsItem {
+                    grupo: variante.clone(),
+                    ..Default::default()
+                });
+                Self::parse_icms_field(tag, text, icms);
+            }
+            "IPI" => {
+                let ipi = impostos.ipi.get_or_insert_with(IpiItem::default);
+                match tag.as_str() {
+                    "cEnq" => ipi.codigo_enquadramento = text.to_string(),
+                    "CST" => ipi.cst = text.to_string(),
+                    "vBC" => ipi.base_calculo = valor(),
+                    "pIPI" => ipi.aliquota = valor(),
+                    "qUnid" => ipi.quantidade_unidade = valor(),
+                    "vUnid" => ipi.valor_unidade = valor(),
+                    "vIPI" => ipi.valor = valor(),
+                    _ => {}
+                }
+            }
+            "II" => {
+                let ii = impostos.ii.get_or_insert_with(IiItem::default);
+                match tag.as_str() {
+                    "vBC" => ii.base_calculo = valor(),
+                    "vDespAdu" => ii.despesas_aduaneiras = valor(),
+                    "vII" => ii.valor = valor(),
+                    "vIOF" => ii.valor_iof = valor(),
+                    _ => {}
+                }
+            }
+            "PIS" | "COFINS" => {
+                let tributo = if grupo == "PIS" {
+                    impostos.pis.get_or_insert_with(PisCofinsItem::default)
+                } else {
+                    impostos.cofins.get_or_insert_with(PisCofinsItem::default)
+                };
+                match tag.as_str() {
+                    "CST" => tributo.cst = text.to_string(),
+                    "vBC" => tributo.base_calculo = valor(),
+                    "pPIS" | "pCOFINS" => tributo.aliquota = valor(),
+                    "qBCProd" => tributo.quantidade_bc = valor(),
+                    "vAliqProd" => tributo.aliquota_valor = valor(),
+                    "vPIS" | "vCOFINS" => tributo.valor = valor(),
+                    _ => {}
+                }
+            }
+            "ISSQN" => {
+                let issqn = impostos.issqn.get_or_insert_with(IssqnItem::default);
+                match tag.as_str() {
+                    "vBC" => issqn.base_calculo = valor(),
+                    "vAliq" => issqn.aliquota = valor(),
+                    "vISSQN" => issqn.valor = valor(),
+                    "cMunFG" => issqn.codigo_municipio = text.to_string(),
+                    "cListServ" => issqn.item_lista_servico = text.to_string(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    /// Preenche um campo de qualquer grupo de ICMS (CST ou CSOSN)
+    fn parse_icms_field(tag: &str, text: &str, icms: &mut IcmsItem) {
+        let valor = || text.parse().unwrap_or(0.0);
+        
+        match tag {
+            "orig" => icms.origem = text.to_string(),
+            "CST" => icms.cst = Some(text.to_string()),
+            "CSOSN" => icms.csosn = Some(text.to_string()),
+            "modBC" => icms.modalidade_bc = Some(text.to_string()),
+            "pRedBC" => icms.percentual_reducao_bc = valor(),
+            "vBC" => icms.base_calculo = valor(),
+            "pICMS" => icms.aliquota = valor(),
+            "vICMSOp" => icms.valor_icms_operacao = valor(),
+            "pDif" => icms.percentual_diferimento = valor(),
+            "vICMSDif" => icms.valor_icms_diferido = valor(),
+            "vICMS" => icms.valor = valor(),
+            "vBCFCP" => icms.base_calculo_fcp = valor(),
+            "pFCP" => icms.aliquota_fcp = valor(),
+            "vFCP" => icms.valor_fcp = valor(),
+            "modBCST" => icms.modalidade_bc_st = Some(text.to_string()),
+            "pMVAST" => icms.percentual_mva_st = valor(),
+            "pRedBCST" => icms.percentual_reducao_bc_st = valor(),
+            "vBCST" => icms.base_calculo_st = valor(),
+            "pICMSST" => icms.aliquota_st = valor(),
+            "vICMSST" => icms.valor_st = valor(),
+            "vBCFCPST" => icms.base_calculo_fcp_st = valor(),
+            "pFCPST" => icms.aliquota_fcp_st = valor(),
+            "vFCPST" => icms.valor_fcp_st = valor(),
+            "vBCSTRet" => icms.base_calculo_st_retido = valor(),
+            "pST" => icms.aliquota_st_retido = valor(),
+            "vICMSSTRet" => icms.valor_st_retido = valor(),
+            "vFCPSTRet" => icms.valor_fcp_st_retido = valor(),
+            "qBCMono" => icms.quantidade_bc_mono = valor(),
+            "adRemICMS" => icms.aliquota_ad_rem = valor(),
+            "vICMSMono" => icms.valor_icms_mono = valor(),
+            "vICMSDeson" => icms.valor_desonerado = valor(),
+            "motDesICMS" => icms.motivo_desoneracao = Some(text.to_string()),
+            "pBCOp" => icms.percentual_bc_operacao_propria = valor(),
+            "UFST" => icms.uf_st = Some(text.to_string()),
+            "pCredSN" => icms.aliquota_credito_sn = valor(),
+            "vCredICMSSN" => icms.valor_credito_sn = valor(),
+            _ => {}
+        }
+    }
 }
 
 impl FiscalDocumentParser for NFeParser {
@@ -252,4 +626,473 @@ mod tests {
         assert_eq!(nfe.serie, "1");
         assert_eq!(nfe.totais.valor_total, 1000.0);
     }
+
+    #[test]
+    fn test_nfe_parser_nota_referenciada() {
+        // Devolução de nota modelo 1: a série e o número do refNF não são os da NF-e
+        let xml = include_str!("../../test_data/sample_nfe.xml").replace(
+            "<verProc>1.0.0</verProc>",
+            "<verProc>1.0.0</verProc><NFref><refNF><cUF>33</cUF><AAMM>2012</AAMM>\
+             <CNPJ>11222333000181</CNPJ><mod>01</mod><serie>7</serie><nNF>999</nNF>\
+             </refNF></NFref>",
+        );
+        let nfe = NFeParser::new().parse_string(&xml).unwrap();
+        assert_eq!(nfe.numero, "12345");
+        assert_eq!(nfe.serie, "1");
+        assert_eq!(nfe.modelo, ModeloNotaFiscal::NFe);
+
+        let result = crate::validators::FiscalValidator::validate_nota_fiscal(&nfe);
+        assert!(!result.errors.iter().any(|e| e.code.starts_with("KEY_MISMATCH")));
+    }
+
+    #[test]
+    fn test_nfe_parser_itens() {
+        let parser = NFeParser::new();
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <nfeProc>
+            <NFe>
+                <infNFe Id="NFe35210112345678901234567890123456789012345678">
+                    <det nItem="1">
+                        <prod>
+                            <cProd>PROD001</cProd>
+                            <cEAN>7891234567890</cEAN>
+                            <xProd>Produto Teste</xProd>
+                            <NCM>84331900</NCM>
+                            <CFOP>5102</CFOP>
+                            <uCom>UN</uCom>
+                            <qCom>10.0000</qCom>
+                            <vUnCom>100.0000</vUnCom>
+                            <vProd>1000.00</vProd>
+                        </prod>
+                        <infAdProd>Lote 42</infAdProd>
+                    </det>
+                    <det nItem="2">
+                        <prod>
+                            <cProd>PROD002</cProd>
+                            <cEAN>SEM GTIN</cEAN>
+                            <xProd>Servico Montagem</xProd>
+                            <NCM>00000000</NCM>
+                            <CFOP>5933</CFOP>
+                            <uCom>H</uCom>
+                            <qCom>2.0000</qCom>
+                            <vUnCom>50.0000</vUnCom>
+                            <vProd>100.00</vProd>
+                        </prod>
+                    </det>
+                    <total>
+                        <ICMSTot>
+                            <vProd>1100.00</vProd>
+                            <vNF>1100.00</vNF>
+                        </ICMSTot>
+                    </total>
+                </infNFe>
+            </NFe>
+        </nfeProc>"#;
+        
+        let nfe = parser.parse_string(xml).unwrap();
+        assert_eq!(nfe.itens.len(), 2);
+        assert_eq!(nfe.totais.valor_produtos, 1100.0);
+        
+        let item = &nfe.itens[0];
+        assert_eq!(item.numero_item, 1);
+        assert_eq!(item.codigo_produto, "PROD001");
+        assert_eq!(item.ncm, "84331900");
+        assert_eq!(item.cfop, "5102");
+        assert_eq!(item.quantidade_comercial, 10.0);
+        assert_eq!(item.valor_total, 1000.0);
+        assert_eq!(item.ean.as_deref(), Some("7891234567890"));
+        assert_eq!(item.informacoes_adicionais.as_deref(), Some("Lote 42"));
+        
+        assert_eq!(nfe.itens[1].numero_item, 2);
+        assert_eq!(nfe.itens[1].ean, None);
+    }
+
+    #[test]
+    fn test_nfe_parser_impostos_item() {
+        let parser = NFeParser::new();
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <nfeProc>
+            <NFe>
+                <infNFe Id="NFe35210112345678901234567890123456789012345678">
+                    <det nItem="1">
+                        <prod>
+                            <cProd>PROD001</cProd>
+                            <vProd>1000.00</vProd>
+                        </prod>
+                        <imposto>
+                            <vTotTrib>321.50</vTotTrib>
+                            <ICMS>
+                                <ICMS10>
+                                    <orig>0</orig>
+                                    <CST>10</CST>
+                                    <modBC>3</modBC>
+                                    <vBC>1000.00</vBC>
+                                    <pICMS>18.00</pICMS>
+                                    <vICMS>180.00</vICMS>
+                                    <modBCST>4</modBCST>
+                                    <pMVAST>40.00</pMVAST>
+                                    <vBCST>1400.00</vBCST>
+                                    <pICMSST>18.00</pICMSST>
+                                    <vICMSST>72.00</vICMSST>
+                                </ICMS10>
+                            </ICMS>
+                            <IPI>
+                                <cEnq>999</cEnq>
+                                <IPITrib>
+                                    <CST>50</CST>
+                                    <vBC>1000.00</vBC>
+                                    <pIPI>5.00</pIPI>
+                                    <vIPI>50.00</vIPI>
+                                </IPITrib>
+                            </IPI>
+                            <PIS>
+                                <PISAliq>
+                                    <CST>01</CST>
+                                    <vBC>1000.00</vBC>
+                                    <pPIS>1.65</pPIS>
+                                    <vPIS>16.50</vPIS>
+                                </PISAliq>
+                            </PIS>
+                            <COFINS>
+                                <COFINSAliq>
+                                    <CST>01</CST>
+                                    <vBC>1000.00</vBC>
+                                    <pCOFINS>7.60</pCOFINS>
+                                    <vCOFINS>76.00</vCOFINS>
+                                </COFINSAliq>
+                            </COFINS>
+                        </imposto>
+                    </det>
+                    <det nItem="2">
+                        <prod>
+                            <cProd>PROD002</cProd>
+                            <vProd>200.00</vProd>
+                        </prod>
+                        <imposto>
+                            <ICMS>
+                                <ICMSSN101>
+                                    <orig>0</orig>
+                                    <CSOSN>101</CSOSN>
+                                    <pCredSN>2.50</pCredSN>
+                                    <vCredICMSSN>5.00</vCredICMSSN>
+                                </ICMSSN101>
+                            </ICMS>
+                        </imposto>
+                    </det>
+                    <det nItem="3">
+                        <prod>
+                            <cProd>SERV001</cProd>
+                            <vProd>300.00</vProd>
+                        </prod>
+                        <imposto>
+                            <ISSQN>
+                                <vBC>300.00</vBC>
+                                <vAliq>5.00</vAliq>
+                                <vISSQN>15.00</vISSQN>
+                                <cMunFG>3550308</cMunFG>
+                                <cListServ>14.01</cListServ>
+                                <indISS>1</indISS>
+                                <indIncentivo>2</indIncentivo>
+                            </ISSQN>
+                        </imposto>
+                    </det>
+                </infNFe>
+            </NFe>
+        </nfeProc>"#;
+        
+        let nfe = parser.parse_string(xml).unwrap();
+        assert_eq!(nfe.itens.len(), 3);
+        assert_eq!(nfe.itens[0].valor_total, 1000.0);
+        
+        let impostos = &nfe.itens[0].impostos;
+        assert_eq!(impostos.valor_total_tributos, 321.5);
+        let icms = impostos.icms.as_ref().unwrap();
+        assert_eq!(icms.grupo, "ICMS10");
+        assert_eq!(icms.situacao_tributaria(), "10");
+        assert_eq!(icms.valor, 180.0);
+        assert_eq!(icms.percentual_mva_st, 40.0);
+        assert_eq!(icms.valor_st, 72.0);
+        let ipi = impostos.ipi.as_ref().unwrap();
+        assert_eq!(ipi.codigo_enquadramento, "999");
+        assert_eq!(ipi.valor, 50.0);
+        assert_eq!(impostos.pis.as_ref().unwrap().valor, 16.5);
+        assert_eq!(impostos.cofins.as_ref().unwrap().aliquota, 7.6);
+        
+        let icms_sn = nfe.itens[1].impostos.icms.as_ref().unwrap();
+        assert_eq!(icms_sn.csosn.as_deref(), Some("101"));
+        assert_eq!(icms_sn.valor_credito_sn, 5.0);
+        assert!(nfe.itens[1].impostos.ipi.is_none());
+        assert!(nfe.itens[1].impostos.issqn.is_none());
+
+        let issqn = nfe.itens[2].impostos.issqn.as_ref().unwrap();
+        assert_eq!(issqn.valor, 15.0);
+        assert_eq!(issqn.codigo_municipio, "3550308");
+        assert_eq!(issqn.item_lista_servico, "14.01");
+    }
+
+    #[test]
+    fn test_nfe_parser_participantes() {
+        let parser = NFeParser::new();
+        let nfe = parser
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap();
+        
+        let emit = &nfe.emitente;
+        assert_eq!(emit.cnpj_cpf, "12345678000190");
+        assert_eq!(emit.nome_fantasia.as_deref(), Some("Empresa Teste"));
+        assert_eq!(emit.inscricao_estadual.as_deref(), Some("123456785909"));
+        assert_eq!(emit.endereco.logradouro, "Rua Teste");
+        assert_eq!(emit.endereco.numero, "123");
+        assert_eq!(emit.endereco.codigo_municipio, "3550308");
+        assert_eq!(emit.endereco.municipio, "São Paulo");
+        assert_eq!(emit.endereco.uf, "SP");
+        assert_eq!(emit.endereco.cep, "01000000");
+        assert_eq!(nfe.destinatario.endereco.logradouro, "Av Cliente");
+        
+        // Destinatário pessoa física, sem cPais/xPais
+        let xml = r#"<NFe><infNFe Id="NFe35210112345678901234567890123456789012345678">
+            <dest>
+                <CPF>11144477735</CPF>
+                <xNome>Consumidor</xNome>
+                <enderDest>
+                    <xLgr>Rua A</xLgr><nro>S/N</nro><xCpl>Casa 2</xCpl><xBairro>Vila</xBairro>
+                    <cMun>3304557</cMun><xMun>Rio de Janeiro</xMun><UF>RJ</UF>
+                    <fone>21999990000</fone>
+                </enderDest>
+                <email>consumidor@example.com</email>
+            </dest>
+        </infNFe></NFe>"#;
+        let dest = parser.parse_string(xml).unwrap().destinatario;
+        assert_eq!(dest.cnpj_cpf, "11144477735");
+        assert_eq!(dest.endereco.complemento.as_deref(), Some("Casa 2"));
+        assert_eq!(dest.endereco.codigo_pais, "1058");
+        assert_eq!(dest.endereco.pais, "Brasil");
+        assert_eq!(dest.telefone.as_deref(), Some("21999990000"));
+        assert_eq!(dest.email.as_deref(), Some("consumidor@example.com"));
+        
+        // Destinatário estrangeiro
+        let xml = r#"<NFe><infNFe Id="NFe35210112345678901234567890123456789012345678">
+            <dest>
+                <idEstrangeiro>AR12345</idEstrangeiro>
+                <xNome>Importador SA</xNome>
+                <enderDest>
+                    <xLgr>Calle 1</xLgr><nro>10</nro><xBairro>Centro</xBairro>
+                    <cMun>9999999</cMun><xMun>EXTERIOR</xMun><UF>EX</UF>
+                    <cPais>0639</cPais><xPais>Argentina</xPais>
+                </enderDest>
+            </dest>
+        </infNFe></NFe>"#;
+        let dest = parser.parse_string(xml).unwrap().destinatario;
+        assert!(dest.cnpj_cpf.is_empty());
+        assert_eq!(dest.id_estrangeiro.as_deref(), Some("AR12345"));
+        assert_eq!(dest.endereco.codigo_pais, "0639");
+        assert_eq!(dest.endereco.pais, "Argentina");
+    }
+
+    #[test]
+    fn test_nfe_parser_totais() {
+        let parser = NFeParser::new();
+        let xml = r#"<NFe><infNFe Id="NFe35210112345678901234567890123456789012345678">
+            <total>
+                <ICMSTot>
+                    <vBC>1000.00</vBC><vICMS>180.00</vICMS><vICMSDeson>5.00</vICMSDeson>
+                    <vFCP>20.00</vFCP><vBCST>1400.00</vBCST><vST>72.00</vST>
+                    <vProd>1000.00</vProd><vFrete>30.00</vFrete><vSeg>10.00</vSeg>
+                    <vDesc>15.00</vDesc><vII>0.00</vII><vIPI>50.00</vIPI>
+                    <vPIS>16.50</vPIS><vCOFINS>76.00</vCOFINS><vOutro>2.00</vOutro>
+                    <vNF>1144.00</vNF><vTotTrib>321.50</vTotTrib>
+                </ICMSTot>
+                <ISSQNtot>
+                    <vServ>200.00</vServ><vBC>200.00</vBC><vISS>10.00</vISS>
+                    <vPIS>3.30</vPIS><vCOFINS>15.20</vCOFINS>
+                </ISSQNtot>
+            </total>
+        </infNFe></NFe>"#;
+        
+        let totais = parser.parse_string(xml).unwrap().totais;
+        assert_eq!(totais.base_calculo_icms, 1000.0);
+        assert_eq!(totais.valor_icms, 180.0);
+        assert_eq!(totais.valor_icms_desonerado, 5.0);
+        assert_eq!(totais.valor_fcp, 20.0);
+        assert_eq!(totais.base_calculo_icms_st, 1400.0);
+        assert_eq!(totais.valor_icms_st, 72.0);
+        assert_eq!(totais.valor_produtos, 1000.0);
+        assert_eq!(totais.valor_frete, 30.0);
+        assert_eq!(totais.valor_seguro, 10.0);
+        assert_eq!(totais.valor_desconto, 15.0);
+        assert_eq!(totais.valor_ipi, 50.0);
+        assert_eq!(totais.valor_pis, 16.5);
+        assert_eq!(totais.valor_cofins, 76.0);
+        assert_eq!(totais.outras_despesas, 2.0);
+        assert_eq!(totais.valor_total, 1144.0);
+        assert_eq!(totais.valor_total_tributos, 321.5);
+        // O ISSQNtot não sobrescreve os campos homônimos do ICMSTot
+        assert_eq!(totais.valor_servicos, 200.0);
+        assert_eq!(totais.base_calculo_icms, 1000.0);
+    }
+    
+    #[test]
+    fn test_nfe_parser_transporte() {
+        let parser = NFeParser::new();
+        let xml = r#"<NFe><infNFe Id="NFe35210112345678901234567890123456789012345678">
+            <emit><CNPJ>12345678000190</CNPJ><enderEmit><UF>SP</UF></enderEmit></emit>
+            <transp>
+                <modFrete>1</modFrete>
+                <transporta>
+                    <CNPJ>11222333000181</CNPJ>
+                    <xNome>Transportadora Teste Ltda</xNome>
+                    <IE>123456789</IE>
+                    <xEnder>Rua do Frete, 100</xEnder>
+                    <xMun>Campinas</xMun>
+                    <UF>SP</UF>
+                </transporta>
+                <veicTransp><placa>ABC1D23</placa><UF>SP</UF><RNTC>12345678</RNTC></veicTransp>
+                <reboque><placa>XYZ9A87</placa><UF>PR</UF></reboque>
+                <vol>
+                    <qVol>10</qVol><esp>CAIXA</esp><marca>ACME</marca>
+                    <pesoL>95.500</pesoL><pesoB>100.000</pesoB>
+                    <lacres><nLacre>L001</nLacre></lacres>
+                    <lacres><nLacre>L002</nLacre></lacres>
+                </vol>
+                <vol><qVol>2</qVol><esp>PALETE</esp><pesoL>400.000</pesoL><pesoB>420.000</pesoB></vol>
+            </transp>
+        </infNFe></NFe>"#;
+        
+        let nf = parser.parse_string(xml).unwrap();
+        assert_eq!(nf.emitente.endereco.uf, "SP");
+        let transporte = nf.transporte;
+        assert_eq!(transporte.modalidade_frete, ModalidadeFrete::Destinatario);
+        let transportadora = transporte.transportadora.as_ref().unwrap();
+        assert_eq!(transportadora.cnpj_cpf, "11222333000181");
+        assert_eq!(transportadora.razao_social, "Transportadora Teste Ltda");
+        assert_eq!(transportadora.endereco.logradouro, "Rua do Frete, 100");
+        assert_eq!(transportadora.endereco.municipio, "Campinas");
+        let veiculo = transporte.veiculo.as_ref().unwrap();
+        assert_eq!(veiculo.placa, "ABC1D23");
+        assert_eq!(veiculo.rntc.as_deref(), Some("12345678"));
+        assert_eq!(transporte.reboques.len(), 1);
+        assert_eq!(transporte.reboques[0].uf.as_deref(), Some("PR"));
+        assert_eq!(transporte.volumes.len(), 2);
+        assert_eq!(transporte.volumes[0].quantidade, Some(10.0));
+        assert_eq!(transporte.volumes[0].lacres, vec!["L001", "L002"]);
+        assert_eq!(transporte.peso_bruto_total(), 520.0);
+        assert_eq!(transporte.peso_liquido_total(), 495.5);
+    }
+    
+    #[test]
+    fn test_nfe_parser_protocolo() {
+        let parser = NFeParser::new();
+        let nf = parser.parse_string(include_str!("../../test_data/sample_nfe.xml")).unwrap();
+        assert_eq!(nf.status, ProcessingStatus::Completed);
+        assert_eq!(nf.protocolo_autorizacao.as_deref(), Some("135210012345678"));
+        let protocolo = nf.protocolo.as_ref().unwrap();
+        assert!(protocolo.autorizado());
+        assert_eq!(protocolo.codigo_status, "100");
+        assert_eq!(protocolo.motivo, "Autorizado o uso da NF-e");
+        assert_eq!(protocolo.chave_acesso, nf.chave_acesso);
+        assert_eq!(protocolo.digest_value.as_deref(), Some("abcdef1234567890"));
+        assert_eq!(
+            protocolo.data_recebimento.unwrap().to_rfc3339(),
+            "2021-01-15T13:30:05+00:00"
+        );
+        
+        let denegada = r#"<nfeProc>
+            <NFe><infNFe Id="NFe35210112345678901234567890123456789012345678"></infNFe></NFe>
+            <protNFe><infProt>
+                <chNFe>35210112345678901234567890123456789012345678</chNFe>
+                <nProt>135210012345999</nProt>
+                <cStat>302</cStat>
+                <xMotivo>Uso Denegado: Irregularidade fiscal do destinatário</xMotivo>
+            </infProt></protNFe>
+        </nfeProc>"#;
+        let nf = parser.parse_string(denegada).unwrap();
+        assert_eq!(nf.status, ProcessingStatus::Unauthorized);
+        assert!(!nf.protocolo.as_ref().unwrap().autorizado());
+        
+        // protNFe autorizado de outra nota enxertado no XML: a chave vem do `Id`, não do protocolo
+        let enxertado = r#"<nfeProc>
+            <NFe><infNFe Id="NFe35210112345678901234567890123456789012345678"></infNFe></NFe>
+            <protNFe><infProt>
+                <chNFe>35210112345678000190550010000123451123456788</chNFe>
+                <nProt>135210000000001</nProt>
+                <cStat>100</cStat>
+                <xMotivo>Autorizado o uso da NF-e</xMotivo>
+            </infProt></protNFe>
+        </nfeProc>"#;
+        let nf = parser.parse_string(enxertado).unwrap();
+        assert_eq!(nf.chave_acesso, "35210112345678901234567890123456789012345678");
+        assert!(nf.protocolo.as_ref().unwrap().autorizado());
+        assert_eq!(nf.status, ProcessingStatus::Unauthorized);
+        
+        let sem_protocolo = r#"<NFe><infNFe Id="NFe35210112345678901234567890123456789012345678"></infNFe></NFe>"#;
+        let nf = parser.parse_string(sem_protocolo).unwrap();
+        assert!(nf.protocolo.is_none());
+        assert_eq!(nf.status, ProcessingStatus::Unauthorized);
+    }
+    
+    #[test]
+    fn test_nfce_parser() {
+        let parser = NFeParser::new();
+        let nf = parser.parse_string(include_str!("../../test_data/sample_nfe.xml")).unwrap();
+        assert_eq!(nf.modelo, ModeloNotaFiscal::NFe);
+        assert_eq!(nf.document_type(), DocumentType::NotaFiscal);
+        assert_eq!(nf.identificacao.codigo_uf, "35");
+        assert_eq!(nf.identificacao.tipo_emissao, "1");
+        assert_eq!(nf.identificacao.codigo_numerico, "12345678");
+        assert!(nf.informacoes_suplementares.is_none());
+        
+        let xml = r#"<nfeProc><NFe>
+            <infNFe Id="NFe35210112345678901234650010000000011000000010">
+                <ide><mod>65</mod><nNF>1</nNF><serie>1</serie></ide>
+            </infNFe>
+            <infNFeSupl>
+                <qrCode><![CDATA[https://www.homologacao.nfce.fazenda.sp.gov.br/qrcode?p=35210112345678901234650010000000011000000010|2|1|1|ABCDEF]]></qrCode>
+                <urlChave>https://www.homologacao.nfce.fazenda.sp.gov.br/consulta</urlChave>
+            </infNFeSupl>
+        </NFe></nfeProc>"#;
+        let nf = parser.parse_string(xml).unwrap();
+        assert_eq!(nf.modelo, ModeloNotaFiscal::NFCe);
+        assert_eq!(nf.document_type(), DocumentType::NotaFiscalConsumidor);
+        let supl = nf.informacoes_suplementares.unwrap();
+        assert!(supl.qr_code.starts_with("https://www.homologacao.nfce.fazenda.sp.gov.br/qrcode?p=3521"));
+        assert!(supl.qr_code.ends_with("|2|1|1|ABCDEF"));
+        assert_eq!(supl.url_chave, "https://www.homologacao.nfce.fazenda.sp.gov.br/consulta");
+    }
+    
+    #[test]
+    fn test_nfe_parser_pagamento_cobranca() {
+        let parser = NFeParser::new();
+        let nf = parser.parse_string(include_str!("../../test_data/sample_nfe.xml")).unwrap();
+        let fatura = nf.cobranca.fatura.as_ref().unwrap();
+        assert_eq!(fatura.numero.as_deref(), Some("000001"));
+        assert_eq!(fatura.valor_liquido, 1000.0);
+        assert_eq!(nf.cobranca.duplicatas.len(), 2);
+        assert_eq!(nf.cobranca.duplicatas[1].numero, "002");
+        assert_eq!(nf.cobranca.duplicatas[1].vencimento, NaiveDate::from_ymd_opt(2021, 3, 1));
+        assert_eq!(nf.cobranca.duplicatas[1].valor, 500.0);
+        // vDesc da fatura não pode vazar para o total da nota
+        assert_eq!(nf.totais.valor_total, 1000.0);
+        
+        let xml = r#"<NFe><infNFe Id="NFe35210112345678901234567890123456789012345678">
+            <pag>
+                <detPag><tPag>01</tPag><vPag>50.00</vPag></detPag>
+                <detPag>
+                    <tPag>03</tPag><vPag>150.00</vPag>
+                    <card><tpIntegra>1</tpIntegra><CNPJ>01027058000191</CNPJ><tBand>02</tBand><cAut>AUT123</cAut></card>
+                </detPag>
+                <vTroco>10.00</vTroco>
+            </pag>
+        </infNFe></NFe>"#;
+        let pagamento = parser.parse_string(xml).unwrap().pagamento;
+        assert_eq!(pagamento.formas.len(), 2);
+        assert_eq!(pagamento.formas[0].meio, MeioPagamento::Dinheiro);
+        assert!(pagamento.formas[0].cartao.is_none());
+        assert_eq!(pagamento.formas[1].meio, MeioPagamento::CartaoCredito);
+        assert_eq!(pagamento.formas[1].valor, 150.0);
+        let cartao = pagamento.formas[1].cartao.as_ref().unwrap();
+        assert_eq!(cartao.cnpj_credenciadora.as_deref(), Some("01027058000191"));
+        assert_eq!(cartao.bandeira.as_deref(), Some("02"));
+        assert_eq!(cartao.autorizacao.as_deref(), Some("AUT123"));
+        assert_eq!(pagamento.valor_troco, 10.0);
+    }
 }
@@ -0,0 +1,498 @@
+use crate::error::{GeolocationError, Result};
+use crate::models::*;
+use crate::parsers::{finalize_participante, parse_participante_field, FiscalDocumentParser};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Parser de NFS-e no Padrão Nacional (`NFSe`/`infNFSe`) e no ABRASF 2.x (`CompNfse`/`InfNfse`)
+pub struct NFSeParser;
+
+impl NFSeParser {
+    pub fn new() -> Self {
+        NFSeParser
+    }
+
+    fn parse_xml_content(&self, xml: &str) -> Result<NotaFiscalServico> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut current_path: Vec<String> = Vec::new();
+
+        let mut layout = LayoutNFSe::Nacional;
+        let mut chave_acesso = String::new();
+        let mut numero = String::new();
+        let mut codigo_verificacao: Option<String> = None;
+        let mut data_emissao = String::new();
+        let mut competencia: Option<NaiveDate> = None;
+        let mut codigo_municipio_emissor = String::new();
+        let mut numero_rps: Option<String> = None;
+        let mut serie_rps: Option<String> = None;
+        let mut prestador = Participante::default();
+        let mut tomador: Option<Participante> = None;
+        let mut optante_simples_nacional = false;
+        let mut servico = ServicoNFSe::default();
+        let mut valores = ValoresNFSe::default();
+        let mut retencoes = RetencoesNFSe::default();
+        // PIS/COFINS do Padrão Nacional: valores apurados e se foram retidos (`tpRetPisCofins`)
+        let mut pis_cofins = (0.0, 0.0);
+        let mut pis_cofins_retidos = false;
+        let mut cancelada = false;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => {
+                    // Prefixos de namespace são comuns no ABRASF (`ns2:CompNfse`)
+                    let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                    match name.as_str() {
+                        "infNFSe" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"Id" {
+                                    let id = String::from_utf8_lossy(&attr.value).to_string();
+                                    chave_acesso = id.trim_start_matches("NFS").to_string();
+                                }
+                            }
+                        }
+                        "InfNfse" => layout = LayoutNFSe::Abrasf,
+                        "NfseCancelamento" => cancelada = true,
+                        "toma" | "Tomador" | "TomadorServico" => {
+                            tomador.get_or_insert_with(Default::default);
+                        }
+                        _ => {}
+                    }
+                    current_path.push(name);
+                }
+                Ok(Event::End(_)) => {
+                    current_path.pop();
+                }
+                Ok(Event::Text(e)) => {
+                    let text = e.unescape().unwrap_or_default().to_string();
+                    let Some(tag) = current_path.last() else {
+                        buf.clear();
+                        continue;
+                    };
+                    let parent = current_path
+                        .len()
+                        .checked_sub(2)
+                        .map(|i| current_path[i].as_str())
+                        .unwrap_or_default();
+                    let dentro = |grupo: &str| current_path.iter().any(|p| p == grupo);
+
+                    match layout {
+                        LayoutNFSe::Nacional => match tag.as_str() {
+                            _ if dentro("emit") || dentro("prest") => match tag.as_str() {
+                                "IM" => prestador.inscricao_municipal = Some(text),
+                                "opSimpNac" => optante_simples_nacional = text != "1",
+                                _ => parse_participante_field(tag, &text, &mut prestador),
+                            },
+                            _ if dentro("toma") => {
+                                if let Some(tomador) = tomador.as_mut() {
+                                    match tag.as_str() {
+                                        "IM" => tomador.inscricao_municipal = Some(text),
+                                        _ => parse_participante_field(tag, &text, tomador),
+                                    }
+                                }
+                            }
+                            // Valores apurados pelo Sistema Nacional, fora da DPS
+                            _ if parent == "valores" && !dentro("infDPS") => match tag.as_str() {
+                                "vBC" => valores.base_calculo = parse_valor(&text),
+                                "pAliqAplic" => valores.aliquota_iss = parse_valor(&text),
+                                "vISSQN" => valores.valor_iss = parse_valor(&text),
+                                "vLiq" => valores.valor_liquido = parse_valor(&text),
+                                _ => {}
+                            },
+                            "nNFSe" => numero = text,
+                            "cLocIncid" => servico.codigo_municipio_incidencia = Some(text),
+                            "dhEmi" => data_emissao = text,
+                            "serie" => serie_rps = Some(text),
+                            "nDPS" => numero_rps = Some(text),
+                            "dCompet" => competencia = parse_data(&text),
+                            "cLocEmi" => codigo_municipio_emissor = text,
+                            "cLocPrestacao" => servico.codigo_municipio_prestacao = text,
+                            "cTribNac" => servico.codigo_tributacao_nacional = Some(text),
+                            "cTribMun" => servico.codigo_tributacao_municipal = Some(text),
+                            "xDescServ" => servico.discriminacao = text,
+                            "cNBS" => servico.codigo_nbs = Some(text),
+                            "vServ" => valores.valor_servicos = parse_valor(&text),
+                            "vDR" => valores.valor_deducoes = parse_valor(&text),
+                            "vDescIncond" => valores.desconto_incondicionado = parse_valor(&text),
+                            "vDescCond" => valores.desconto_condicionado = parse_valor(&text),
+                            // 1 = não retido; 2 = retido pelo tomador; 3 = pelo intermediário
+                            "tpRetISSQN" => valores.iss_retido = text != "1",
+                            "pAliq" if valores.aliquota_iss == 0.0 => {
+                                valores.aliquota_iss = parse_valor(&text)
+                            }
+                            "vPis" => pis_cofins.0 = parse_valor(&text),
+                            "vCofins" => pis_cofins.1 = parse_valor(&text),
+                            // 1 = retido; 2 = não retido
+                            "tpRetPisCofins" => pis_cofins_retidos = text == "1",
+                            "vRetCP" => retencoes.inss = parse_valor(&text),
+                            "vRetIRRF" => retencoes.irrf = parse_valor(&text),
+                            "vRetCSLL" => retencoes.csll = parse_valor(&text),
+                            _ => {}
+                        },
+                        LayoutNFSe::Abrasf => match tag.as_str() {
+                            _ if dentro("NfseCancelamento") => {}
+                            _ if dentro("PrestadorServico") || dentro("Prestador") => {
+                                parse_participante_abrasf(tag, &text, &mut prestador)
+                            }
+                            _ if dentro("Tomador") || dentro("TomadorServico") => {
+                                if let Some(tomador) = tomador.as_mut() {
+                                    parse_participante_abrasf(tag, &text, tomador);
+                                }
+                            }
+                            _ if dentro("OrgaoGerador") => {
+                                if tag == "CodigoMunicipio" {
+                                    codigo_municipio_emissor = text;
+                                }
+                            }
+                            _ if dentro("IdentificacaoRps") => match tag.as_str() {
+                                "Numero" => numero_rps = Some(text),
+                                "Serie" => serie_rps = Some(text),
+                                _ => {}
+                            },
+                            _ if dentro("ValoresNfse") => match tag.as_str() {
+                                "BaseCalculo" => valores.base_calculo = parse_valor(&text),
+                                "Aliquota" => valores.aliquota_iss = parse_aliquota(&text),
+                                "ValorIss" => valores.valor_iss = parse_valor(&text),
+                                "ValorLiquidoNfse" => valores.valor_liquido = parse_valor(&text),
+                                _ => {}
+                            },
+                            _ if dentro("Servico") => match tag.as_str() {
+                                "ValorServicos" => valores.valor_servicos = parse_valor(&text),
+                                "ValorDeducoes" => valores.valor_deducoes = parse_valor(&text),
+                                "DescontoIncondicionado" => {
+                                    valores.desconto_incondicionado = parse_valor(&text)
+                                }
+                                "DescontoCondicionado" => {
+                                    valores.desconto_condicionado = parse_valor(&text)
+                                }
+                                "ValorPis" => retencoes.pis = parse_valor(&text),
+                                "ValorCofins" => retencoes.cofins = parse_valor(&text),
+                                "ValorInss" => retencoes.inss = parse_valor(&text),
+                                "ValorIr" => retencoes.irrf = parse_valor(&text),
+                                "ValorCsll" => retencoes.csll = parse_valor(&text),
+                                "OutrasRetencoes" => retencoes.outras = parse_valor(&text),
+                                // ValoresNfse prevalece sobre os valores declarados no RPS
+                                "ValorIss" if valores.valor_iss == 0.0 => {
+                                    valores.valor_iss = parse_valor(&text)
+                                }
+                                "Aliquota" if valores.aliquota_iss == 0.0 => {
+                                    valores.aliquota_iss = parse_aliquota(&text)
+                                }
+                                "BaseCalculo" if valores.base_calculo == 0.0 => {
+                                    valores.base_calculo = parse_valor(&text)
+                                }
+                                // 1 = sim; 2 = não
+                                "IssRetido" => valores.iss_retido = text == "1",
+                                "ItemListaServico" => servico.item_lista_servico = Some(text),
+                                "CodigoCnae" => servico.codigo_cnae = Some(text),
+                                "CodigoTributacaoMunicipio" => {
+                                    servico.codigo_tributacao_municipal = Some(text)
+                                }
+                                "CodigoNbs" => servico.codigo_nbs = Some(text),
+                                "Discriminacao" => servico.discriminacao = text,
+                                "CodigoMunicipio" => servico.codigo_municipio_prestacao = text,
+                                "MunicipioIncidencia" => {
+                                    servico.codigo_municipio_incidencia = Some(text)
+                                }
+                                _ => {}
+                            },
+                            "Numero" if parent == "InfNfse" => numero = text,
+                            "CodigoVerificacao" => codigo_verificacao = Some(text),
+                            "DataEmissao" if parent == "InfNfse" => data_emissao = text,
+                            "Competencia" => competencia = parse_data(&text),
+                            // 1 = sim; 2 = não
+                            "OptanteSimplesNacional" => optante_simples_nacional = text == "1",
+                            _ => {}
+                        },
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(GeolocationError::XmlParseError(format!(
+                        "Erro ao parsear NFS-e: {}",
+                        e
+                    )))
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        // ABRASF não tem chave de acesso: o número só é único por prestador e município
+        if layout == LayoutNFSe::Abrasf {
+            if numero.is_empty() || prestador.cnpj_cpf.is_empty() {
+                return Err(GeolocationError::InvalidAccessKey(
+                    "Número ou prestador da NFS-e não encontrado".into(),
+                ));
+            }
+            chave_acesso = format!(
+                "{}{}{:0>15}",
+                codigo_municipio_emissor, prestador.cnpj_cpf, numero
+            );
+        } else if chave_acesso.len() != 50 || !chave_acesso.chars().all(|c| c.is_ascii_digit()) {
+            return Err(GeolocationError::InvalidAccessKey(
+                "Chave da NFS-e não encontrada".into(),
+            ));
+        }
+
+        finalize_participante(&mut prestador);
+        if let Some(tomador) = tomador.as_mut() {
+            finalize_participante(tomador);
+        }
+
+        if pis_cofins_retidos {
+            (retencoes.pis, retencoes.cofins) = pis_cofins;
+        }
+
+        if valores.valor_liquido == 0.0 {
+            valores.valor_liquido = valores.valor_servicos
+                - retencoes.total()
+                - valores.desconto_incondicionado
+                - valores.desconto_condicionado
+                - if valores.iss_retido { valores.valor_iss } else { 0.0 };
+        }
+
+        let status = if cancelada {
+            ProcessingStatus::Cancelled
+        } else {
+            ProcessingStatus::Completed
+        };
+
+        Ok(NotaFiscalServico {
+            id: Uuid::new_v4(),
+            layout,
+            chave_acesso,
+            numero,
+            codigo_verificacao,
+            data_emissao: parse_data_hora(&data_emissao),
+            competencia,
+            codigo_municipio_emissor,
+            numero_rps,
+            serie_rps,
+            prestador,
+            tomador,
+            optante_simples_nacional,
+            servico,
+            valores,
+            retencoes,
+            status,
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Preenche um campo de prestador ou tomador no leiaute ABRASF
+fn parse_participante_abrasf(tag: &str, text: &str, participante: &mut Participante) {
+    let endereco = &mut participante.endereco;
+    match tag {
+        "Cnpj" | "Cpf" => participante.cnpj_cpf = text.to_string(),
+        "InscricaoMunicipal" => participante.inscricao_municipal = Some(text.to_string()),
+        "RazaoSocial" => participante.razao_social = text.to_string(),
+        "NomeFantasia" => participante.nome_fantasia = Some(text.to_string()),
+        "Telefone" => participante.telefone = Some(text.to_string()),
+        "Email" => participante.email = Some(text.to_string()),
+        "Endereco" => endereco.logradouro = text.to_string(),
+        "Numero" => endereco.numero = text.to_string(),
+        "Complemento" => endereco.complemento = Some(text.to_string()),
+        "Bairro" => endereco.bairro = text.to_string(),
+        "CodigoMunicipio" => endereco.codigo_municipio = text.to_string(),
+        "Uf" => endereco.uf = text.to_string(),
+        "Cep" => endereco.cep = text.to_string(),
+        "CodigoPais" => endereco.codigo_pais = text.to_string(),
+        _ => {}
+    }
+}
+
+fn parse_valor(text: &str) -> f64 {
+    text.parse().unwrap_or(0.0)
+}
+
+/// Alíquota em percentual: alguns municípios ABRASF informam a fração (0.05 para 5%)
+fn parse_aliquota(text: &str) -> f64 {
+    let aliquota = parse_valor(text);
+    if aliquota > 0.0 && aliquota < 1.0 {
+        aliquota * 100.0
+    } else {
+        aliquota
+    }
+}
+
+fn parse_data(text: &str) -> Option<NaiveDate> {
+    text.get(..10)
+        .and_then(|data| NaiveDate::parse_from_str(data, "%Y-%m-%d").ok())
+}
+
+/// Data e hora com ou sem fuso; o ABRASF costuma omitir o fuso
+fn parse_data_hora(text: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S").map(|dt| dt.and_utc())
+        })
+        .or_else(|_| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        })
+        .unwrap_or_else(|_| Utc::now())
+}
+
+impl FiscalDocumentParser for NFSeParser {
+    type Output = NotaFiscalServico;
+
+    fn parse_file(&self, path: &Path) -> Result<Self::Output> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| GeolocationError::XmlReadError(e.to_string()))?;
+        self.parse_string(&content)
+    }
+
+    fn parse_bytes(&self, data: &[u8]) -> Result<Self::Output> {
+        let content = String::from_utf8(data.to_vec())
+            .map_err(|e| GeolocationError::EncodingError(e.to_string()))?;
+        self.parse_string(&content)
+    }
+
+    fn parse_string(&self, xml: &str) -> Result<Self::Output> {
+        self.parse_xml_content(xml)
+    }
+}
+
+impl Default for NFSeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nfse_padrao_nacional() {
+        let parser = NFSeParser::new();
+        let nfse = parser.parse_string(include_str!("../../test_data/sample_nfse.xml")).unwrap();
+
+        assert_eq!(nfse.layout, LayoutNFSe::Nacional);
+        assert_eq!(nfse.chave_acesso, "35503082212345678000190000000000001221010123456789");
+        assert_eq!(nfse.numero, "12");
+        assert_eq!(nfse.numero_rps.as_deref(), Some("7"));
+        assert_eq!(nfse.codigo_municipio_emissor, "3550308");
+        assert_eq!(nfse.competencia, NaiveDate::from_ymd_opt(2021, 1, 15));
+        assert_eq!(nfse.prestador.cnpj_cpf, "12345678000190");
+        assert_eq!(nfse.prestador.inscricao_municipal.as_deref(), Some("12345"));
+        assert_eq!(nfse.prestador.endereco.uf, "SP");
+        assert!(!nfse.optante_simples_nacional);
+
+        let tomador = nfse.tomador.as_ref().unwrap();
+        assert_eq!(tomador.cnpj_cpf, "98765432000110");
+        assert_eq!(tomador.razao_social, "Cliente Servicos SA");
+
+        assert_eq!(nfse.servico.codigo_tributacao_nacional.as_deref(), Some("010101"));
+        assert_eq!(nfse.servico.codigo_municipio_incidencia.as_deref(), Some("3550308"));
+        assert_eq!(nfse.valores.valor_servicos, 1000.0);
+        assert_eq!(nfse.valores.aliquota_iss, 5.0);
+        assert_eq!(nfse.valores.valor_iss, 50.0);
+        assert!(nfse.valores.iss_retido);
+        assert_eq!(nfse.retencoes.irrf, 15.0);
+        assert_eq!(nfse.valores.valor_liquido, 935.0);
+        assert_eq!(nfse.status, ProcessingStatus::Completed);
+    }
+
+    #[test]
+    fn test_nfse_padrao_nacional_pis_cofins() {
+        let xml = include_str!("../../test_data/sample_nfse.xml")
+            .replace("<vLiq>935.00</vLiq>", "")
+            .replace(
+                "<vRetIRRF>15.00</vRetIRRF>",
+                "<piscofins><CST>01</CST><vBCPisCofins>1000.00</vBCPisCofins>\
+                 <pAliqPis>0.65</pAliqPis><pAliqCofins>3.00</pAliqCofins>\
+                 <vPis>6.50</vPis><vCofins>30.00</vCofins>\
+                 <tpRetPisCofins>2</tpRetPisCofins></piscofins><vRetIRRF>15.00</vRetIRRF>",
+            );
+        let nfse = NFSeParser::new().parse_string(&xml).unwrap();
+        // Apurados mas não retidos: não reduzem o valor líquido
+        assert_eq!(nfse.retencoes.pis, 0.0);
+        assert_eq!(nfse.retencoes.cofins, 0.0);
+        assert_eq!(nfse.valores.valor_liquido, 935.0);
+
+        let xml = xml.replace(
+            "<tpRetPisCofins>2</tpRetPisCofins>",
+            "<tpRetPisCofins>1</tpRetPisCofins>",
+        );
+        let nfse = NFSeParser::new().parse_string(&xml).unwrap();
+        assert_eq!(nfse.retencoes.pis, 6.5);
+        assert_eq!(nfse.retencoes.cofins, 30.0);
+        assert_eq!(nfse.valores.valor_liquido, 898.5);
+    }
+
+    #[test]
+    fn test_nfse_abrasf() {
+        let xml = r#"<ns2:ConsultarNfseResposta xmlns:ns2="http://www.abrasf.org.br/nfse.xsd">
+            <ns2:ListaNfse><ns2:CompNfse><ns2:Nfse versao="2.02"><ns2:InfNfse Id="nfse123">
+                <ns2:Numero>202100000000042</ns2:Numero>
+                <ns2:CodigoVerificacao>ABCD1234</ns2:CodigoVerificacao>
+                <ns2:DataEmissao>2021-01-15T10:30:00</ns2:DataEmissao>
+                <ns2:ValoresNfse><ns2:BaseCalculo>2000.00</ns2:BaseCalculo><ns2:Aliquota>0.02</ns2:Aliquota>
+                    <ns2:ValorIss>40.00</ns2:ValorIss><ns2:ValorLiquidoNfse>1890.00</ns2:ValorLiquidoNfse></ns2:ValoresNfse>
+                <ns2:PrestadorServico>
+                    <ns2:IdentificacaoPrestador><ns2:CpfCnpj><ns2:Cnpj>12345678000190</ns2:Cnpj></ns2:CpfCnpj>
+                        <ns2:InscricaoMunicipal>998877</ns2:InscricaoMunicipal></ns2:IdentificacaoPrestador>
+                    <ns2:RazaoSocial>Consultoria Teste Ltda</ns2:RazaoSocial>
+                    <ns2:Endereco><ns2:Endereco>Av. Brasil</ns2:Endereco><ns2:Numero>100</ns2:Numero>
+                        <ns2:Bairro>Centro</ns2:Bairro><ns2:CodigoMunicipio>3106200</ns2:CodigoMunicipio>
+                        <ns2:Uf>MG</ns2:Uf><ns2:Cep>30100000</ns2:Cep></ns2:Endereco>
+                </ns2:PrestadorServico>
+                <ns2:OrgaoGerador><ns2:CodigoMunicipio>3106200</ns2:CodigoMunicipio><ns2:Uf>MG</ns2:Uf></ns2:OrgaoGerador>
+                <ns2:DeclaracaoPrestacaoServico><ns2:InfDeclaracaoPrestacaoServico>
+                    <ns2:Rps><ns2:IdentificacaoRps><ns2:Numero>55</ns2:Numero><ns2:Serie>A</ns2:Serie><ns2:Tipo>1</ns2:Tipo></ns2:IdentificacaoRps>
+                        <ns2:DataEmissao>2021-01-14</ns2:DataEmissao><ns2:Status>1</ns2:Status></ns2:Rps>
+                    <ns2:Competencia>2021-01-14</ns2:Competencia>
+                    <ns2:Servico>
+                        <ns2:Valores><ns2:ValorServicos>2000.00</ns2:ValorServicos><ns2:ValorPis>13.00</ns2:ValorPis>
+                            <ns2:ValorCofins>60.00</ns2:ValorCofins><ns2:ValorIr>30.00</ns2:ValorIr><ns2:ValorCsll>20.00</ns2:ValorCsll>
+                            <ns2:ValorIss>40.00</ns2:ValorIss><ns2:Aliquota>0.02</ns2:Aliquota></ns2:Valores>
+                        <ns2:IssRetido>2</ns2:IssRetido>
+                        <ns2:ItemListaServico>17.01</ns2:ItemListaServico>
+                        <ns2:CodigoCnae>7020400</ns2:CodigoCnae>
+                        <ns2:Discriminacao>Consultoria empresarial</ns2:Discriminacao>
+                        <ns2:CodigoMunicipio>3106200</ns2:CodigoMunicipio>
+                        <ns2:MunicipioIncidencia>3106200</ns2:MunicipioIncidencia>
+                    </ns2:Servico>
+                    <ns2:Prestador><ns2:CpfCnpj><ns2:Cnpj>12345678000190</ns2:Cnpj></ns2:CpfCnpj></ns2:Prestador>
+                    <ns2:TomadorServico>
+                        <ns2:IdentificacaoTomador><ns2:CpfCnpj><ns2:Cpf>52998224725</ns2:Cpf></ns2:CpfCnpj></ns2:IdentificacaoTomador>
+                        <ns2:RazaoSocial>Maria Souza</ns2:RazaoSocial>
+                        <ns2:Contato><ns2:Email>maria@example.com</ns2:Email></ns2:Contato>
+                    </ns2:TomadorServico>
+                    <ns2:OptanteSimplesNacional>2</ns2:OptanteSimplesNacional>
+                </ns2:InfDeclaracaoPrestacaoServico></ns2:DeclaracaoPrestacaoServico>
+            </ns2:InfNfse></ns2:Nfse>
+            <ns2:NfseCancelamento><ns2:Confirmacao><ns2:Pedido><ns2:InfPedidoCancelamento>
+                <ns2:IdentificacaoNfse><ns2:Numero>202100000000042</ns2:Numero></ns2:IdentificacaoNfse>
+                <ns2:CodigoCancelamento>1</ns2:CodigoCancelamento>
+            </ns2:InfPedidoCancelamento></ns2:Pedido></ns2:Confirmacao></ns2:NfseCancelamento>
+            </ns2:CompNfse></ns2:ListaNfse></ns2:ConsultarNfseResposta>"#;
+        let nfse = NFSeParser::new().parse_string(xml).unwrap();
+
+        assert_eq!(nfse.layout, LayoutNFSe::Abrasf);
+        assert_eq!(nfse.numero, "202100000000042");
+        assert_eq!(nfse.chave_acesso, "310620012345678000190202100000000042");
+        assert_eq!(nfse.codigo_verificacao.as_deref(), Some("ABCD1234"));
+        assert_eq!((nfse.numero_rps.as_deref(), nfse.serie_rps.as_deref()), (Some("55"), Some("A")));
+        assert_eq!(nfse.prestador.inscricao_municipal.as_deref(), Some("998877"));
+        assert_eq!(nfse.prestador.endereco.numero, "100");
+        assert_eq!(nfse.prestador.endereco.uf, "MG");
+        assert_eq!(nfse.tomador.as_ref().unwrap().cnpj_cpf, "52998224725");
+        assert_eq!(nfse.servico.item_lista_servico.as_deref(), Some("17.01"));
+        assert_eq!(nfse.valores.aliquota_iss, 2.0);
+        assert!(!nfse.valores.iss_retido);
+        assert!((nfse.retencoes.total() - 123.0).abs() < 0.001);
+        assert_eq!(nfse.valores.valor_liquido, 1890.0);
+        assert_eq!(nfse.status, ProcessingStatus::Cancelled);
+    }
+}
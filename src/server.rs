@@ -8,14 +8,14 @@ use axum::{
 use geolocation::database::repository::DocumentSummary;
 use geolocation::database::mongodb::MongoDB;
 use geolocation::utils::metrics::{gather_metrics, register_metrics};
-use geolocation::{process_document_content, DocumentType};
+use geolocation::{process_document_content, DocumentType, GeolocationError};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tower_http::{
     cors::{Any, CorsLayer},
     services::ServeDir,
 };
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 use tracing_subscriber::prelude::*;
 
 mod search_api;
@@ -40,7 +40,13 @@ struct StatsResponse {
     total_documents: i64,
     processed_today: i64,
     notas_fiscais: i64,
+    nfces: i64,
     ctes: i64,
+    ctes_os: i64,
+    ctes_simplificados: i64,
+    mdfes: i64,
+    nfses: i64,
+    cancelados: i64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -57,6 +63,17 @@ async fn main() {
         .with(fmt::layer().with_target(false).compact())
         .init();
 
+    // Sem a TIPI completa o validador não aponta NCM inexistente
+    let tipi = geolocation::validators::TabelaNcm::padrao();
+    match tipi.exigir_completa() {
+        Ok(_) => info!("✓ TIPI {} carregada ({} NCMs)", tipi.versao, tipi.len()),
+        Err(e) if geolocation::validators::TabelaNcm::obrigatoria() => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+        Err(e) => warn!("{}; NCM_NOT_FOUND desativado", e),
+    }
+
     // Conecta ao MongoDB
     let mongo_uri = std::env::var("MONGODB_URI").expect("MONGODB_URI deve ser definido");
     let mongo = MongoDB::connect(&mongo_uri)
@@ -187,18 +204,37 @@ async fn upload_document(
 
             match process_document_content(&xml_content, state.mongo.as_ref()).await {
                 Ok(result) => {
-                    let document_type = match result.document_type {
-                        DocumentType::NotaFiscal => "NFe".to_string(),
-                        DocumentType::ConhecimentoTransporte => "CTe".to_string(),
-                    };
                     return Ok(Json(UploadResponse {
                         success: result.success,
-                        document_type,
+                        document_type: result.document_type.codigo().to_string(),
                         chave_acesso: result.chave_acesso,
                         message: result.message,
                         duplicate: result.duplicate,
                     }));
                 }
+                Err(
+                    e @ (GeolocationError::DocumentNotAuthorized(_)
+                    | GeolocationError::InvalidAccessKey(_)),
+                ) => {
+                    warn!(error = %e, "Documento rejeitado");
+                    return Err((
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        Json(ErrorResponse { error: e.to_string() }),
+                    ));
+                }
+                // XML malformado ou de tipo não suportado é erro do cliente
+                Err(
+                    e @ (GeolocationError::XmlParseError(_)
+                    | GeolocationError::InvalidXmlStructure(_)
+                    | GeolocationError::UnsupportedDocumentType(_)
+                    | GeolocationError::EncodingError(_)),
+                ) => {
+                    warn!(error = %e, "Documento inválido");
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(ErrorResponse { error: e.to_string() }),
+                    ));
+                }
                 Err(e) => {
                     warn!(error = %e, "Erro ao processar documento");
                     return Err(internal_error(format!(
@@ -218,10 +254,28 @@ async fn get_stats(
 ) -> Result<Json<StatsResponse>, (StatusCode, Json<ErrorResponse>)> {
     let total_nfes = state.mongo.count_documents(Some("NFe")).await
         .map_err(|e| internal_error(format!("Erro ao contar NFes: {}", e)))?;
+    let total_nfces = state.mongo.count_documents(Some("NFCe")).await
+        .map_err(|e| internal_error(format!("Erro ao contar NFCes: {}", e)))?;
     let total_ctes = state.mongo.count_documents(Some("CTe")).await
         .map_err(|e| internal_error(format!("Erro ao contar CTes: {}", e)))?;
+    let total_ctes_os = state.mongo.count_documents(Some("CTeOS")).await
+        .map_err(|e| internal_error(format!("Erro ao contar CTes OS: {}", e)))?;
+    let total_ctes_simp = state.mongo.count_documents(Some("CTeSimp")).await
+        .map_err(|e| internal_error(format!("Erro ao contar CTes Simplificados: {}", e)))?;
+    let total_mdfes = state.mongo.count_documents(Some("MDFe")).await
+        .map_err(|e| internal_error(format!("Erro ao contar MDFes: {}", e)))?;
+    let total_nfses = state.mongo.count_documents(Some("NFSe")).await
+        .map_err(|e| internal_error(format!("Erro ao contar NFSes: {}", e)))?;
+    let total_cancelados = state.mongo.count_cancelados().await
+        .map_err(|e| internal_error(format!("Erro ao contar cancelados: {}", e)))?;
     
-    let total_documents = total_nfes + total_ctes;
+    let total_documents = total_nfes
+        + total_nfces
+        + total_ctes
+        + total_ctes_os
+        + total_ctes_simp
+        + total_mdfes
+        + total_nfses;
     
     // TODO: Implementar contagem de documentos processados hoje
     let processed_today = 0i64;
@@ -230,7 +284,13 @@ async fn get_stats(
         total_documents,
         processed_today,
         notas_fiscais: total_nfes,
+        nfces: total_nfces,
         ctes: total_ctes,
+        ctes_os: total_ctes_os,
+        ctes_simplificados: total_ctes_simp,
+        mdfes: total_mdfes,
+        nfses: total_nfses,
+        cancelados: total_cancelados,
     }))
 }
 
@@ -263,7 +323,7 @@ struct ListQuery {
     offset: Option<i64>,
 }
 
-/// Retorna detalhes de um documento (NF-e ou CT-e) incluindo última validação
+/// Retorna detalhes de um documento (NF-e, CT-e ou NFS-e) incluindo última validação
 async fn get_document_by_chave(
     State(state): State<AppState>,
     Path(chave): Path<String>,
@@ -272,7 +332,7 @@ async fn get_document_by_chave(
     if let Ok(Some(nfe)) = state.mongo.find_nota_fiscal_by_chave(&chave).await {
         let validation = state.mongo.find_validation_by_chave(&chave).await.ok().flatten();
         let json = serde_json::json!({
-            "document_type": "NFe",
+            "document_type": nfe.document_type().codigo(),
             "chave_acesso": nfe.chave_acesso,
             "numero": nfe.numero,
             "serie": nfe.serie,
@@ -289,7 +349,7 @@ async fn get_document_by_chave(
     if let Ok(Some(cte)) = state.mongo.find_conhecimento_by_chave(&chave).await {
         let validation = state.mongo.find_validation_by_chave(&chave).await.ok().flatten();
         let json = serde_json::json!({
-            "document_type": "CTe",
+            "document_type": cte.document_type().codigo(),
             "chave_acesso": cte.chave_acesso,
             "numero": cte.numero,
             "serie": cte.serie,
@@ -302,6 +362,45 @@ async fn get_document_by_chave(
         return Ok(Json(json));
     }
     
+    // Buscar em MDF-es, com as chaves dos documentos vinculados à viagem
+    if let Ok(Some(mdfe)) = state.mongo.find_manifesto_by_chave(&chave).await {
+        let validation = state.mongo.find_validation_by_chave(&chave).await.ok().flatten();
+        let json = serde_json::json!({
+            "document_type": DocumentType::ManifestoDocumentos.codigo(),
+            "chave_acesso": mdfe.chave_acesso,
+            "numero": mdfe.numero,
+            "serie": mdfe.serie,
+            "data_emissao": mdfe.data_emissao.to_rfc3339(),
+            "emitente": mdfe.emitente.razao_social,
+            "uf_inicio": mdfe.uf_inicio,
+            "uf_fim": mdfe.uf_fim,
+            "percurso_ufs": mdfe.percurso_ufs,
+            "municipios_descarregamento": mdfe.municipios_descarregamento,
+            "valor_total": mdfe.totais.valor_carga,
+            "validation": validation
+        });
+        return Ok(Json(json));
+    }
+    
+    // Buscar em NFS-es
+    if let Ok(Some(nfse)) = state.mongo.find_nota_servico_by_chave(&chave).await {
+        let validation = state.mongo.find_validation_by_chave(&chave).await.ok().flatten();
+        let json = serde_json::json!({
+            "document_type": DocumentType::NotaFiscalServico.codigo(),
+            "chave_acesso": nfse.chave_acesso,
+            "numero": nfse.numero,
+            "serie": nfse.serie_rps,
+            "data_emissao": nfse.data_emissao.to_rfc3339(),
+            "emitente": nfse.prestador.razao_social,
+            "destinatario": nfse.tomador.map(|t| t.razao_social),
+            "valor_total": nfse.valores.valor_servicos,
+            "valor_iss": nfse.valores.valor_iss,
+            "iss_retido": nfse.valores.iss_retido,
+            "validation": validation
+        });
+        return Ok(Json(json));
+    }
+    
     // Documento não encontrado
     Err((
         StatusCode::NOT_FOUND,
@@ -0,0 +1,314 @@
+/// Valida uma Inscrição Estadual pelas regras de dígito verificador da UF publicadas no Sintegra
+///
+/// Aceita pontuação e o literal "ISENTO". Em SP, aceita também o produtor rural (`P-0MMMSSSS.D/SSS`).
+pub fn validate_ie(ie: &str, uf: &str) -> bool {
+    let ie: String = ie
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if ie == "ISENTO" {
+        return true;
+    }
+
+    if uf == "SP" {
+        if let Some(rural) = ie.strip_prefix('P') {
+            return sp_produtor_rural(&digitos(rural).unwrap_or_default());
+        }
+    }
+    let Some(d) = digitos(&ie) else {
+        return false;
+    };
+    if d.is_empty() {
+        return false;
+    }
+
+    match uf {
+        "AC" => d.len() == 13 && d[..2] == [0, 1] && dois_digitos(&d, 11),
+        "AL" => d.len() == 9 && d[..2] == [2, 4] && al(&d),
+        "AP" => d.len() == 9 && d[..2] == [0, 3] && ap(&d),
+        "AM" => d.len() == 9 && am(&d),
+        "BA" => matches!(d.len(), 8 | 9) && ba(&d),
+        "CE" | "PB" | "PI" | "SE" => d.len() == 9 && d[8] == dv_zerando(&d[..8], &PESOS_9),
+        "DF" => d.len() == 13 && d[0] == 0 && matches!(d[1], 7 | 8) && dois_digitos(&d, 11),
+        "ES" | "MA" | "PA" | "SC" | "TO" if d.len() == 9 => {
+            let prefixo = match uf {
+                "MA" => Some([1, 2]),
+                "PA" => Some([1, 5]),
+                _ => None,
+            };
+            !matches!(prefixo, Some(p) if d[..2] != p) && d[8] == dv(&d[..8], &PESOS_9)
+        }
+        // TO antigo: 11 dígitos com o tipo de empresa (01, 02, 03 ou 99) na 3ª e 4ª posições
+        "TO" => {
+            d.len() == 11 && matches!(d[2] * 10 + d[3], 1 | 2 | 3 | 99) && {
+                let base: Vec<u32> = [&d[..2], &d[4..10]].concat();
+                d[10] == dv(&base, &PESOS_9)
+            }
+        }
+        "GO" => d.len() == 9 && go(&d),
+        "MT" => (9..=11).contains(&d.len()) && mt(&d),
+        "MS" => d.len() == 9 && d[..2] == [2, 8] && ms(&d),
+        "MG" => d.len() == 13 && mg(&d),
+        "PR" => {
+            d.len() == 10
+                && d[8] == dv(&d[..8], &[3, 2, 7, 6, 5, 4, 3, 2])
+                && d[9] == dv(&d[..9], &[4, 3, 2, 7, 6, 5, 4, 3, 2])
+        }
+        "PE" => pe(&d),
+        "RJ" => d.len() == 8 && d[7] == dv(&d[..7], &[2, 7, 6, 5, 4, 3, 2]),
+        "RN" => matches!(d.len(), 9 | 10) && d[..2] == [2, 0] && rn(&d),
+        "RS" => d.len() == 10 && d[9] == dv_zerando(&d[..9], &[2, 9, 8, 7, 6, 5, 4, 3, 2]),
+        "RO" => ro(&d),
+        "RR" => d.len() == 9 && d[..2] == [2, 4] && rr(&d),
+        "SP" => d.len() == 12 && sp(&d),
+        _ => false,
+    }
+}
+
+const PESOS_9: [u32; 8] = [9, 8, 7, 6, 5, 4, 3, 2];
+
+fn digitos(texto: &str) -> Option<Vec<u32>> {
+    texto.chars().map(|c| c.to_digit(10)).collect()
+}
+
+fn soma(digitos: &[u32], pesos: &[u32]) -> u32 {
+    digitos.iter().zip(pesos).map(|(d, p)| d * p).sum()
+}
+
+/// Módulo 11 com DV 0 para restos 0 e 1
+fn dv(digitos: &[u32], pesos: &[u32]) -> u32 {
+    match soma(digitos, pesos) % 11 {
+        0 | 1 => 0,
+        resto => 11 - resto,
+    }
+}
+
+/// Módulo 11 com DV = 11 − resto, zerando quando passa de 9
+fn dv_zerando(digitos: &[u32], pesos: &[u32]) -> u32 {
+    match 11 - soma(digitos, pesos) % 11 {
+        10 | 11 => 0,
+        dv => dv,
+    }
+}
+
+/// AC e DF: dois DVs em módulo 11, com pesos de 2 a 9 aplicados da direita para a esquerda
+fn dois_digitos(d: &[u32], base: usize) -> bool {
+    let pesos = |n: usize| -> Vec<u32> { (0..n).rev().map(|i| (i % 8) as u32 + 2).collect() };
+    d[base] == dv_zerando(&d[..base], &pesos(base))
+        && d[base + 1] == dv_zerando(&d[..base + 1], &pesos(base + 1))
+}
+
+fn al(d: &[u32]) -> bool {
+    // 3º dígito: tipo de empresa (0 normal, 3 produtor rural, 5 substituta, 7 micro ambulante, 8 micro)
+    matches!(d[2], 0 | 3 | 5 | 7 | 8) && d[8] == soma(&d[..8], &PESOS_9) * 10 % 11 % 10
+}
+
+fn ap(d: &[u32]) -> bool {
+    let numero = d[..8].iter().fold(0, |n, d| n * 10 + d);
+    let (p, resto_zero) = match numero {
+        3_000_001..=3_017_000 => (5, 0),
+        3_017_001..=3_019_022 => (9, 1),
+        _ => (0, 0),
+    };
+    let esperado = match 11 - (p + soma(&d[..8], &PESOS_9)) % 11 {
+        10 => 0,
+        11 => resto_zero,
+        dv => dv,
+    };
+    d[8] == esperado
+}
+
+fn am(d: &[u32]) -> bool {
+    let soma = soma(&d[..8], &PESOS_9);
+    let esperado = if soma < 11 {
+        11 - soma
+    } else {
+        match soma % 11 {
+            0 | 1 => 0,
+            resto => 11 - resto,
+        }
+    };
+    d[8] == esperado
+}
+
+/// BA: o 1º dígito (8 posições) ou o 2º (9 posições) escolhe módulo 10 ou 11;
+/// o segundo DV é calculado antes e entra no cálculo do primeiro
+fn ba(d: &[u32]) -> bool {
+    let n = d.len();
+    let modulo = if matches!(d[n - 8], 6 | 7 | 9) {
+        11
+    } else {
+        10
+    };
+    let calcular = |digitos: &[u32]| {
+        let pesos: Vec<u32> = (2..=digitos.len() as u32 + 1).rev().collect();
+        let resto = soma(digitos, &pesos) % modulo;
+        match (modulo, resto) {
+            (10, 0) | (11, 0 | 1) => 0,
+            _ => modulo - resto,
+        }
+    };
+    let base = &d[..n - 2];
+    let dv2 = calcular(base);
+    let dv1 = calcular(&[base, &[dv2]].concat());
+    d[n - 2] == dv1 && d[n - 1] == dv2
+}
+
+fn go(d: &[u32]) -> bool {
+    if !matches!(d[0] * 10 + d[1], 10 | 11 | 15 | 20..=29) {
+        return false;
+    }
+    let numero = d[..8].iter().fold(0, |n, d| n * 10 + d);
+    let esperado = match soma(&d[..8], &PESOS_9) % 11 {
+        0 => 0,
+        // Inscrições antigas dessa faixa usam DV 1 quando o resto é 1
+        1 if (10_103_105..=10_119_997).contains(&numero) => 1,
+        1 => 0,
+        resto => 11 - resto,
+    };
+    // 11.094.402 foi emitida com DV 0 e 1
+    d[8] == esperado || (numero == 11_094_402 && d[8] <= 1)
+}
+
+fn mt(d: &[u32]) -> bool {
+    let mut completo = vec![0; 11 - d.len()];
+    completo.extend_from_slice(d);
+    completo[10] == dv(&completo[..10], &[3, 2, 9, 8, 7, 6, 5, 4, 3, 2])
+}
+
+fn ms(d: &[u32]) -> bool {
+    let esperado = match soma(&d[..8], &PESOS_9) % 11 {
+        0 => 0,
+        resto if 11 - resto > 9 => 0,
+        resto => 11 - resto,
+    };
+    d[8] == esperado
+}
+
+/// MG: 1º DV pela soma dos algarismos dos produtos (pesos 1 e 2) com um zero após o município
+fn mg(d: &[u32]) -> bool {
+    let base: Vec<u32> = [&d[..3], &[0], &d[3..11]].concat();
+    let soma: u32 = base
+        .iter()
+        .enumerate()
+        .map(|(i, d)| d * (1 + (i % 2) as u32))
+        .map(|produto| produto / 10 + produto % 10)
+        .sum();
+    let dv1 = (10 - soma % 10) % 10;
+    d[11] == dv1 && d[12] == dv(&d[..12], &[3, 2, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2])
+}
+
+/// PE: e-Fisco (9 dígitos, dois DVs) ou CACEPE antigo (14 dígitos)
+fn pe(d: &[u32]) -> bool {
+    match d.len() {
+        9 => d[7] == dv(&d[..7], &PESOS_9[1..]) && d[8] == dv(&d[..8], &PESOS_9),
+        14 => {
+            let pesos = [5, 4, 3, 2, 1, 9, 8, 7, 6, 5, 4, 3, 2];
+            let dv = 11 - soma(&d[..13], &pesos) % 11;
+            d[13] == if dv > 9 { dv - 10 } else { dv }
+        }
+        _ => false,
+    }
+}
+
+fn rn(d: &[u32]) -> bool {
+    let n = d.len();
+    let pesos: Vec<u32> = (2..=n as u32).rev().collect();
+    d[n - 1] == soma(&d[..n - 1], &pesos) * 10 % 11 % 10
+}
+
+/// RO: 14 dígitos desde 2000; o formato anterior tinha 3 dígitos de município, 5 da empresa e o DV
+fn ro(d: &[u32]) -> bool {
+    let (base, pesos): (&[u32], &[u32]) = match d.len() {
+        9 => (&d[3..8], &[6, 5, 4, 3, 2]),
+        14 => (&d[..13], &[6, 5, 4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2]),
+        _ => return false,
+    };
+    let dv = 11 - soma(base, pesos) % 11;
+    d[d.len() - 1] == if dv > 9 { dv - 10 } else { dv }
+}
+
+fn rr(d: &[u32]) -> bool {
+    d[8] == soma(&d[..8], &[1, 2, 3, 4, 5, 6, 7, 8]) % 9
+}
+
+/// SP: DV na 9ª e na 12ª posições
+fn sp(d: &[u32]) -> bool {
+    d[8] == sp_dv1(d) && d[11] == soma(&d[..11], &[3, 2, 10, 9, 8, 7, 6, 5, 4, 3, 2]) % 11 % 10
+}
+
+/// SP, produtor rural: `P` seguido de 12 dígitos, com DV na 9ª posição
+fn sp_produtor_rural(d: &[u32]) -> bool {
+    d.len() == 12 && d[8] == sp_dv1(d)
+}
+
+fn sp_dv1(d: &[u32]) -> u32 {
+    soma(&d[..8], &[1, 3, 4, 5, 6, 7, 8, 10]) % 11 % 10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ie_exemplos() {
+        let exemplos = [
+            ("AC", "01.004.823/001-12"),
+            ("AL", "240000048"),
+            ("AP", "030123459"),
+            ("AM", "04.293.368-4"),
+            ("BA", "123456-63"),
+            ("BA", "1000003-06"),
+            ("CE", "06000001-5"),
+            ("DF", "07300001001-09"),
+            ("ES", "99999999-0"),
+            ("GO", "10.987.654-7"),
+            ("MA", "12000038-5"),
+            ("MT", "0013000001-9"),
+            ("MS", "28325360-6"),
+            ("MG", "062.307.904/0081"),
+            ("PA", "15-999999-5"),
+            ("PB", "06000001-5"),
+            ("PR", "123.45678-50"),
+            ("PE", "0321418-40"),
+            ("PE", "18.1.001.0000004-9"),
+            ("PI", "19.301.656-7"),
+            ("RJ", "99.999.99-3"),
+            ("RN", "20.040.040-1"),
+            ("RN", "20.0.040.040-0"),
+            ("RS", "224/3658792"),
+            ("RO", "101.62521-3"),
+            ("RO", "0000000062521-3"),
+            ("RR", "24006628-1"),
+            ("SC", "251.040.852"),
+            ("SP", "110.042.490.114"),
+            ("SE", "27123456-3"),
+            ("TO", "29.022.783-6"),
+            ("TO", "29.01.022783-6"),
+        ];
+        for (uf, ie) in exemplos {
+            assert!(validate_ie(ie, uf), "{} {}", uf, ie);
+
+            // Alterar o último dígito invalida a inscrição
+            let ultimo = ie.chars().last().unwrap().to_digit(10).unwrap();
+            let alterada = format!("{}{}", &ie[..ie.len() - 1], (ultimo + 1) % 10);
+            assert!(!validate_ie(&alterada, uf), "{} {}", uf, alterada);
+        }
+    }
+
+    #[test]
+    fn test_validate_ie_formatos() {
+        assert!(validate_ie("ISENTO", "RJ"));
+        assert!(validate_ie("isento", "SP"));
+        assert!(!validate_ie("", "SP"));
+        assert!(!validate_ie("110042490114", "XX"));
+        // IE de outra UF ou com tamanho errado
+        assert!(!validate_ie("110042490114", "RJ"));
+        assert!(!validate_ie("11004249011", "SP"));
+        assert!(validate_ie("P-01100424.3/002", "SP"));
+        assert!(!validate_ie("P-01100425.3/002", "SP"));
+        assert!(!validate_ie("P-01100424.3", "SP"));
+        assert!(!validate_ie("P-011004243002", "RJ"));
+    }
+}
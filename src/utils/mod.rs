@@ -1,5 +1,8 @@
-/// Utilitários gerais para o sistema
-use crate::error::{GeolocationError, Result};
+//! Utilitários gerais para o sistema
+
+mod inscricao_estadual;
+
+pub use inscricao_estadual::validate_ie;
 
 /// Valida um CNPJ
 pub fn validate_cnpj(cnpj: &str) -> bool {
@@ -6,8 +6,18 @@
 /// - Cálculo de ICMS, PIS, COFINS
 /// - Detecção de divergências fiscais
 /// - Sugestões de correção
+use crate::models::{
+    ChaveAcesso, ConhecimentoTransporte, DocumentType, IdentificacaoEmissao, ItemNota,
+    ManifestoDocumentos, ModeloNotaFiscal, NotaFiscal, NotaFiscalServico, Participante,
+    ProtocoloAutorizacao, TipoNota, TipoTomador,
+};
+use crate::utils::validate_ie;
+use crate::validators::cfop::{Abrangencia, Cfop};
+use crate::validators::icms;
+use crate::validators::ncm::TabelaNcm;
+use crate::validators::totais;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
@@ -17,7 +27,7 @@ pub struct ValidationResult {
     pub errors: Vec<ValidationError>,
     pub warnings: Vec<ValidationWarning>,
     pub suggestions: Vec<String>,
-    pub validated_at: chrono::DateTime<chrono::Utc>,
+    pub validated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,240 +57,753 @@ pub enum ErrorSeverity {
 /// Validador principal de documentos fiscais
 pub struct FiscalValidator;
 
-impl FiscalValidator {
-    /// Valida um documento fiscal completo
-    pub fn validate_document(xml_content: &str, document_type: &str) -> ValidationResult {
-        // Extrair chave de acesso do XML
-        let chave_acesso = Self::extract_chave_acesso(xml_content).unwrap_or_default();
-        
-        let mut result = ValidationResult {
-            chave_acesso,
-            document_type: document_type.to_string(),
+/// Atraso entre emissão e autorização a partir do qual a emissão é tida como retroativa
+const DIAS_RETROATIVO: i64 = 5;
+
+/// Folga para diferença de relógio entre emitente e SEFAZ
+const MINUTOS_RELOGIO: i64 = 5;
+
+impl ValidationResult {
+    fn new(chave_acesso: &str, document_type: &DocumentType) -> Self {
+        ValidationResult {
+            chave_acesso: chave_acesso.to_string(),
+            document_type: document_type.codigo().to_string(),
             is_valid: true,
             errors: Vec::new(),
             warnings: Vec::new(),
             suggestions: Vec::new(),
-            validated_at: chrono::Utc::now(),
+            validated_at: Utc::now(),
+        }
+    }
+
+    fn finalizar(mut self) -> Self {
+        self.is_valid = self.errors.is_empty();
+        self
+    }
+}
+
+impl FiscalValidator {
+    /// Interpreta o XML e valida conforme o tipo (`NFe`, `NFCe`, `CTe`, `CTeOS`, `CTeSimp`,
+    /// `MDFe` ou `NFSe`); XML que não pode ser interpretado vira erro `XML_PARSE_ERROR`
+    #[deprecated(
+        note = "interprete o XML e use validate_nota_fiscal, validate_conhecimento, validate_manifesto ou validate_nota_servico"
+    )]
+    pub fn validate_document(xml_content: &str, document_type: &str) -> ValidationResult {
+        use crate::parsers::{
+            cte::CTeParser, mdfe::MDFeParser, nfe::NFeParser, nfse::NFSeParser,
+            FiscalDocumentParser,
         };
 
-        match document_type {
-            "NFe" => Self::validate_nfe(xml_content, &mut result),
-            "CTe" => Self::validate_cte(xml_content, &mut result),
-            _ => {
-                result.is_valid = false;
-                result.errors.push(ValidationError {
-                    code: "DOC_TYPE_INVALID".to_string(),
+        let validado = match document_type {
+            "NFe" | "NFCe" => NFeParser::new()
+                .parse_string(xml_content)
+                .map(|nota| Self::validate_nota_fiscal(&nota)),
+            "CTe" | "CTeOS" | "CTeSimp" => CTeParser::new()
+                .parse_string(xml_content)
+                .map(|cte| Self::validate_conhecimento(&cte)),
+            "MDFe" => MDFeParser::new()
+                .parse_string(xml_content)
+                .map(|mdfe| Self::validate_manifesto(&mdfe)),
+            "NFSe" => NFSeParser::new()
+                .parse_string(xml_content)
+                .map(|nfse| Self::validate_nota_servico(&nfse)),
+            _ => Err(crate::error::GeolocationError::UnsupportedDocumentType(
+                document_type.to_string(),
+            )),
+        };
+
+        validado.unwrap_or_else(|e| {
+            let code = match e {
+                crate::error::GeolocationError::UnsupportedDocumentType(_) => "DOC_TYPE_INVALID",
+                _ => "XML_PARSE_ERROR",
+            };
+            ValidationResult {
+                chave_acesso: crate::parsers::extract_access_key(xml_content).unwrap_or_default(),
+                document_type: document_type.to_string(),
+                is_valid: false,
+                errors: vec![ValidationError {
+                    code: code.to_string(),
                     field: "document_type".to_string(),
-                    message: "Tipo de documento não suportado".to_string(),
+                    message: e.to_string(),
                     severity: ErrorSeverity::Critical,
-                });
+                }],
+                warnings: Vec::new(),
+                suggestions: Vec::new(),
+                validated_at: Utc::now(),
             }
+        })
+    }
+
+    /// Valida NF-e ou NFC-e já interpretada, item a item
+    pub fn validate_nota_fiscal(nota: &NotaFiscal) -> ValidationResult {
+        let mut result = ValidationResult::new(&nota.chave_acesso, &nota.document_type());
+        let tolerancia = totais::tolerancia_padrao();
+
+        for item in &nota.itens {
+            let det = format!("det[{}]", item.numero_item);
+            Self::validate_cfop(&det, &item.cfop, nota, &mut result);
+            Self::validate_ncm(
+                &det,
+                item,
+                nota.data_emissao,
+                TabelaNcm::padrao(),
+                &mut result,
+            );
+            Self::validate_icms_item(&det, item, nota, tolerancia, &mut result);
+        }
+        Self::validate_totais(nota, tolerancia, &mut result);
+        let mut participantes = vec![("emit", &nota.emitente), ("dest", &nota.destinatario)];
+        if let Some(transportadora) = &nota.transporte.transportadora {
+            participantes.push(("transporta", transportadora));
         }
+        Self::validate_inscricoes(&participantes, &mut result);
+
+        Self::validate_access_key(&nota.chave_acesso, &mut result);
+        Self::validate_key_header(
+            &nota.chave_acesso,
+            &CabecalhoChave::from_nota(nota),
+            &mut result,
+        );
+        Self::validate_dates(nota.data_emissao, nota.protocolo.as_ref(), &mut result);
 
-        result.is_valid = result.errors.is_empty();
-        result
+        result.finalizar()
     }
 
-    /// Valida NF-e específica
-    fn validate_nfe(xml_content: &str, result: &mut ValidationResult) {
-        // Validação 1: CFOP
-        Self::validate_cfop(xml_content, result);
+    /// Valida CT-e, CT-e OS ou CT-e Simplificado já interpretado
+    pub fn validate_conhecimento(cte: &ConhecimentoTransporte) -> ValidationResult {
+        let mut result = ValidationResult::new(&cte.chave_acesso, &cte.document_type());
 
-        // Validação 2: NCM
-        Self::validate_ncm(xml_content, result);
+        if let Some(icms) = &cte.impostos.icms {
+            Self::validate_icms(
+                "imp.ICMS",
+                icms.base_calculo,
+                icms.aliquota,
+                icms.valor,
+                totais::tolerancia_padrao(),
+                &mut result,
+            );
+        }
+        let mut participantes = vec![
+            ("emit", &cte.emitente),
+            ("rem", &cte.remetente),
+            ("dest", &cte.destinatario),
+        ];
+        participantes.extend(cte.expedidor.as_ref().map(|p| ("exped", p)));
+        participantes.extend(cte.recebedor.as_ref().map(|p| ("receb", p)));
+        // Nos demais papéis o tomador repete um participante já conferido
+        if let Some(tomador) = cte
+            .tomador
+            .as_ref()
+            .filter(|t| t.tipo == TipoTomador::Outros)
+        {
+            participantes.push(("toma", &tomador.participante));
+        }
+        Self::validate_inscricoes(&participantes, &mut result);
+        Self::validate_access_key(&cte.chave_acesso, &mut result);
+        Self::validate_key_header(
+            &cte.chave_acesso,
+            &CabecalhoChave::from_conhecimento(cte),
+            &mut result,
+        );
+        Self::validate_dates(cte.data_emissao, cte.protocolo.as_ref(), &mut result);
 
-        // Validação 3: Cálculo de impostos
-        Self::validate_tax_calculation(xml_content, result);
+        result.finalizar()
+    }
+
+    /// Valida MDF-e já interpretado
+    pub fn validate_manifesto(mdfe: &ManifestoDocumentos) -> ValidationResult {
+        let mut result =
+            ValidationResult::new(&mdfe.chave_acesso, &DocumentType::ManifestoDocumentos);
 
-        // Validação 4: Chave de acesso
-        Self::validate_access_key(xml_content, result);
+        Self::validate_access_key(&mdfe.chave_acesso, &mut result);
+        Self::validate_key_header(
+            &mdfe.chave_acesso,
+            &CabecalhoChave::from_manifesto(mdfe),
+            &mut result,
+        );
+        Self::validate_dates(mdfe.data_emissao, mdfe.protocolo.as_ref(), &mut result);
 
-        // Validação 5: Datas
-        Self::validate_dates(xml_content, result);
+        result.finalizar()
     }
 
-    /// Valida CT-e específico
-    fn validate_cte(xml_content: &str, result: &mut ValidationResult) {
-        // Validação de CT-e (simplificada)
-        Self::validate_access_key(xml_content, result);
-        Self::validate_dates(xml_content, result);
+    /// Valida NFS-e, que não tem chave de acesso de 44 dígitos nem protocolo
+    pub fn validate_nota_servico(nfse: &NotaFiscalServico) -> ValidationResult {
+        let mut result =
+            ValidationResult::new(&nfse.chave_acesso, &DocumentType::NotaFiscalServico);
+
+        Self::validate_dates(nfse.data_emissao, None, &mut result);
+
+        result.finalizar()
     }
 
-    /// Valida CFOP contra tabela oficial
-    fn validate_cfop(xml_content: &str, result: &mut ValidationResult) {
-        // Extrai CFOP do XML (simulação)
-        let cfop = Self::extract_cfop(xml_content);
+    /// Valida o CFOP do item contra a tabela oficial, o `tpNF` e as UFs de emitente e destinatário
+    fn validate_cfop(det: &str, codigo: &str, nota: &NotaFiscal, result: &mut ValidationResult) {
+        let campo = format!("{}.CFOP", det);
+        let Some(cfop) = Cfop::buscar(codigo) else {
+            result.errors.push(ValidationError {
+                code: "CFOP_INVALID".to_string(),
+                field: campo,
+                message: format!("CFOP {} não existe na tabela oficial", codigo),
+                severity: ErrorSeverity::High,
+            });
+            result
+                .suggestions
+                .push("Verifique a tabela de CFOPs da Receita Federal".to_string());
+            return;
+        };
 
-        if let Some(cfop_code) = cfop {
-            // Valida se CFOP existe
-            if !Self::is_valid_cfop(&cfop_code) {
-                result.errors.push(ValidationError {
-                    code: "CFOP_INVALID".to_string(),
-                    field: "CFOP".to_string(),
-                    message: format!("CFOP {} não existe na tabela oficial", cfop_code),
-                    severity: ErrorSeverity::High,
-                });
-                result
-                    .suggestions
-                    .push("Verifique a tabela de CFOPs da Receita Federal".to_string());
-            }
+        // Rejeições 518/519: CFOP de entrada em nota de saída e vice-versa
+        if cfop.tipo_nota() != nota.tipo_nota {
+            result.errors.push(ValidationError {
+                code: "CFOP_DIRECTION".to_string(),
+                field: campo.clone(),
+                message: format!(
+                    "CFOP {} é de {}, mas a nota é de {} (tpNF = {})",
+                    codigo,
+                    Self::sentido(&cfop.tipo_nota()),
+                    Self::sentido(&nota.tipo_nota),
+                    nota.tipo_nota.codigo()
+                ),
+                severity: ErrorSeverity::High,
+            });
+        }
 
-            // Valida compatibilidade (exemplo: 5xxx para operações internas)
-            if cfop_code.starts_with('5') {
-                // Verificar se UF origem == UF destino
-                result.warnings.push(ValidationWarning {
-                    code: "CFOP_CHECK_UF".to_string(),
-                    field: "CFOP".to_string(),
-                    message: format!(
-                        "CFOP {} é para operações internas - verifique UFs",
-                        cfop_code
-                    ),
-                    impact: "Pode gerar multa se UFs forem diferentes".to_string(),
-                });
-            }
+        // Venda presencial (e toda NFC-e) é interna mesmo com consumidor de outra UF
+        if nota.modelo == ModeloNotaFiscal::NFCe || nota.presencial {
+            return;
+        }
+        // O destinatário é a outra parte também nas notas de entrada emitidas pelo adquirente
+        let uf_emitente = nota.emitente.endereco.uf.as_str();
+        let uf_destinatario = nota.destinatario.endereco.uf.as_str();
+        if uf_emitente.is_empty() || uf_destinatario.is_empty() {
+            return;
+        }
+        let exterior = uf_destinatario == "EX";
+        let coerente = match cfop.abrangencia() {
+            Abrangencia::Estadual => uf_emitente == uf_destinatario,
+            Abrangencia::Interestadual => uf_emitente != uf_destinatario && !exterior,
+            Abrangencia::Exterior => exterior,
+        };
+        if !coerente {
+            result.warnings.push(ValidationWarning {
+                code: "CFOP_CHECK_UF".to_string(),
+                field: campo,
+                message: format!(
+                    "CFOP {} ({}) incompatível com operação entre {} e {}",
+                    codigo, cfop.descricao, uf_emitente, uf_destinatario
+                ),
+                impact: "Pode gerar multa e recolhimento de ICMS à UF errada".to_string(),
+            });
         }
     }
 
-    /// Valida NCM
-    fn validate_ncm(xml_content: &str, result: &mut ValidationResult) {
-        let ncm = Self::extract_ncm(xml_content);
+    /// Valida o NCM do item contra a NCM/TIPI: existência, vigência e alíquota de IPI
+    fn validate_ncm(
+        det: &str,
+        item: &ItemNota,
+        data_emissao: DateTime<Utc>,
+        tabela: &TabelaNcm,
+        result: &mut ValidationResult,
+    ) {
+        let campo = format!("{}.NCM", det);
+        let ncm = item.ncm.as_str();
 
-        if let Some(ncm_code) = ncm {
-            // Valida formato (8 dígitos)
-            if ncm_code.len() != 8 || !ncm_code.chars().all(char::is_numeric) {
+        // Serviços e itens sem classificação informam "00"
+        if ncm == "00" {
+            return;
+        }
+        // Valida formato (8 dígitos)
+        if ncm.len() != 8 || !ncm.chars().all(|c| c.is_ascii_digit()) {
+            result.errors.push(ValidationError {
+                code: "NCM_INVALID_FORMAT".to_string(),
+                field: campo,
+                message: format!("NCM {} deve ter 8 dígitos numéricos", ncm),
+                severity: ErrorSeverity::High,
+            });
+            return;
+        }
+
+        let Some(entrada) = tabela.buscar(ncm) else {
+            // Fora de um extrato não dá para afirmar que o código não existe
+            if tabela.completa {
                 result.errors.push(ValidationError {
-                    code: "NCM_INVALID_FORMAT".to_string(),
-                    field: "NCM".to_string(),
-                    message: format!("NCM {} deve ter 8 dígitos numéricos", ncm_code),
+                    code: "NCM_NOT_FOUND".to_string(),
+                    field: campo,
+                    message: format!("NCM {} não existe na TIPI {}", ncm, tabela.versao),
                     severity: ErrorSeverity::High,
                 });
             }
+            return;
+        };
+
+        // Vigência avaliada na data de Brasília
+        let data = (data_emissao - Duration::hours(3)).date_naive();
+        if !entrada.vigente_em(data) {
+            let (code, situacao) = match entrada.fim_vigencia {
+                Some(fim) if data > fim => (
+                    "NCM_EXPIRED",
+                    format!("extinto em {}", fim.format("%d/%m/%Y")),
+                ),
+                _ => (
+                    "NCM_NOT_IN_FORCE",
+                    format!(
+                        "vigente apenas a partir de {}",
+                        entrada
+                            .inicio_vigencia
+                            .unwrap_or_default()
+                            .format("%d/%m/%Y")
+                    ),
+                ),
+            };
+            result.errors.push(ValidationError {
+                code: code.to_string(),
+                field: campo,
+                message: format!(
+                    "NCM {} {}, anterior à emissão em {}",
+                    ncm,
+                    situacao,
+                    data.format("%d/%m/%Y")
+                ),
+                severity: ErrorSeverity::High,
+            });
+            return;
+        }
 
-            // Avisa sobre necessidade de alíquota IPI
-            if Self::ncm_requires_ipi(&ncm_code) {
+        let aliquota_tipi = entrada.aliquota_ipi.unwrap_or_default();
+        match &item.impostos.ipi {
+            // Avisa sobre necessidade de IPI quando o item não traz o grupo
+            None if aliquota_tipi > 0.0 => {
                 result.warnings.push(ValidationWarning {
                     code: "NCM_REQUIRES_IPI".to_string(),
-                    field: "NCM".to_string(),
-                    message: format!("NCM {} pode exigir IPI - verifique alíquota", ncm_code),
+                    field: campo,
+                    message: format!(
+                        "NCM {} tem IPI de {}% na TIPI - verifique alíquota",
+                        ncm, aliquota_tipi
+                    ),
                     impact: "Falta de IPI pode gerar autuação".to_string(),
                 });
             }
+            // CST 00/50: entrada/saída tributada; 49 e 99 ("outras") admitem qualquer alíquota
+            Some(ipi)
+                if matches!(ipi.cst.as_str(), "00" | "50")
+                    && ipi.aliquota > 0.0
+                    && (ipi.aliquota - aliquota_tipi).abs() > 0.001 =>
+            {
+                result.warnings.push(ValidationWarning {
+                    code: "IPI_RATE_MISMATCH".to_string(),
+                    field: format!("{}.IPI", det),
+                    message: format!(
+                        "Alíquota de IPI {}% difere da TIPI ({}%) para o NCM {}",
+                        ipi.aliquota, aliquota_tipi, ncm
+                    ),
+                    impact: "Diferença de IPI só se justifica por Ex-tarifário ou benefício fiscal"
+                        .to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Recalcula o ICMS do item e confere a alíquota interestadual
+    fn validate_icms_item(
+        det: &str,
+        item: &ItemNota,
+        nota: &NotaFiscal,
+        tolerancia: f64,
+        result: &mut ValidationResult,
+    ) {
+        for divergencia in icms::recalcular(item, tolerancia) {
+            result.errors.push(ValidationError {
+                code: divergencia.codigo().to_string(),
+                field: format!("{}.ICMS.{}", det, divergencia.campo),
+                message: format!(
+                    "{} informado (R$ {:.2}) difere do recalculado (R$ {:.2})",
+                    divergencia.campo, divergencia.informado, divergencia.esperado
+                ),
+                severity: ErrorSeverity::Medium,
+            });
+            result.suggestions.push(format!(
+                "Item {}: {} esperado R$ {:.2}",
+                item.numero_item, divergencia.campo, divergencia.esperado
+            ));
+        }
+
+        // Venda presencial na NFC-e usa a alíquota interna mesmo com destinatário de outra UF
+        let Some(icms) = &item.impostos.icms else {
+            return;
+        };
+        let uf_origem = nota.emitente.endereco.uf.as_str();
+        let uf_destino = nota.destinatario.endereco.uf.as_str();
+        let interestadual = nota.modelo == ModeloNotaFiscal::NFe
+            && !uf_origem.is_empty()
+            && !uf_destino.is_empty()
+            && uf_destino != "EX"
+            && uf_origem != uf_destino;
+        let tributada = matches!(icms.cst.as_deref(), Some("00" | "10" | "20" | "70" | "90"));
+        if !interestadual || !tributada || icms.aliquota <= 0.0 {
+            return;
+        }
+        let esperada = icms::aliquota_interestadual(uf_origem, uf_destino, &icms.origem);
+        if (icms.aliquota - esperada).abs() > 0.001 {
+            result.warnings.push(ValidationWarning {
+                code: "ICMS_INTERSTATE_RATE".to_string(),
+                field: format!("{}.ICMS.pICMS", det),
+                message: format!(
+                    "Alíquota de ICMS {}% difere da interestadual de {}% ({} para {}, origem {})",
+                    icms.aliquota, esperada, uf_origem, uf_destino, icms.origem
+                ),
+                impact: "Destaque a maior gera crédito indevido; a menor, diferença de imposto"
+                    .to_string(),
+            });
         }
     }
 
-    /// Valida cálculo de impostos
-    fn validate_tax_calculation(xml_content: &str, result: &mut ValidationResult) {
-        // Extrai valores (simulação)
-        let valor_produtos: f64 = 10000.0;
-        let valor_icms: f64 = 1800.0;
-        let aliquota_icms: f64 = 18.0;
+    /// Confere o `ICMSTot` contra a soma dos itens e o vNF contra a fórmula da rejeição 610
+    fn validate_totais(nota: &NotaFiscal, tolerancia: f64, result: &mut ValidationResult) {
+        for divergencia in totais::conferir(nota, tolerancia) {
+            let message = if divergencia.campo == "vNF" {
+                format!(
+                    "vNF informado (R$ {:.2}) difere do calculado pelos totais (R$ {:.2}); \
+                     SEFAZ rejeita com código 610",
+                    divergencia.informado, divergencia.esperado
+                )
+            } else {
+                format!(
+                    "{} informado (R$ {:.2}) difere da soma dos itens (R$ {:.2})",
+                    divergencia.campo, divergencia.informado, divergencia.esperado
+                )
+            };
+            result.errors.push(ValidationError {
+                code: divergencia.codigo().to_string(),
+                field: format!("ICMSTot.{}", divergencia.campo),
+                message,
+                severity: ErrorSeverity::High,
+            });
+            result.suggestions.push(format!(
+                "Totais: {} esperado R$ {:.2}",
+                divergencia.campo, divergencia.esperado
+            ));
+        }
+    }
 
-        // Verifica cálculo de ICMS
-        let icms_esperado: f64 = valor_produtos * (aliquota_icms / 100.0);
-        let diferenca: f64 = (valor_icms - icms_esperado).abs();
+    /// Confere a IE de cada participante pelo algoritmo da UF do seu endereço
+    fn validate_inscricoes(participantes: &[(&str, &Participante)], result: &mut ValidationResult) {
+        for (grupo, participante) in participantes {
+            let Some(ie) = participante
+                .inscricao_estadual
+                .as_deref()
+                .filter(|ie| !ie.trim().is_empty())
+            else {
+                continue;
+            };
+            // Estrangeiros não têm IE brasileira
+            let uf = participante.endereco.uf.as_str();
+            if uf.is_empty() || uf == "EX" || validate_ie(ie, uf) {
+                continue;
+            }
+            result.errors.push(ValidationError {
+                code: "IE_INVALID".to_string(),
+                field: format!("{}.IE", grupo),
+                message: format!("Inscrição Estadual {} inválida para {}", ie, uf),
+                severity: ErrorSeverity::High,
+            });
+            result.suggestions.push(format!(
+                "Confira a IE de {} no Sintegra/CCC da UF {} ou informe ISENTO",
+                participante.razao_social, uf
+            ));
+        }
+    }
 
-        if diferenca > 0.01 {
+    /// Confere vICMS contra vBC × pICMS
+    fn validate_icms(
+        campo: &str,
+        base_calculo: f64,
+        aliquota: f64,
+        valor_icms: f64,
+        tolerancia: f64,
+        result: &mut ValidationResult,
+    ) {
+        // Isentos, não tributados e Simples Nacional sem crédito não trazem base e alíquota
+        if base_calculo <= 0.0 || aliquota <= 0.0 {
+            return;
+        }
+        let icms_esperado = (base_calculo * aliquota).round() / 100.0;
+        if (valor_icms - icms_esperado).abs() > tolerancia {
             result.errors.push(ValidationError {
                 code: "ICMS_CALC_ERROR".to_string(),
-                field: "ICMS".to_string(),
+                field: campo.to_string(),
                 message: format!(
-                    "ICMS calculado (R$ {:.2}) difere do esperado (R$ {:.2})",
+                    "ICMS informado (R$ {:.2}) difere do esperado (R$ {:.2})",
                     valor_icms, icms_esperado
                 ),
                 severity: ErrorSeverity::Medium,
             });
             result.suggestions.push(format!(
-                "Recalcule: {} × {}% = R$ {:.2}",
-                valor_produtos, aliquota_icms, icms_esperado
+                "Recalcule: {:.2} × {}% = R$ {:.2}",
+                base_calculo, aliquota, icms_esperado
             ));
         }
     }
 
     /// Valida chave de acesso
-    fn validate_access_key(xml_content: &str, result: &mut ValidationResult) {
-        let chave = Self::extract_access_key(xml_content);
+    fn validate_access_key(chave: &str, result: &mut ValidationResult) {
+        // Valida formato (44 dígitos)
+        if chave.len() != 44 || !chave.chars().all(|c| c.is_ascii_digit()) {
+            result.errors.push(ValidationError {
+                code: "KEY_INVALID_FORMAT".to_string(),
+                field: "chave_acesso".to_string(),
+                message: "Chave de acesso deve ter 44 dígitos numéricos".to_string(),
+                severity: ErrorSeverity::Critical,
+            });
+        } else if !Self::validate_key_digit(chave) {
+            // Valida dígito verificador (módulo 11)
+            let calculado = ChaveAcesso::calcular_digito(&chave[..43]).unwrap_or_default();
+            result.errors.push(ValidationError {
+                code: "KEY_INVALID_DIGIT".to_string(),
+                field: "chave_acesso".to_string(),
+                message: format!(
+                    "Dígito verificador da chave de acesso inválido (calculado: {})",
+                    calculado
+                ),
+                severity: ErrorSeverity::Critical,
+            });
+        }
+    }
 
-        if let Some(key) = chave {
-            // Valida formato (44 dígitos)
-            if key.len() != 44 || !key.chars().all(char::is_numeric) {
-                result.errors.push(ValidationError {
-                    code: "KEY_INVALID_FORMAT".to_string(),
-                    field: "chave_acesso".to_string(),
-                    message: "Chave de acesso deve ter 44 dígitos numéricos".to_string(),
-                    severity: ErrorSeverity::Critical,
-                });
-            }
+    /// Confere os campos da chave de acesso com o cabeçalho (`ide`/`emit`) do documento.
+    /// Divergência entre os dois é sinal típico de XML adulterado.
+    fn validate_key_header(chave: &str, cabecalho: &CabecalhoChave, result: &mut ValidationResult) {
+        // Chave com formato ou DV inválido já foi reportada por `validate_access_key`
+        let Ok(chave) = ChaveAcesso::parse(chave) else {
+            return;
+        };
+
+        let ano_mes = cabecalho.data_emissao.map(|data| {
+            // A chave usa o mês no fuso do emitente (UTC-2 a UTC-5); basta um deles coincidir
+            let candidatos = [-3, -2, -4, -5]
+                .map(|horas| (data + Duration::hours(horas)).format("%y%m").to_string());
+            candidatos
+                .iter()
+                .find(|aamm| **aamm == chave.ano_mes)
+                .unwrap_or(&candidatos[0])
+                .clone()
+        });
+        let cnpj_cpf = cabecalho
+            .cnpj_cpf
+            .as_deref()
+            .map(|doc| format!("{:0>14}", doc));
+        let codigo_numerico = cabecalho
+            .codigo_numerico
+            .as_deref()
+            .map(|c| format!("{:0>8}", c));
+        let numero = |valor: &Option<String>| valor.as_deref().and_then(|v| v.parse::<u32>().ok());
 
-            // Valida dígito verificador
-            if !Self::validate_key_digit(&key) {
+        let comparacoes = [
+            ("UF", "uf", "cUF", chave.uf.clone(), cabecalho.uf.clone()),
+            (
+                "DATA",
+                "data_emissao",
+                "AAMM de dhEmi",
+                chave.ano_mes.clone(),
+                ano_mes,
+            ),
+            (
+                "CNPJ",
+                "emitente",
+                "CNPJ/CPF do emitente",
+                chave.cnpj_cpf.clone(),
+                cnpj_cpf,
+            ),
+            (
+                "MODELO",
+                "modelo",
+                "mod",
+                chave.modelo.clone(),
+                cabecalho.modelo.clone(),
+            ),
+            (
+                "SERIE",
+                "serie",
+                "serie",
+                chave.serie.to_string(),
+                numero(&cabecalho.serie).map(|n| n.to_string()),
+            ),
+            (
+                "NUMERO",
+                "numero",
+                "nNF/nCT/nMDF",
+                chave.numero.to_string(),
+                numero(&cabecalho.numero).map(|n| n.to_string()),
+            ),
+            (
+                "TPEMIS",
+                "tipo_emissao",
+                "tpEmis",
+                chave.tipo_emissao.to_string(),
+                cabecalho.tipo_emissao.clone(),
+            ),
+            (
+                "CODIGO",
+                "codigo_numerico",
+                "cNF/cCT/cMDF",
+                chave.codigo_numerico.clone(),
+                codigo_numerico,
+            ),
+        ];
+
+        for (codigo, campo, rotulo, na_chave, no_documento) in comparacoes {
+            // Campo ausente no XML não tem com o que comparar
+            let Some(no_documento) = no_documento else {
+                continue;
+            };
+            if na_chave != no_documento {
                 result.errors.push(ValidationError {
-                    code: "KEY_INVALID_DIGIT".to_string(),
-                    field: "chave_acesso".to_string(),
-                    message: "Dígito verificador da chave de acesso inválido".to_string(),
+                    code: format!("KEY_MISMATCH_{}", codigo),
+                    field: campo.to_string(),
+                    message: format!(
+                        "Chave de acesso informa {} = {}, mas o documento traz {}",
+                        rotulo, na_chave, no_documento
+                    ),
                     severity: ErrorSeverity::Critical,
                 });
             }
         }
     }
 
-    /// Valida datas
-    fn validate_dates(_xml_content: &str, result: &mut ValidationResult) {
-        // Validação de datas (emissão, saída, etc)
-        // Simulação: detecta data retroativa
-        result.warnings.push(ValidationWarning {
-            code: "DATE_RETROACTIVE".to_string(),
-            field: "data_emissao".to_string(),
-            message: "Data de emissão está retroativa (mais de 5 dias)".to_string(),
-            impact: "Pode indicar manipulação fiscal".to_string(),
-        });
-    }
-
-    // === Funções auxiliares (simuladas) ===
+    /// Valida a data de emissão contra o relógio e o recebimento pela SEFAZ
+    fn validate_dates(
+        data_emissao: DateTime<Utc>,
+        protocolo: Option<&ProtocoloAutorizacao>,
+        result: &mut ValidationResult,
+    ) {
+        let folga = Duration::minutes(MINUTOS_RELOGIO);
+        if data_emissao > Utc::now() + folga {
+            result.errors.push(ValidationError {
+                code: "DATE_FUTURE".to_string(),
+                field: "data_emissao".to_string(),
+                message: format!(
+                    "Data de emissão {} está no futuro",
+                    data_emissao.to_rfc3339()
+                ),
+                severity: ErrorSeverity::High,
+            });
+        }
 
-    fn extract_cfop(xml: &str) -> Option<String> {
-        // Em produção: parse real do XML
-        if xml.contains("5102") {
-            Some("5102".to_string())
-        } else {
-            None
+        let Some(recebimento) = protocolo.and_then(|p| p.data_recebimento) else {
+            return;
+        };
+        if data_emissao > recebimento + folga {
+            result.errors.push(ValidationError {
+                code: "DATE_AFTER_RECEIPT".to_string(),
+                field: "data_emissao".to_string(),
+                message: format!(
+                    "Data de emissão {} posterior ao recebimento pela SEFAZ ({})",
+                    data_emissao.to_rfc3339(),
+                    recebimento.to_rfc3339()
+                ),
+                severity: ErrorSeverity::High,
+            });
+        } else if recebimento - data_emissao > Duration::days(DIAS_RETROATIVO) {
+            result.warnings.push(ValidationWarning {
+                code: "DATE_RETROACTIVE".to_string(),
+                field: "data_emissao".to_string(),
+                message: format!(
+                    "Data de emissão está retroativa ({} dias antes da autorização)",
+                    (recebimento - data_emissao).num_days()
+                ),
+                impact: "Pode indicar manipulação fiscal".to_string(),
+            });
         }
     }
 
-    fn extract_ncm(xml: &str) -> Option<String> {
-        // Em produção: parse real do XML
-        if xml.contains("NCM") {
-            Some("84331900".to_string())
-        } else {
-            None
+    // === Funções auxiliares ===
+
+    fn sentido(tipo: &TipoNota) -> &'static str {
+        match tipo {
+            TipoNota::Entrada => "entrada",
+            TipoNota::Saida => "saída",
         }
     }
 
-    fn extract_access_key(_xml: &str) -> Option<String> {
-        // Em produção: parse real do XML
-        Some("35240911223344000156550010000123451234567890".to_string())
+    fn validate_key_digit(key: &str) -> bool {
+        ChaveAcesso::valida(key)
     }
+}
 
-    fn is_valid_cfop(cfop: &str) -> bool {
-        // Lista simplificada de CFOPs válidos
-        matches!(
-            cfop,
-            "5102" | "5103" | "5104" | "5405" | "5949" | "6102" | "6103" | "6104" | "6405" | "6949"
-        )
+/// Campos do cabeçalho que também compõem a chave de acesso
+#[derive(Debug, Clone, Default)]
+pub struct CabecalhoChave {
+    pub uf: Option<String>,
+    pub data_emissao: Option<DateTime<Utc>>,
+    pub cnpj_cpf: Option<String>,
+    pub modelo: Option<String>,
+    pub serie: Option<String>,
+    pub numero: Option<String>,
+    pub tipo_emissao: Option<String>,
+    pub codigo_numerico: Option<String>,
+}
+
+impl CabecalhoChave {
+    pub fn from_nota(nota: &NotaFiscal) -> Self {
+        CabecalhoChave {
+            modelo: Some(nota.modelo.codigo().to_string()),
+            ..Self::montar(
+                &nota.identificacao,
+                nota.data_emissao,
+                &nota.emitente,
+                &nota.serie,
+                &nota.numero,
+            )
+        }
     }
 
-    fn ncm_requires_ipi(ncm: &str) -> bool {
-        // Alguns NCMs que tipicamente têm IPI
-        ncm.starts_with("8433") || ncm.starts_with("8704")
+    pub fn from_conhecimento(cte: &ConhecimentoTransporte) -> Self {
+        CabecalhoChave {
+            modelo: Some(cte.modelo.codigo().to_string()),
+            ..Self::montar(
+                &cte.identificacao,
+                cte.data_emissao,
+                &cte.emitente,
+                &cte.serie,
+                &cte.numero,
+            )
+        }
     }
 
-    fn validate_key_digit(key: &str) -> bool {
-        if key.len() != 44 {
-            return false;
+    pub fn from_manifesto(mdfe: &ManifestoDocumentos) -> Self {
+        CabecalhoChave {
+            modelo: Some("58".to_string()),
+            ..Self::montar(
+                &mdfe.identificacao,
+                mdfe.data_emissao,
+                &mdfe.emitente,
+                &mdfe.serie,
+                &mdfe.numero,
+            )
         }
+    }
 
-        // Implementação do módulo 11 (simplificada)
-        // Em produção: implementar algoritmo completo
-        key.chars().all(char::is_numeric)
+    fn montar(
+        identificacao: &IdentificacaoEmissao,
+        data_emissao: DateTime<Utc>,
+        emitente: &Participante,
+        serie: &str,
+        numero: &str,
+    ) -> Self {
+        // Campos vazios não foram informados no XML
+        let informado = |valor: &str| Some(valor.to_string()).filter(|v| !v.is_empty());
+
+        CabecalhoChave {
+            uf: informado(&identificacao.codigo_uf),
+            data_emissao: Some(data_emissao),
+            // CPF entra na chave precedido de zeros, como no CNPJ
+            cnpj_cpf: informado(&emitente.cnpj_cpf),
+            modelo: None,
+            serie: informado(serie),
+            numero: informado(numero),
+            tipo_emissao: informado(&identificacao.tipo_emissao),
+            codigo_numerico: informado(&identificacao.codigo_numerico),
+        }
     }
 }
 
@@ -307,6 +830,23 @@ pub struct CfopStat {
     pub percentual: f64,
 }
 
+impl CfopStat {
+    /// Monta a estatística com a descrição da tabela oficial e o percentual sobre `valor_periodo`
+    pub fn new(cfop: &str, quantidade: u32, valor_total: f64, valor_periodo: f64) -> Self {
+        CfopStat {
+            cfop: cfop.to_string(),
+            descricao: Cfop::descricao(cfop).unwrap_or_default().to_string(),
+            quantidade,
+            valor_total,
+            percentual: if valor_periodo > 0.0 {
+                valor_total / valor_periodo * 100.0
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NcmStat {
     pub ncm: String,
@@ -315,52 +855,38 @@ pub struct NcmStat {
     pub valor_total: f64,
 }
 
-/// Extrai a chave de acesso do XML
-impl FiscalValidator {
-    fn extract_chave_acesso(xml_content: &str) -> Option<String> {
-        // Procura por padrões de chave de acesso em tags comuns
-        let patterns = [
-            r"<chNFe>([0-9]{44})</chNFe>",
-            r"<chCTe>([0-9]{44})</chCTe>",
-            r#"chave="([0-9]{44})""#,
-            r#"Id="NFe([0-9]{44})""#,
-            r#"Id="CTe([0-9]{44})""#,
-        ];
-
-        for pattern in &patterns {
-            // Busca manual simples sem regex
-            if let Some(start) = xml_content.find(&pattern.replace("([0-9]{44})", "")) {
-                let search_area = &xml_content[start..];
-                for i in 0..(search_area.len() - 44) {
-                    let potential_key = &search_area[i..i + 44];
-                    if potential_key.chars().all(|c| c.is_ascii_digit()) && potential_key.len() == 44 {
-                        return Some(potential_key.to_string());
-                    }
-                }
-            }
-        }
-        
-        // Busca simples por sequências de 44 dígitos
-        for i in 0..(xml_content.len() - 44) {
-            let potential_key = &xml_content[i..i + 44];
-            if potential_key.chars().all(|c| c.is_ascii_digit()) && potential_key.len() == 44 {
-                return Some(potential_key.to_string());
-            }
+impl NcmStat {
+    /// Monta a estatística com a descrição da NCM/TIPI
+    pub fn new(ncm: &str, quantidade: u32, valor_total: f64) -> Self {
+        NcmStat {
+            ncm: ncm.to_string(),
+            descricao: TabelaNcm::padrao()
+                .descricao(ncm)
+                .unwrap_or_default()
+                .to_string(),
+            quantidade,
+            valor_total,
         }
-        
-        None
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{IcmsItem, IpiItem};
+    use crate::parsers::{cte::CTeParser, nfe::NFeParser, FiscalDocumentParser};
 
     #[test]
     fn test_validate_cfop() {
-        assert!(FiscalValidator::is_valid_cfop("5102"));
-        assert!(FiscalValidator::is_valid_cfop("6102"));
-        assert!(!FiscalValidator::is_valid_cfop("9999"));
+        assert!(Cfop::buscar("5102").is_some());
+        assert!(Cfop::buscar("6102").is_some());
+        assert!(Cfop::buscar("9999").is_none());
+
+        let stat = CfopStat::new("5405", 3, 250.0, 1000.0);
+        assert!(stat
+            .descricao
+            .ends_with("na condição de contribuinte substituído"));
+        assert_eq!(stat.percentual, 25.0);
     }
 
     #[test]
@@ -368,5 +894,278 @@ mod tests {
         let valid_key = "35240911223344000156550010000123451234567890";
         assert_eq!(valid_key.len(), 44);
         assert!(FiscalValidator::validate_key_digit(valid_key));
+        assert!(!FiscalValidator::validate_key_digit(
+            "35240911223344000156550010000123451234567891"
+        ));
+    }
+
+    fn nota_exemplo() -> NotaFiscal {
+        NFeParser::new()
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_validate_samples() {
+        let nfe = FiscalValidator::validate_nota_fiscal(&nota_exemplo());
+        assert!(nfe.is_valid, "{:?}", nfe.errors);
+        assert!(nfe.warnings.is_empty(), "{:?}", nfe.warnings);
+        assert_eq!(nfe.document_type, "NFe");
+
+        let cte = CTeParser::new()
+            .parse_string(include_str!("../../test_data/sample_cte.xml"))
+            .unwrap();
+        let cte = FiscalValidator::validate_conhecimento(&cte);
+        assert!(cte.is_valid, "{:?}", cte.errors);
+    }
+
+    #[test]
+    fn test_validate_itens() {
+        let mut nota = nota_exemplo();
+        nota.presencial = false;
+        nota.destinatario.endereco.uf = "RJ".to_string();
+        nota.destinatario.inscricao_estadual = Some("99.999.99-3".to_string());
+        let mut item = nota.itens[0].clone();
+        item.numero_item = 2;
+        item.cfop = "6102".to_string();
+        item.ncm = "8433190".to_string();
+        item.impostos.icms = Some(IcmsItem {
+            cst: Some("00".to_string()),
+            origem: "0".to_string(),
+            base_calculo: 1000.0,
+            aliquota: 18.0,
+            valor: 170.0,
+            ..Default::default()
+        });
+        nota.itens.push(item);
+        let mut item = nota.itens[0].clone();
+        item.numero_item = 3;
+        item.cfop = "9102".to_string();
+        item.ncm = "24022000".to_string();
+        nota.itens.push(item);
+        let mut item = nota.itens[0].clone();
+        item.numero_item = 4;
+        item.cfop = "1102".to_string();
+        nota.itens.push(item);
+
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        let erros: Vec<_> = result
+            .errors
+            .iter()
+            .map(|e| (e.code.as_str(), e.field.as_str()))
+            .collect();
+        assert_eq!(
+            erros,
+            vec![
+                ("NCM_INVALID_FORMAT", "det[2].NCM"),
+                ("ICMS_CALC_ERROR", "det[2].ICMS.vICMS"),
+                ("CFOP_INVALID", "det[3].CFOP"),
+                ("CFOP_DIRECTION", "det[4].CFOP"),
+                ("TOTAL_SUM_MISMATCH", "ICMSTot.vBC"),
+                ("TOTAL_SUM_MISMATCH", "ICMSTot.vICMS"),
+                ("TOTAL_SUM_MISMATCH", "ICMSTot.vProd"),
+            ]
+        );
+        let avisos: Vec<_> = result
+            .warnings
+            .iter()
+            .map(|w| (w.code.as_str(), w.field.as_str()))
+            .collect();
+        assert_eq!(
+            avisos,
+            vec![
+                ("CFOP_CHECK_UF", "det[1].CFOP"),
+                ("ICMS_INTERSTATE_RATE", "det[1].ICMS.pICMS"),
+                ("ICMS_INTERSTATE_RATE", "det[2].ICMS.pICMS"),
+                ("NCM_REQUIRES_IPI", "det[3].NCM"),
+                ("ICMS_INTERSTATE_RATE", "det[3].ICMS.pICMS"),
+                ("CFOP_CHECK_UF", "det[4].CFOP"),
+                ("ICMS_INTERSTATE_RATE", "det[4].ICMS.pICMS"),
+            ]
+        );
+        assert!(!result.is_valid);
+    }
+
+    #[test]
+    fn test_validate_cfop_presencial() {
+        // Consumidor de outra UF comprando no balcão: CFOP 5.xxx está correto
+        let mut nota = nota_exemplo();
+        nota.destinatario.endereco.uf = "RJ".to_string();
+        nota.destinatario.inscricao_estadual = None;
+        assert!(nota.presencial);
+        let uf = |nota: &NotaFiscal| {
+            FiscalValidator::validate_nota_fiscal(nota)
+                .warnings
+                .iter()
+                .any(|w| w.code == "CFOP_CHECK_UF")
+        };
+        assert!(!uf(&nota));
+
+        nota.presencial = false;
+        assert!(uf(&nota));
+        nota.modelo = ModeloNotaFiscal::NFCe;
+        assert!(!uf(&nota));
+    }
+
+    #[test]
+    fn test_validate_ncm_tipi() {
+        let tabela = TabelaNcm::carregar(
+            "# versao: teste\n# completa: sim\n\
+             8517.12.31;Telefones celulares portáteis;15;;2022-03-31\n\
+             8517.13.00;Smartphones;15;2022-04-01;\n",
+        )
+        .unwrap();
+        let emissao = "2023-05-10T12:00:00Z".parse().unwrap();
+        let mut item = nota_exemplo().itens.remove(0);
+        let mut validar = |ncm: &str, ipi: Option<IpiItem>| {
+            item.ncm = ncm.to_string();
+            item.impostos.ipi = ipi;
+            let mut result = ValidationResult::new("", &DocumentType::NotaFiscal);
+            FiscalValidator::validate_ncm("det[1]", &item, emissao, &tabela, &mut result);
+            let mut codigos: Vec<_> = result.errors.iter().map(|e| e.code.clone()).collect();
+            codigos.extend(result.warnings.iter().map(|w| w.code.clone()));
+            codigos
+        };
+
+        assert_eq!(validar("12345678", None), vec!["NCM_NOT_FOUND"]);
+        assert_eq!(validar("85171231", None), vec!["NCM_EXPIRED"]);
+        assert_eq!(validar("85171300", None), vec!["NCM_REQUIRES_IPI"]);
+        let ipi = |aliquota| {
+            Some(IpiItem {
+                cst: "50".to_string(),
+                aliquota,
+                ..Default::default()
+            })
+        };
+        assert_eq!(validar("85171300", ipi(10.0)), vec!["IPI_RATE_MISMATCH"]);
+        assert!(validar("85171300", ipi(15.0)).is_empty());
+        assert!(validar("00", None).is_empty());
+
+        assert_eq!(
+            NcmStat::new("24022000", 1, 10.0).descricao,
+            "Cigarros que contenham tabaco"
+        );
+    }
+
+    #[test]
+    fn test_validate_dates() {
+        let mut nota = nota_exemplo();
+        let recebimento = nota
+            .protocolo
+            .as_ref()
+            .and_then(|p| p.data_recebimento)
+            .unwrap();
+        nota.data_emissao = recebimento - Duration::days(7);
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        assert_eq!(result.warnings[0].code, "DATE_RETROACTIVE");
+
+        nota.data_emissao = recebimento + Duration::hours(1);
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        assert!(result.errors.iter().any(|e| e.code == "DATE_AFTER_RECEIPT"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validate_key_header_mismatch() {
+        // Chave de NF-e série 1, número 12345, emitida em 01/2021 pelo CNPJ 12345678000190
+        let mut nota = nota_exemplo();
+        nota.identificacao.codigo_uf = "33".to_string();
+        nota.serie = "2".to_string();
+        nota.emitente.cnpj_cpf = "98765432000110".to_string();
+        nota.data_emissao = "2021-02-15T13:30:00Z".parse().unwrap();
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        let mut codigos: Vec<_> = result
+            .errors
+            .iter()
+            .map(|e| e.code.as_str())
+            .filter(|c| c.starts_with("KEY_"))
+            .collect();
+        codigos.sort();
+        assert_eq!(
+            codigos,
+            vec![
+                "KEY_MISMATCH_CNPJ",
+                "KEY_MISMATCH_DATA",
+                "KEY_MISMATCH_SERIE",
+                "KEY_MISMATCH_UF"
+            ]
+        );
+
+        // Emitida às 22h de 31/01 em Brasília, já fevereiro em UTC
+        let mut nota = nota_exemplo();
+        nota.data_emissao = "2021-02-01T01:00:00Z".parse().unwrap();
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        assert!(!result.errors.iter().any(|e| e.code.starts_with("KEY_")));
+    }
+
+    #[test]
+    fn test_validate_key_digit_error() {
+        let mut nota = nota_exemplo();
+        nota.chave_acesso = "35240911223344000156550010000123451234567891".to_string();
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        let erro = result
+            .errors
+            .iter()
+            .find(|e| e.code == "KEY_INVALID_DIGIT")
+            .unwrap();
+        assert!(erro.message.contains("calculado: 0"));
+        assert!(!result.is_valid);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_validate_document_xml() {
+        let xml = include_str!("../../test_data/sample_nfe.xml");
+        let nota = NFeParser::new().parse_string(xml).unwrap();
+        let result = FiscalValidator::validate_document(xml, "NFe");
+        let esperado = FiscalValidator::validate_nota_fiscal(&nota);
+        assert_eq!(result.chave_acesso, nota.chave_acesso);
+        assert_eq!(result.errors.len(), esperado.errors.len());
+
+        let result = FiscalValidator::validate_document(xml, "XYZ");
+        assert_eq!(result.errors[0].code, "DOC_TYPE_INVALID");
+        assert_eq!(result.chave_acesso, nota.chave_acesso);
+        assert!(!result.is_valid);
+
+        let result = FiscalValidator::validate_document("<NFe></NFe>", "NFe");
+        assert_eq!(result.errors[0].code, "XML_PARSE_ERROR");
+    }
+
+    #[test]
+    fn test_validate_key_digit_error_parseado() {
+        // O parser aceita a chave com DV errado para que a validação o registre
+        let xml = include_str!("../../test_data/sample_nfe.xml").replace(
+            "Id=\"NFe35210112345678000190550010000123451123456788",
+            "Id=\"NFe35210112345678000190550010000123451123456781",
+        );
+        let nota = NFeParser::new().parse_string(&xml).unwrap();
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        assert!(result.errors.iter().any(|e| e.code == "KEY_INVALID_DIGIT"));
+    }
+
+    #[test]
+    fn test_validate_inscricoes() {
+        let mut nota = nota_exemplo();
+        nota.destinatario.inscricao_estadual = Some("ISENTO".to_string());
+        assert!(FiscalValidator::validate_nota_fiscal(&nota).is_valid);
+
+        nota.destinatario.inscricao_estadual = Some("987.654.326.105".to_string());
+        let result = FiscalValidator::validate_nota_fiscal(&nota);
+        let erros: Vec<_> = result
+            .errors
+            .iter()
+            .map(|e| (e.code.as_str(), e.field.as_str()))
+            .collect();
+        assert_eq!(erros, vec![("IE_INVALID", "dest.IE")]);
+
+        let mut cte = CTeParser::new()
+            .parse_string(include_str!("../../test_data/sample_cte.xml"))
+            .unwrap();
+        cte.remetente.inscricao_estadual = Some("123456785909".to_string());
+        cte.remetente.endereco.uf = "RJ".to_string();
+        let result = FiscalValidator::validate_conhecimento(&cte);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].field, "rem.IE");
+        assert!(result.errors[0].message.contains("inválida para RJ"));
     }
 }
@@ -0,0 +1,284 @@
+use crate::models::{IcmsItem, ItemNota};
+
+/// UFs do Sul e Sudeste, exceto Espírito Santo (Resolução do Senado nº 22/1989)
+const UFS_SUL_SUDESTE: [&str; 6] = ["MG", "PR", "RJ", "RS", "SC", "SP"];
+
+/// Campo do grupo ICMS cujo valor informado difere do recalculado
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergencia {
+    /// Tag do XML (ex.: "vBC", "vICMSST")
+    pub campo: &'static str,
+    pub informado: f64,
+    pub esperado: f64,
+}
+
+impl Divergencia {
+    /// Código usado no `ValidationError`
+    pub fn codigo(&self) -> &'static str {
+        match self.campo {
+            "vBC" => "ICMS_BC_ERROR",
+            "vBCST" => "ICMS_ST_BC_ERROR",
+            "vICMSST" => "ICMS_ST_CALC_ERROR",
+            "vFCP" => "FCP_CALC_ERROR",
+            "vFCPST" => "FCP_ST_CALC_ERROR",
+            _ => "ICMS_CALC_ERROR",
+        }
+    }
+}
+
+/// Recalcula base e valores de ICMS, ICMS-ST e FCP do item conforme CST/CSOSN, `modBC` e `modBCST`.
+/// Bases só são refeitas quando a modalidade é valor da operação (`modBC` 3) ou MVA (`modBCST` 4);
+/// nas demais (pauta, preço tabelado) confere-se apenas o valor sobre a base informada.
+/// `tolerancia` é a diferença máxima aceita (ver `totais::tolerancia_padrao`).
+pub fn recalcular(item: &ItemNota, tolerancia: f64) -> Vec<Divergencia> {
+    let Some(icms) = &item.impostos.icms else {
+        return Vec::new();
+    };
+    let situacao = icms
+        .cst
+        .as_deref()
+        .or(icms.csosn.as_deref())
+        .unwrap_or_default();
+    let valor_operacao =
+        item.valor_total + item.valor_frete + item.valor_seguro + item.valor_outros
+            - item.valor_desconto;
+    // O IPI integra a base quando o destinatário não o aproveita como crédito
+    let valor_ipi = item.impostos.ipi.as_ref().map_or(0.0, |ipi| ipi.valor);
+    let mut divergencias = Vec::new();
+    let mut conferir = |campo, informado: f64, candidatos: &[f64]| {
+        if !candidatos
+            .iter()
+            .any(|esperado| (informado - esperado).abs() <= tolerancia)
+        {
+            divergencias.push(Divergencia {
+                campo,
+                informado,
+                esperado: candidatos[0],
+            });
+        }
+    };
+
+    // ICMS próprio
+    if matches!(situacao, "00" | "10" | "20" | "51" | "70")
+        && icms.modalidade_bc.as_deref() == Some("3")
+    {
+        let reducao = 1.0 - icms.percentual_reducao_bc / 100.0;
+        conferir(
+            "vBC",
+            icms.base_calculo,
+            &[
+                arredondar(valor_operacao * reducao),
+                arredondar((valor_operacao + valor_ipi) * reducao),
+            ],
+        );
+    }
+    match situacao {
+        "40" | "41" | "50" => conferir("vICMS", icms.valor, &[0.0]),
+        "51" => {
+            let operacao = percentual(icms.base_calculo, icms.aliquota);
+            let diferido = arredondar(operacao * icms.percentual_diferimento / 100.0);
+            if icms.valor_icms_operacao > 0.0 {
+                conferir("vICMSOp", icms.valor_icms_operacao, &[operacao]);
+            }
+            if icms.percentual_diferimento > 0.0 {
+                conferir("vICMSDif", icms.valor_icms_diferido, &[diferido]);
+            }
+            conferir("vICMS", icms.valor, &[arredondar(operacao - diferido)]);
+        }
+        _ if icms.base_calculo > 0.0 && icms.aliquota > 0.0 => {
+            conferir(
+                "vICMS",
+                icms.valor,
+                &[percentual(icms.base_calculo, icms.aliquota)],
+            );
+        }
+        _ => {}
+    }
+
+    // FCP sobre a base própria, salvo quando informada base específica
+    if icms.aliquota_fcp > 0.0 {
+        let base = if icms.base_calculo_fcp > 0.0 {
+            icms.base_calculo_fcp
+        } else {
+            icms.base_calculo
+        };
+        conferir(
+            "vFCP",
+            icms.valor_fcp,
+            &[percentual(base, icms.aliquota_fcp)],
+        );
+    }
+
+    // Substituição tributária
+    if matches!(
+        situacao,
+        "10" | "30" | "70" | "90" | "201" | "202" | "203" | "900"
+    ) && icms.aliquota_st > 0.0
+    {
+        recalcular_st(icms, situacao, valor_operacao, valor_ipi, &mut conferir);
+    }
+
+    divergencias
+}
+
+fn recalcular_st(
+    icms: &IcmsItem,
+    situacao: &str,
+    valor_operacao: f64,
+    valor_ipi: f64,
+    conferir: &mut impl FnMut(&'static str, f64, &[f64]),
+) {
+    if icms.modalidade_bc_st.as_deref() == Some("4") {
+        let base = (valor_operacao + valor_ipi)
+            * (1.0 + icms.percentual_mva_st / 100.0)
+            * (1.0 - icms.percentual_reducao_bc_st / 100.0);
+        conferir("vBCST", icms.base_calculo_st, &[arredondar(base)]);
+    }
+
+    // No CST 30 e no Simples Nacional o ICMS próprio a deduzir não vem no grupo
+    if matches!(situacao, "10" | "70" | "90") {
+        let bruto = percentual(icms.base_calculo_st, icms.aliquota_st);
+        conferir(
+            "vICMSST",
+            icms.valor_st,
+            &[arredondar((bruto - icms.valor).max(0.0))],
+        );
+    }
+
+    if icms.aliquota_fcp_st > 0.0 {
+        let base = if icms.base_calculo_fcp_st > 0.0 {
+            icms.base_calculo_fcp_st
+        } else {
+            icms.base_calculo_st
+        };
+        let bruto = percentual(base, icms.aliquota_fcp_st);
+        // Há UFs que deduzem o FCP próprio e UFs que não deduzem
+        conferir(
+            "vFCPST",
+            icms.valor_fcp_st,
+            &[bruto, arredondar((bruto - icms.valor_fcp).max(0.0))],
+        );
+    }
+}
+
+/// Alíquota interestadual de ICMS (Resoluções do Senado nº 22/1989 e nº 13/2012)
+///
+/// 4% para importados (origem 1, 2, 3 e 8); 7% do Sul/Sudeste (exceto ES) para Norte, Nordeste,
+/// Centro-Oeste e ES; 12% nos demais casos.
+pub fn aliquota_interestadual(uf_origem: &str, uf_destino: &str, origem_mercadoria: &str) -> f64 {
+    if matches!(origem_mercadoria, "1" | "2" | "3" | "8") {
+        4.0
+    } else if UFS_SUL_SUDESTE.contains(&uf_origem) && !UFS_SUL_SUDESTE.contains(&uf_destino) {
+        7.0
+    } else {
+        12.0
+    }
+}
+
+fn percentual(base: f64, aliquota: f64) -> f64 {
+    arredondar(base * aliquota / 100.0)
+}
+
+fn arredondar(valor: f64) -> f64 {
+    (valor * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::IpiItem;
+    use crate::validators::totais::TOLERANCIA_PADRAO;
+
+    fn item(icms: IcmsItem) -> ItemNota {
+        let mut item = ItemNota {
+            valor_total: 1000.0,
+            valor_frete: 50.0,
+            valor_desconto: 50.0,
+            ..Default::default()
+        };
+        item.impostos.icms = Some(icms);
+        item
+    }
+
+    #[test]
+    fn test_recalcular_icms_proprio() {
+        // CST 20: base reduzida em 10%, informada sem a redução
+        let icms = IcmsItem {
+            cst: Some("20".to_string()),
+            modalidade_bc: Some("3".to_string()),
+            percentual_reducao_bc: 10.0,
+            base_calculo: 1000.0,
+            aliquota: 18.0,
+            valor: 180.0,
+            aliquota_fcp: 2.0,
+            valor_fcp: 20.0,
+            ..Default::default()
+        };
+        let divergencias = recalcular(&item(icms), TOLERANCIA_PADRAO);
+        assert_eq!(
+            divergencias,
+            vec![Divergencia {
+                campo: "vBC",
+                informado: 1000.0,
+                esperado: 900.0
+            }]
+        );
+        assert_eq!(divergencias[0].codigo(), "ICMS_BC_ERROR");
+
+        // CST 51: 1000 × 18% = 180, com 1/3 diferido
+        let icms = IcmsItem {
+            cst: Some("51".to_string()),
+            base_calculo: 1000.0,
+            aliquota: 18.0,
+            valor_icms_operacao: 180.0,
+            percentual_diferimento: 33.33,
+            valor_icms_diferido: 59.99,
+            valor: 130.0,
+            ..Default::default()
+        };
+        let divergencias = recalcular(&item(icms), TOLERANCIA_PADRAO);
+        assert_eq!(divergencias.len(), 1);
+        assert_eq!(
+            (divergencias[0].campo, divergencias[0].esperado),
+            ("vICMS", 120.01)
+        );
+    }
+
+    #[test]
+    fn test_recalcular_st() {
+        // (1000 + 100 de IPI) × 1,40 = 1540; 1540 × 18% − 180 = 97,20
+        let icms = IcmsItem {
+            cst: Some("10".to_string()),
+            modalidade_bc: Some("3".to_string()),
+            base_calculo: 1000.0,
+            aliquota: 18.0,
+            valor: 180.0,
+            modalidade_bc_st: Some("4".to_string()),
+            percentual_mva_st: 40.0,
+            base_calculo_st: 1540.0,
+            aliquota_st: 18.0,
+            valor_st: 97.2,
+            aliquota_fcp_st: 2.0,
+            valor_fcp_st: 30.0,
+            ..Default::default()
+        };
+        let mut item = item(icms);
+        item.impostos.ipi = Some(IpiItem {
+            valor: 100.0,
+            ..Default::default()
+        });
+        let divergencias = recalcular(&item, TOLERANCIA_PADRAO);
+        assert_eq!(divergencias.len(), 1);
+        assert_eq!(divergencias[0].codigo(), "FCP_ST_CALC_ERROR");
+        assert_eq!(divergencias[0].esperado, 30.8);
+    }
+
+    #[test]
+    fn test_aliquota_interestadual() {
+        assert_eq!(aliquota_interestadual("SP", "BA", "0"), 7.0);
+        assert_eq!(aliquota_interestadual("SP", "ES", "0"), 7.0);
+        assert_eq!(aliquota_interestadual("SP", "RJ", "0"), 12.0);
+        assert_eq!(aliquota_interestadual("BA", "SP", "0"), 12.0);
+        assert_eq!(aliquota_interestadual("SP", "BA", "1"), 4.0);
+    }
+}
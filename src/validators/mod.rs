@@ -1,3 +1,9 @@
+pub mod cfop;
 pub mod fiscal;
+pub mod icms;
+pub mod ncm;
+pub mod totais;
 
+pub use cfop::Cfop;
+pub use ncm::TabelaNcm;
 pub use fiscal::{FiscalValidator, ValidationError, ValidationResult, ValidationWarning};
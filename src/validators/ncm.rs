@@ -0,0 +1,203 @@
+use crate::error::{GeolocationError, Result};
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use std::path::Path;
+
+/// Extrato da TIPI embutido no binário
+const TIPI_EMBUTIDA: &str = include_str!("tipi.csv");
+
+lazy_static! {
+    static ref TABELA_PADRAO: TabelaNcm = TabelaNcm::carregar_padrao();
+}
+
+/// Linha da NCM/TIPI
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntradaNcm {
+    /// Código de 8 dígitos, sem pontos
+    pub codigo: String,
+    pub descricao: String,
+    /// Alíquota de IPI em percentual; `None` quando o produto é NT (não tributado)
+    pub aliquota_ipi: Option<f64>,
+    pub inicio_vigencia: Option<NaiveDate>,
+    /// Último dia em que o código pode ser usado
+    pub fim_vigencia: Option<NaiveDate>,
+}
+
+impl EntradaNcm {
+    pub fn vigente_em(&self, data: NaiveDate) -> bool {
+        !matches!(self.inicio_vigencia, Some(inicio) if data < inicio)
+            && !matches!(self.fim_vigencia, Some(fim) if data > fim)
+    }
+}
+
+/// Tabela NCM/TIPI versionada
+///
+/// Formato: `codigo;descricao;ipi;inicio;fim`, com `ipi` numérico ou `NT` e datas AAAA-MM-DD
+/// opcionais. Linhas `# versao: ...` e `# completa: sim|nao` trazem os metadados.
+#[derive(Debug, Clone, Default)]
+pub struct TabelaNcm {
+    pub versao: String,
+    /// Só uma tabela completa permite afirmar que um NCM não existe
+    pub completa: bool,
+    entradas: Vec<EntradaNcm>,
+}
+
+impl TabelaNcm {
+    /// Tabela usada pelo validador: o arquivo de `TIPI_PATH`, se houver, ou o extrato embutido
+    pub fn padrao() -> &'static TabelaNcm {
+        &TABELA_PADRAO
+    }
+
+    /// `TIPI_REQUIRED=true`: servidor e CLI não sobem sem a tabela completa
+    pub fn obrigatoria() -> bool {
+        std::env::var("TIPI_REQUIRED").is_ok_and(|valor| matches!(valor.as_str(), "true" | "1"))
+    }
+
+    /// Exige a tabela completa; com o extrato o validador não aponta `NCM_NOT_FOUND`
+    pub fn exigir_completa(&self) -> Result<&Self> {
+        if self.completa {
+            return Ok(self);
+        }
+        Err(GeolocationError::InvalidReferenceTable(format!(
+            "TIPI {} incompleta ({} NCMs): defina TIPI_PATH com a tabela completa",
+            self.versao,
+            self.len()
+        )))
+    }
+
+    fn carregar_padrao() -> Self {
+        if let Ok(caminho) = std::env::var("TIPI_PATH") {
+            match Self::from_file(&caminho) {
+                Ok(tabela) => return tabela,
+                Err(e) => log::error!(
+                    "Falha ao carregar TIPI de {}: {}; usando extrato embutido",
+                    caminho,
+                    e
+                ),
+            }
+        }
+        Self::carregar(TIPI_EMBUTIDA).expect("TIPI embutida inválida")
+    }
+
+    pub fn from_file(caminho: impl AsRef<Path>) -> Result<Self> {
+        Self::carregar(&std::fs::read_to_string(caminho)?)
+    }
+
+    pub fn carregar(conteudo: &str) -> Result<Self> {
+        let mut tabela = TabelaNcm::default();
+
+        for (indice, linha) in conteudo.lines().enumerate() {
+            let linha = linha.trim();
+            if let Some(comentario) = linha.strip_prefix('#') {
+                match comentario
+                    .split_once(':')
+                    .map(|(k, v)| (k.trim(), v.trim()))
+                {
+                    Some(("versao", versao)) => tabela.versao = versao.to_string(),
+                    Some(("completa", completa)) => tabela.completa = completa == "sim",
+                    _ => {}
+                }
+                continue;
+            }
+            if linha.is_empty() || linha.starts_with("codigo;") {
+                continue;
+            }
+
+            let erro = |motivo: &str| {
+                GeolocationError::InvalidReferenceTable(format!("linha {}: {}", indice + 1, motivo))
+            };
+            let campos: Vec<&str> = linha.split(';').map(str::trim).collect();
+            let [codigo, descricao, ipi, inicio, fim] = campos[..] else {
+                return Err(erro("esperados 5 campos"));
+            };
+            let codigo = codigo.replace('.', "");
+            if codigo.len() != 8 || !codigo.chars().all(|c| c.is_ascii_digit()) {
+                return Err(erro("NCM deve ter 8 dígitos"));
+            }
+            let aliquota_ipi = match ipi {
+                "NT" => None,
+                _ => Some(
+                    ipi.replace(',', ".")
+                        .parse()
+                        .map_err(|_| erro("alíquota de IPI inválida"))?,
+                ),
+            };
+            let data = |valor: &str| {
+                if valor.is_empty() {
+                    return Ok(None);
+                }
+                NaiveDate::parse_from_str(valor, "%Y-%m-%d")
+                    .map(Some)
+                    .map_err(|_| erro("data de vigência inválida"))
+            };
+
+            tabela.entradas.push(EntradaNcm {
+                codigo,
+                descricao: descricao.to_string(),
+                aliquota_ipi,
+                inicio_vigencia: data(inicio)?,
+                fim_vigencia: data(fim)?,
+            });
+        }
+
+        tabela.entradas.sort_by(|a, b| a.codigo.cmp(&b.codigo));
+        Ok(tabela)
+    }
+
+    pub fn buscar(&self, codigo: &str) -> Option<&EntradaNcm> {
+        self.entradas
+            .binary_search_by(|entrada| entrada.codigo.as_str().cmp(codigo))
+            .ok()
+            .map(|i| &self.entradas[i])
+    }
+
+    pub fn descricao(&self, codigo: &str) -> Option<&str> {
+        self.buscar(codigo)
+            .map(|entrada| entrada.descricao.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entradas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entradas.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tipi_embutida() {
+        let tabela = TabelaNcm::carregar(TIPI_EMBUTIDA).unwrap();
+        assert_eq!(tabela.versao, "2022.08");
+        assert!(!tabela.completa);
+        assert!(!tabela.is_empty());
+
+        let cigarro = tabela.buscar("24022000").unwrap();
+        assert_eq!(cigarro.aliquota_ipi, Some(300.0));
+        assert_eq!(tabela.buscar("27161000"), None);
+        assert_eq!(tabela.buscar("27160000").unwrap().aliquota_ipi, None);
+
+        let celular = tabela.buscar("85171231").unwrap();
+        assert!(celular.vigente_em(NaiveDate::from_ymd_opt(2022, 3, 31).unwrap()));
+        assert!(!celular.vigente_em(NaiveDate::from_ymd_opt(2022, 4, 1).unwrap()));
+
+        let erro = tabela.exigir_completa().unwrap_err();
+        assert!(erro.to_string().contains("defina TIPI_PATH"));
+        let completa = TabelaNcm::carregar("# completa: sim\n01022990;Bovinos;NT;;").unwrap();
+        assert!(completa.exigir_completa().is_ok());
+    }
+
+    #[test]
+    fn test_tabela_invalida() {
+        let erro = TabelaNcm::carregar("0102.29.90;Bovinos;X;;").unwrap_err();
+        assert!(erro
+            .to_string()
+            .contains("linha 1: alíquota de IPI inválida"));
+        assert!(TabelaNcm::carregar("0102.29;Bovinos;NT;;").is_err());
+        assert!(TabelaNcm::carregar("01022990;Bovinos;NT").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use crate::models::{IcmsItem, ItemNota, NotaFiscal, Totais};
+use lazy_static::lazy_static;
+
+/// Diferença máxima aceita entre um valor informado e o recalculado (R$ 0,01),
+/// nos totais e no ICMS
+pub const TOLERANCIA_PADRAO: f64 = 0.01;
+
+lazy_static! {
+    static ref TOLERANCIA: f64 = carregar_tolerancia();
+}
+
+/// Tolerância usada pelo validador nos totais e no ICMS: `TOTALS_TOLERANCE`, se definida, ou R$ 0,01
+pub fn tolerancia_padrao() -> f64 {
+    *TOLERANCIA
+}
+
+fn carregar_tolerancia() -> f64 {
+    let Ok(valor) = std::env::var("TOTALS_TOLERANCE") else {
+        return TOLERANCIA_PADRAO;
+    };
+    match valor.replace(',', ".").parse::<f64>() {
+        Ok(tolerancia) if tolerancia >= 0.0 => tolerancia,
+        _ => {
+            log::warn!(
+                "TOTALS_TOLERANCE inválida ({}); usando R$ {:.2}",
+                valor,
+                TOLERANCIA_PADRAO
+            );
+            TOLERANCIA_PADRAO
+        }
+    }
+}
+
+/// Campo do `ICMSTot` cujo valor informado difere do recalculado
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivergenciaTotal {
+    /// Tag do XML (ex.: "vProd", "vNF")
+    pub campo: &'static str,
+    pub informado: f64,
+    pub esperado: f64,
+}
+
+impl DivergenciaTotal {
+    /// Código usado no `ValidationError`
+    pub fn codigo(&self) -> &'static str {
+        match self.campo {
+            "vNF" => "TOTAL_VNF_MISMATCH",
+            _ => "TOTAL_SUM_MISMATCH",
+        }
+    }
+}
+
+/// Tag, valor informado no `ICMSTot` e parcela de cada item
+type Soma = (&'static str, fn(&Totais) -> f64, fn(&ItemNota) -> f64);
+
+/// Campos do `ICMSTot` (e o `vServ` do `ISSQNtot`) que são a soma do campo correspondente dos itens
+const SOMAS: &[Soma] = &[
+    (
+        "vBC",
+        |t| t.base_calculo_icms,
+        |i| icms(i, |c| c.base_calculo),
+    ),
+    ("vICMS", |t| t.valor_icms, |i| icms(i, |c| c.valor)),
+    (
+        "vICMSDeson",
+        |t| t.valor_icms_desonerado,
+        |i| icms(i, |c| c.valor_desonerado),
+    ),
+    ("vFCP", |t| t.valor_fcp, |i| icms(i, |c| c.valor_fcp)),
+    (
+        "vBCST",
+        |t| t.base_calculo_icms_st,
+        |i| icms(i, |c| c.base_calculo_st),
+    ),
+    ("vST", |t| t.valor_icms_st, |i| icms(i, |c| c.valor_st)),
+    (
+        "vFCPST",
+        |t| t.valor_fcp_st,
+        |i| icms(i, |c| c.valor_fcp_st),
+    ),
+    ("vProd", |t| t.valor_produtos, produto),
+    ("vServ", |t| t.valor_servicos, servico),
+    ("vFrete", |t| t.valor_frete, |i| i.valor_frete),
+    ("vSeg", |t| t.valor_seguro, |i| i.valor_seguro),
+    ("vDesc", |t| t.valor_desconto, |i| i.valor_desconto),
+    (
+        "vII",
+        |t| t.valor_ii,
+        |i| i.impostos.ii.as_ref().map_or(0.0, |ii| ii.valor),
+    ),
+    (
+        "vIPI",
+        |t| t.valor_ipi,
+        |i| i.impostos.ipi.as_ref().map_or(0.0, |ipi| ipi.valor),
+    ),
+    (
+        "vIPIDevol",
+        |t| t.valor_ipi_devolvido,
+        |i| i.valor_ipi_devolvido,
+    ),
+    ("vOutro", |t| t.outras_despesas, |i| i.valor_outros),
+];
+
+/// Confere os totais da nota contra a soma dos itens e o `vNF` contra a fórmula da rejeição 610
+///
+/// vNF = vProd − vDesc − vICMSDeson + vST + vFCPST + vFrete + vSeg + vOutro + vII + vIPI + vIPIDevol +
+/// vServ, este do `ISSQNtot` da NF-e conjugada. A dedução do vICMSDeson depende do motivo e, desde a
+/// NT 2023.004, de `indDeduzDeson`, que não é interpretado; por isso aceita-se o vNF com e sem ela.
+pub fn conferir(nota: &NotaFiscal, tolerancia: f64) -> Vec<DivergenciaTotal> {
+    let totais = &nota.totais;
+    let mut divergencias = Vec::new();
+
+    for (campo, informado, item) in SOMAS {
+        let informado = informado(totais);
+        let esperado = arredondar(nota.itens.iter().map(item).sum());
+        if (informado - esperado).abs() > tolerancia {
+            divergencias.push(DivergenciaTotal {
+                campo,
+                informado,
+                esperado,
+            });
+        }
+    }
+
+    let sem_deducao = arredondar(
+        totais.valor_produtos - totais.valor_desconto
+            + totais.valor_icms_st
+            + totais.valor_fcp_st
+            + totais.valor_frete
+            + totais.valor_seguro
+            + totais.outras_despesas
+            + totais.valor_ii
+            + totais.valor_ipi
+            + totais.valor_ipi_devolvido
+            + totais.valor_servicos,
+    );
+    let com_deducao = arredondar(sem_deducao - totais.valor_icms_desonerado);
+    if [com_deducao, sem_deducao]
+        .iter()
+        .all(|esperado| (totais.valor_total - esperado).abs() > tolerancia)
+    {
+        divergencias.push(DivergenciaTotal {
+            campo: "vNF",
+            informado: totais.valor_total,
+            esperado: com_deducao,
+        });
+    }
+
+    divergencias
+}
+
+/// `vProd` do item de mercadoria que compõe o total da nota (`indTot` = 1)
+fn produto(item: &ItemNota) -> f64 {
+    if item.fora_do_total || item.impostos.issqn.is_some() {
+        0.0
+    } else {
+        item.valor_total
+    }
+}
+
+/// `vProd` do item de serviço sujeito ao ISSQN, que vai para o `ISSQNtot`
+fn servico(item: &ItemNota) -> f64 {
+    if item.fora_do_total || item.impostos.issqn.is_none() {
+        0.0
+    } else {
+        item.valor_total
+    }
+}
+
+fn icms(item: &ItemNota, campo: fn(&IcmsItem) -> f64) -> f64 {
+    item.impostos.icms.as_ref().map_or(0.0, campo)
+}
+
+fn arredondar(valor: f64) -> f64 {
+    (valor * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{IpiItem, IssqnItem};
+    use crate::parsers::{nfe::NFeParser, FiscalDocumentParser};
+
+    fn nota() -> NotaFiscal {
+        let mut nota = NFeParser::new()
+            .parse_string(include_str!("../../test_data/sample_nfe.xml"))
+            .unwrap();
+        let mut item = nota.itens[0].clone();
+        item.numero_item = 2;
+        item.valor_total = 500.0;
+        item.valor_desconto = 20.0;
+        item.impostos.icms = Some(IcmsItem {
+            valor_st: 45.5,
+            ..Default::default()
+        });
+        item.impostos.ipi = Some(IpiItem {
+            valor: 50.0,
+            ..Default::default()
+        });
+        nota.itens.push(item);
+        // Item que não compõe o vProd (indTot = 0)
+        let mut item = nota.itens[0].clone();
+        item.numero_item = 3;
+        item.fora_do_total = true;
+        item.impostos.icms = None;
+        nota.itens.push(item);
+
+        nota.totais.base_calculo_icms = 1000.0;
+        nota.totais.valor_icms = 180.0;
+        nota.totais.valor_produtos = 1500.0;
+        nota.totais.valor_desconto = 20.0;
+        nota.totais.valor_icms_st = 45.5;
+        nota.totais.valor_ipi = 50.0;
+        nota.totais.valor_total = 1575.5;
+        nota
+    }
+
+    #[test]
+    fn test_conferir_totais() {
+        let mut nota = nota();
+        assert_eq!(conferir(&nota, TOLERANCIA_PADRAO), vec![]);
+
+        nota.totais.valor_ipi = 55.0;
+        nota.totais.valor_total = 1580.5;
+        let divergencias = conferir(&nota, TOLERANCIA_PADRAO);
+        assert_eq!(
+            divergencias,
+            vec![DivergenciaTotal {
+                campo: "vIPI",
+                informado: 55.0,
+                esperado: 50.0
+            }]
+        );
+        assert_eq!(divergencias[0].codigo(), "TOTAL_SUM_MISMATCH");
+        assert!(conferir(&nota, 5.0).is_empty());
+    }
+
+    #[test]
+    fn test_conferir_vnf() {
+        let mut nota = nota();
+        nota.totais.valor_total = 1620.5;
+        let divergencias = conferir(&nota, TOLERANCIA_PADRAO);
+        assert_eq!(divergencias.len(), 1);
+        assert_eq!(divergencias[0].codigo(), "TOTAL_VNF_MISMATCH");
+        assert_eq!(divergencias[0].esperado, 1575.5);
+
+        // Desoneração: vNF aceito com e sem a dedução
+        nota.itens[0]
+            .impostos
+            .icms
+            .as_mut()
+            .unwrap()
+            .valor_desonerado = 30.0;
+        nota.totais.valor_icms_desonerado = 30.0;
+        for vnf in [1575.5, 1545.5] {
+            nota.totais.valor_total = vnf;
+            assert!(conferir(&nota, TOLERANCIA_PADRAO).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_conferir_vnf_com_servico() {
+        let mut nota = nota();
+        // Item de serviço da NF-e conjugada: vai para o ISSQNtot, não para o vProd
+        let mut item = nota.itens[0].clone();
+        item.numero_item = 4;
+        item.valor_total = 300.0;
+        item.impostos.icms = None;
+        item.impostos.issqn = Some(IssqnItem {
+            base_calculo: 300.0,
+            aliquota: 5.0,
+            valor: 15.0,
+            ..Default::default()
+        });
+        nota.itens.push(item);
+        nota.totais.valor_servicos = 300.0;
+        nota.totais.valor_total = 1875.5;
+        assert_eq!(conferir(&nota, TOLERANCIA_PADRAO), vec![]);
+
+        // vNF sem os serviços
+        nota.totais.valor_total = 1575.5;
+        let divergencias = conferir(&nota, TOLERANCIA_PADRAO);
+        assert_eq!(divergencias.len(), 1);
+        assert_eq!(divergencias[0].campo, "vNF");
+        assert_eq!(divergencias[0].esperado, 1875.5);
+    }
+}
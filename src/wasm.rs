@@ -1,5 +1,6 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
+use crate::models::ChaveAcesso;
 use crate::parsers::{parse_nfe, parse_cte};
 
 #[cfg(feature = "wasm")]
@@ -24,6 +25,13 @@ pub struct WasmParseResult {
     pub error: Option<String>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct WasmKeyResult {
+    pub success: bool,
+    pub chave: Option<ChaveAcesso>,
+    pub error: Option<String>,
+}
+
 /// Parse NF-e XML content and return structured data
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
@@ -126,3 +134,23 @@ pub fn parse_document_wasm(xml_content: &str) -> JsValue {
         serde_wasm_bindgen::to_value(&result).unwrap()
     }
 }
+
+/// Validate the access key check digit (módulo 11) and return its components
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn parse_access_key_wasm(chave: &str) -> JsValue {
+    let result = match ChaveAcesso::parse(chave.trim()) {
+        Ok(chave) => WasmKeyResult {
+            success: true,
+            chave: Some(chave),
+            error: None,
+        },
+        Err(e) => WasmKeyResult {
+            success: false,
+            chave: None,
+            error: Some(e.to_string()),
+        },
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
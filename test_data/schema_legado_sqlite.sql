@@ -0,0 +1,101 @@
+-- Esquema SQLite anterior às colunas de impostos, transporte e protocolo (bancos legados)
+
+CREATE TABLE IF NOT EXISTS notas_fiscais (
+    id TEXT PRIMARY KEY,
+    chave_acesso TEXT UNIQUE NOT NULL,
+    numero TEXT NOT NULL,
+    serie TEXT NOT NULL,
+    data_emissao DATETIME NOT NULL,
+    tipo_nota TEXT NOT NULL,
+    protocolo_autorizacao TEXT,
+    status TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+
+    -- Emitente
+    emit_cnpj_cpf TEXT NOT NULL,
+    emit_razao_social TEXT NOT NULL,
+    emit_nome_fantasia TEXT,
+    emit_logradouro TEXT,
+    emit_numero TEXT,
+    emit_bairro TEXT,
+    emit_municipio TEXT,
+    emit_uf TEXT,
+    emit_cep TEXT,
+
+    -- Destinatário
+    dest_cnpj_cpf TEXT NOT NULL,
+    dest_razao_social TEXT NOT NULL,
+    dest_logradouro TEXT,
+    dest_numero TEXT,
+    dest_bairro TEXT,
+    dest_municipio TEXT,
+    dest_uf TEXT,
+    dest_cep TEXT,
+
+    -- Totais
+    valor_produtos REAL NOT NULL,
+    valor_total REAL NOT NULL,
+    valor_icms REAL,
+    valor_ipi REAL,
+    valor_pis REAL,
+    valor_cofins REAL,
+
+    informacoes_adicionais TEXT
+);
+
+CREATE TABLE IF NOT EXISTS itens_nota_fiscal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    nota_fiscal_id TEXT NOT NULL,
+    numero_item INTEGER NOT NULL,
+    codigo_produto TEXT NOT NULL,
+    descricao TEXT NOT NULL,
+    ncm TEXT NOT NULL,
+    cfop TEXT NOT NULL,
+    unidade_comercial TEXT NOT NULL,
+    quantidade_comercial REAL NOT NULL,
+    valor_unitario REAL NOT NULL,
+    valor_total REAL NOT NULL,
+    ean TEXT,
+    informacoes_adicionais TEXT,
+
+    FOREIGN KEY (nota_fiscal_id) REFERENCES notas_fiscais(id)
+);
+
+CREATE TABLE IF NOT EXISTS conhecimentos_transporte (
+    id TEXT PRIMARY KEY,
+    chave_acesso TEXT UNIQUE NOT NULL,
+    numero TEXT NOT NULL,
+    serie TEXT NOT NULL,
+    data_emissao DATETIME NOT NULL,
+    tipo_servico TEXT NOT NULL,
+    modal TEXT NOT NULL,
+    protocolo_autorizacao TEXT,
+    status TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+
+    -- Emitente
+    emit_cnpj_cpf TEXT NOT NULL,
+    emit_razao_social TEXT NOT NULL,
+    emit_uf TEXT,
+
+    -- Remetente
+    rem_cnpj_cpf TEXT NOT NULL,
+    rem_razao_social TEXT NOT NULL,
+    rem_municipio TEXT,
+    rem_uf TEXT,
+
+    -- Destinatário
+    dest_cnpj_cpf TEXT NOT NULL,
+    dest_razao_social TEXT NOT NULL,
+    dest_municipio TEXT,
+    dest_uf TEXT,
+
+    -- Valores
+    valor_total REAL NOT NULL,
+    valor_receber REAL NOT NULL,
+    valor_carga REAL NOT NULL,
+    produto_predominante TEXT NOT NULL,
+    peso_bruto REAL NOT NULL,
+
+    informacoes_adicionais TEXT
+);